@@ -0,0 +1,55 @@
+import * as anchor from "@coral-xyz/anchor";
+import { IdlAccounts, Program } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Blocktok } from "../target/types/blocktok";
+
+type AnalyticsBucket = IdlAccounts<Blocktok>["analyticsHistory"]["buckets"][number];
+
+/** Analytics deltas recorded during one bucket of a content's history */
+export type AnalyticsHistoryPoint = {
+  /** Unix timestamp the bucket starts at */
+  start: number;
+  views: anchor.BN;
+  likes: anchor.BN;
+  shares: anchor.BN;
+  comments: anchor.BN;
+};
+
+/** Address of the analytics history PDA of `content` */
+export function findAnalyticsHistoryAddress(programId: PublicKey, content: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("analytics_history"), content.toBuffer()],
+    programId
+  )[0];
+}
+
+/**
+ * Orders the ring buffer slots of an analytics history into a time series,
+ * oldest bucket first. Slots that never recorded an update are skipped.
+ */
+export function analyticsTimeSeries(buckets: AnalyticsBucket[]): AnalyticsHistoryPoint[] {
+  return buckets
+    .filter((bucket) => !bucket.start.isZero())
+    .sort((a, b) => a.start.cmp(b.start))
+    .map(({ start, views, likes, shares, comments }) => ({
+      start: start.toNumber(),
+      views,
+      likes,
+      shares,
+      comments,
+    }));
+}
+
+/**
+ * Fetches the analytics history of `content` as a time series, or null when
+ * the content has no history
+ */
+export async function fetchAnalyticsTimeSeries(
+  program: Program<Blocktok>,
+  content: PublicKey
+): Promise<AnalyticsHistoryPoint[] | null> {
+  const history = await program.account.analyticsHistory.fetchNullable(
+    findAnalyticsHistoryAddress(program.programId, content)
+  );
+  return history === null ? null : analyticsTimeSeries(history.buckets);
+}
@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
 use anchor_spl::{
-    token::{Mint, Token, TokenAccount},
-    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+    associated_token::{get_associated_token_address, AssociatedToken},
 };
 use mpl_token_metadata::{
     ID as metadata_program_id,
@@ -10,6 +11,73 @@ use mpl_token_metadata::{
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
+/// Maximum number of royalty recipients a content may split royalties between
+pub const MAX_ROYALTY_RECIPIENTS: usize = 16;
+
+/// Maximum platform fee in basis points (100%)
+pub const MAX_PLATFORM_FEE_BPS: u16 = 10000;
+
+/// Maximum number of category tags on a single content
+pub const MAX_CONTENT_TAGS: usize = 5;
+
+/// Maximum length of a category name, so the name can be used as a seed directly
+pub const MAX_CATEGORY_NAME_LEN: usize = 32;
+
+/// Maximum number of analytics oracles the platform config can designate
+pub const MAX_ANALYTICS_ORACLES: usize = 8;
+
+/// Maximum number of contents a single `batch_update_analytics` can update,
+/// keeping the instruction within the transaction size and compute limits
+pub const MAX_BATCH_ANALYTICS_UPDATES: usize = 8;
+
+/// Number of buckets an analytics history keeps before reusing the oldest:
+/// two days of hourly buckets, or seven weeks of daily ones
+pub const ANALYTICS_HISTORY_BUCKETS: usize = 48;
+
+/// Decimals of the reward token
+pub const REWARD_TOKEN_DECIMALS: u8 = 6;
+
+/// Maximum number of recipients of a single reward distributor, keeping its
+/// claim bitmap at 8 KiB
+pub const MAX_DISTRIBUTOR_RECIPIENTS: u32 = 65536;
+
+/// Watch progress of a fully watched content, in basis points
+pub const MAX_WATCH_PROGRESS_BPS: u16 = 10000;
+
+/// Bits of `PlatformConfig::paused_instructions`, one per pausable instruction
+pub mod pause_flags {
+    pub const INITIALIZE_CONTENT: u64 = 1 << 0;
+    pub const MINT_NFT: u64 = 1 << 1;
+    pub const UPDATE_ANALYTICS: u64 = 1 << 2;
+    pub const SET_ROYALTY_DISTRIBUTION: u64 = 1 << 3;
+    pub const UPDATE_CONTENT_METADATA: u64 = 1 << 4;
+    pub const CLOSE_CONTENT: u64 = 1 << 5;
+    pub const MIGRATE_CONTENT: u64 = 1 << 6;
+    pub const PUBLISH_CONTENT: u64 = 1 << 7;
+    pub const UNLIST_CONTENT: u64 = 1 << 8;
+    pub const ARCHIVE_CONTENT: u64 = 1 << 9;
+    pub const INCREMENT_ANALYTICS: u64 = 1 << 10;
+    pub const INITIALIZE_ANALYTICS_HISTORY: u64 = 1 << 11;
+    pub const RECORD_VIEW: u64 = 1 << 12;
+    pub const COMMIT_ENGAGEMENT_EPOCH: u64 = 1 << 13;
+    pub const RECORD_EPOCH_TOTALS: u64 = 1 << 14;
+    pub const BATCH_UPDATE_ANALYTICS: u64 = 1 << 15;
+    pub const MINT_REWARDS: u64 = 1 << 16;
+    pub const ACCRUE_REWARDS: u64 = 1 << 17;
+    pub const CLAIM_REWARDS: u64 = 1 << 18;
+    pub const CREATE_REWARD_DISTRIBUTOR: u64 = 1 << 19;
+    pub const CLAIM_DISTRIBUTION: u64 = 1 << 20;
+    pub const CLAWBACK_DISTRIBUTION: u64 = 1 << 21;
+    pub const ACCRUE_CREATOR_EARNINGS: u64 = 1 << 22;
+    pub const TIP_CONTENT: u64 = 1 << 23;
+}
+
+/// Hashes a content ID into a fixed-size PDA seed, since Solana caps each
+/// seed at 32 bytes and content IDs may be hex digests or URLs
+pub fn content_id_seed(content_id: &str) -> [u8; 32] {
+    solana_program::hash::hash(content_id.as_bytes()).to_bytes()
+}
+
 // Define a local Creator struct to replace mpl_token_metadata::state::Creator
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct Creator {
@@ -29,7 +97,12 @@ pub mod blocktok {
         description: String,
         content_url: String,
         content_type: String,
+        tags: Vec<String>,
     ) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::INITIALIZE_CONTENT)?;
+        validate_tags(&tags)?;
+        
         let content = &mut ctx.accounts.content;
         let creator = &ctx.accounts.creator;
         
@@ -42,34 +115,291 @@ pub mod blocktok {
         content.created_at = Clock::get()?.unix_timestamp as u64;
         content.analytics = ContentAnalytics::default();
         content.nft_mint = None;
+        content.status = ContentStatus::Draft;
+        content.publish_at = 0;
+        content.analytics_nonce = 0;
         content.bump = ctx.bumps.content;
+        let content_key = content.key();
+        
+        // Add the content to each tagged category; remaining accounts are
+        // (category, category index) pairs in tag order
+        require!(
+            ctx.remaining_accounts.len() == 2 * tags.len(),
+            ErrorCode::AccountNotEnoughKeys
+        );
+        for (tag, tag_accounts) in tags.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            add_content_tag(
+                &ctx.accounts.creator.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                content_key,
+                tag,
+                &tag_accounts[0],
+                &tag_accounts[1],
+            )?;
+        }
+        content.tags = tags;
+        
+        // Record the content at the next index of the creator's catalogue
+        let creator_profile = &mut ctx.accounts.creator_profile;
+        creator_profile.creator = creator.key();
+        creator_profile.bump = ctx.bumps.creator_profile;
+        let index = creator_profile.content_count;
+        creator_profile.content_count = index
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        
+        let content_index = &mut ctx.accounts.content_index;
+        content_index.creator = creator.key();
+        content_index.index = index;
+        content_index.content = content_key;
+        content_index.bump = ctx.bumps.content_index;
         
         msg!("Content initialized successfully");
         Ok(())
     }
 
+    /// Sets the analytics counters, which may only grow, at most once per the
+    /// config's `min_analytics_interval`. With `attestation`, the counters must be
+    /// signed by an analytics oracle in an Ed25519 program instruction right
+    /// before this one, the instructions sysvar is passed as the only remaining
+    /// account, and any authority can relay them
     pub fn update_analytics(
         ctx: Context<UpdateAnalytics>,
         views: u64,
         likes: u64,
         shares: u64,
         comments: u64,
+        watch: WatchMetrics,
+        attestation: Option<AttestationTerms>,
+    ) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::UPDATE_ANALYTICS)?;
+        
+        let content_key = ctx.accounts.content.key();
+        let content = &mut ctx.accounts.content;
+        let now = Clock::get()?.unix_timestamp as u64;
+        
+        match attestation {
+            Some(terms) => {
+                require!(ctx.remaining_accounts.len() == 1, ErrorCode::AccountNotEnoughKeys);
+                let attestation = AnalyticsAttestation {
+                    content: content_key,
+                    views,
+                    likes,
+                    shares,
+                    comments,
+                    watch,
+                    nonce: terms.nonce,
+                    expiry: terms.expiry,
+                };
+                verify_analytics_attestation(&ctx.remaining_accounts[0], &config, &attestation)?;
+                
+                require!(now <= attestation.expiry, ContentError::AttestationExpired);
+                
+                // Each attestation can be applied once, and only after older ones
+                require!(
+                    attestation.nonce > content.analytics_nonce,
+                    ContentError::AttestationReplayed
+                );
+                content.analytics_nonce = attestation.nonce;
+            }
+            None => {
+                // Only an analytics oracle, or the creator if allowed, can update analytics
+                require!(
+                    config.can_report_analytics(ctx.accounts.authority.key(), content.creator),
+                    ContentError::Unauthorized
+                );
+            }
+        }
+        
+        // Only published content collects analytics
+        require!(content.is_published(now), ContentError::ContentNotPublished);
+        config.check_analytics_interval(content.analytics.updated_at, now)?;
+        
+        // Counters may only grow
+        let previous = content.analytics.clone();
+        content.analytics.set(views, likes, shares, comments)?;
+        require!(watch.covers(&content.watch_metrics), ContentError::AnalyticsDecreased);
+        content.watch_metrics = watch;
+        content.analytics.updated_at = now;
+        content.refresh_engagement_score(&config.engagement_weights, now);
+        record_analytics_history(&ctx.accounts.history, now, &previous, &content.analytics)?;
+        
+        msg!("Analytics updated successfully");
+        Ok(())
+    }
+
+    pub fn increment_analytics(
+        ctx: Context<UpdateAnalytics>,
+        views: u64,
+        likes: u64,
+        shares: u64,
+        comments: u64,
+        watch: WatchMetrics,
     ) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::INCREMENT_ANALYTICS)?;
+        
         let content = &mut ctx.accounts.content;
         
-        // Only creator can update analytics
+        // Only an analytics oracle, or the creator if allowed, can update analytics
         require!(
-            content.creator == ctx.accounts.authority.key(),
+            config.can_report_analytics(ctx.accounts.authority.key(), content.creator),
             ContentError::Unauthorized
         );
         
-        content.analytics.views = views;
-        content.analytics.likes = likes;
-        content.analytics.shares = shares;
-        content.analytics.comments = comments;
-        content.analytics.updated_at = Clock::get()?.unix_timestamp as u64;
+        // Only published content collects analytics
+        let now = Clock::get()?.unix_timestamp as u64;
+        require!(content.is_published(now), ContentError::ContentNotPublished);
+        config.check_analytics_interval(content.analytics.updated_at, now)?;
         
-        msg!("Analytics updated successfully");
+        // Apply deltas, so concurrent reporters do not overwrite each other
+        let previous = content.analytics.clone();
+        content.analytics.increment(views, likes, shares, comments)?;
+        content.watch_metrics = content
+            .watch_metrics
+            .checked_add(&watch)
+            .ok_or(ContentError::AnalyticsOverflow)?;
+        content.analytics.updated_at = now;
+        content.refresh_engagement_score(&config.engagement_weights, now);
+        record_analytics_history(&ctx.accounts.history, now, &previous, &content.analytics)?;
+        
+        msg!("Analytics incremented successfully");
+        Ok(())
+    }
+
+    /// Records a view by the signing viewer. The first view creates the
+    /// viewer's receipt and counts towards the content's views; later ones only
+    /// advance the receipt's watch progress.
+    pub fn record_view(ctx: Context<RecordView>, watch_progress_bps: u16) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::RECORD_VIEW)?;
+        
+        require!(
+            watch_progress_bps <= MAX_WATCH_PROGRESS_BPS,
+            ContentError::InvalidWatchProgress
+        );
+        
+        // Only published content can be viewed
+        let content = &mut ctx.accounts.content;
+        let now = Clock::get()?.unix_timestamp as u64;
+        require!(content.is_published(now), ContentError::ContentNotPublished);
+        
+        let receipt = &mut ctx.accounts.view_receipt;
+        if receipt.first_seen_at == 0 {
+            // First view: fill in the receipt and count the view. Views from
+            // receipts do not count as a reported update, so they leave
+            // `updated_at` and its minimum interval alone
+            receipt.content = content.key();
+            receipt.viewer = ctx.accounts.viewer.key();
+            receipt.first_seen_at = now;
+            receipt.watch_progress_bps = watch_progress_bps;
+            receipt.bump = ctx.bumps.view_receipt;
+            
+            let previous = content.analytics.clone();
+            content.analytics.increment(1, 0, 0, 0)?;
+            content.add_completion(watch_progress_bps)?;
+            content.refresh_engagement_score(&config.engagement_weights, now);
+            record_analytics_history(&ctx.accounts.history, now, &previous, &content.analytics)?;
+            
+            // The receipt makes the view verified, so it earns the viewer rewards
+            let view_reward = config.view_reward;
+            if view_reward > 0 {
+                accrue_user_rewards(
+                    &ctx.accounts.viewer.to_account_info(),
+                    &ctx.accounts.system_program.to_account_info(),
+                    ctx.accounts.viewer.key(),
+                    &ctx.accounts.user_rewards.to_account_info(),
+                    view_reward,
+                    now,
+                )?;
+            }
+        } else {
+            // Repeat view: count a replay, and advance the watch progress and
+            // the content's completion with it
+            content.watch_metrics.replays = content
+                .watch_metrics
+                .replays
+                .checked_add(1)
+                .ok_or(ContentError::AnalyticsOverflow)?;
+            if watch_progress_bps > receipt.watch_progress_bps {
+                content.add_completion(watch_progress_bps - receipt.watch_progress_bps)?;
+                receipt.watch_progress_bps = watch_progress_bps;
+            }
+            content.refresh_engagement_score(&config.engagement_weights, now);
+        }
+        
+        msg!("View recorded successfully");
+        Ok(())
+    }
+
+    /// Updates the analytics of many contents at once, as one `update_analytics`
+    /// per content without attestations. `remaining_accounts` holds each
+    /// update's content and analytics history, in order
+    pub fn batch_update_analytics(
+        ctx: Context<BatchUpdateAnalytics>,
+        updates: Vec<ContentAnalyticsUpdate>,
+    ) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::BATCH_UPDATE_ANALYTICS)?;
+        
+        require!(
+            updates.len() <= MAX_BATCH_ANALYTICS_UPDATES,
+            ContentError::TooManyBatchUpdates
+        );
+        require!(
+            ctx.remaining_accounts.len() == 2 * updates.len(),
+            ErrorCode::AccountNotEnoughKeys
+        );
+        
+        let now = Clock::get()?.unix_timestamp as u64;
+        
+        // Any failing update fails the instruction, so no update is applied alone
+        for (update, accounts) in updates.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            let (content_info, history_info) = (&accounts[0], &accounts[1]);
+            require_keys_eq!(content_info.key(), update.content, ContentError::BatchAccountMismatch);
+            
+            // `Account` checks the owner and discriminator, leaving the address
+            let mut content = Account::<Content>::try_from(content_info)?;
+            let content_address = Pubkey::create_program_address(
+                &[
+                    b"content",
+                    content.creator.as_ref(),
+                    content_id_seed(&content.content_id).as_ref(),
+                    &[content.bump],
+                ],
+                &crate::ID,
+            )
+            .map_err(|_| ErrorCode::ConstraintSeeds)?;
+            require_keys_eq!(content_address, content_info.key(), ErrorCode::ConstraintSeeds);
+            let (history_address, _) = Pubkey::find_program_address(
+                &[b"analytics_history", content_info.key.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(history_address, history_info.key(), ErrorCode::ConstraintSeeds);
+            
+            // Only an analytics oracle, or the creator if allowed, can update analytics
+            require!(
+                config.can_report_analytics(ctx.accounts.authority.key(), content.creator),
+                ContentError::Unauthorized
+            );
+            
+            // Only published content collects analytics
+            require!(content.is_published(now), ContentError::ContentNotPublished);
+            config.check_analytics_interval(content.analytics.updated_at, now)?;
+            
+            // Counters may only grow
+            let previous = content.analytics.clone();
+            content.analytics.set(update.views, update.likes, update.shares, update.comments)?;
+            require!(update.watch.covers(&content.watch_metrics), ContentError::AnalyticsDecreased);
+            content.watch_metrics = update.watch;
+            content.analytics.updated_at = now;
+            content.refresh_engagement_score(&config.engagement_weights, now);
+            record_analytics_history(history_info, now, &previous, &content.analytics)?;
+            content.exit(&crate::ID)?;
+        }
+        
+        msg!("Analytics batch updated successfully");
         Ok(())
     }
 
@@ -80,6 +410,9 @@ pub mod blocktok {
         uri: String,
         royalty_basis_points: u16,
     ) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::MINT_NFT)?;
+        
         let content = &mut ctx.accounts.content;
         
         // Only creator can mint NFT
@@ -88,6 +421,12 @@ pub mod blocktok {
             ContentError::Unauthorized
         );
         
+        // Only published content can be minted
+        require!(
+            content.is_published(Clock::get()?.unix_timestamp as u64),
+            ContentError::ContentNotPublished
+        );
+        
         // Content should not already have an NFT
         require!(
             content.nft_mint.is_none(),
@@ -181,6 +520,9 @@ pub mod blocktok {
         ctx: Context<SetRoyaltyDistribution>,
         royalty_percentages: Vec<u16>,
     ) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::SET_ROYALTY_DISTRIBUTION)?;
+        
         let content = &mut ctx.accounts.content;
         
         // Only creator can set royalty distribution
@@ -195,8 +537,13 @@ pub mod blocktok {
             ContentError::InvalidRoyaltyData
         );
         
+        require!(
+            royalty_percentages.len() <= MAX_ROYALTY_RECIPIENTS,
+            ContentError::TooManyRoyaltyRecipients
+        );
+        
         // Validate percentages sum to 100%
-        let total_percentage: u16 = royalty_percentages.iter().sum();
+        let total_percentage: u32 = royalty_percentages.iter().map(|p| *p as u32).sum();
         require!(
             total_percentage == 10000, // 100% in basis points
             ContentError::InvalidRoyaltyPercentage
@@ -214,139 +561,2048 @@ pub mod blocktok {
         // Set royalty distribution
         content.royalty_recipients = recipients;
         
+        // Resize the account to fit the new recipient list
+        let new_space = 8 + content.current_space();
+        resize_account(
+            &ctx.accounts.content.to_account_info(),
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_space,
+        )?;
+        
         msg!("Royalty distribution set successfully");
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-#[instruction(content_id: String)]
-pub struct InitializeContent<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = creator,
-        space = 8 + Content::space(&content_id),
-        seeds = [b"content", creator.key().as_ref(), content_id.as_bytes()],
-        bump
-    )]
-    pub content: Account<'info, Content>,
-    
-    pub system_program: Program<'info, System>,
-}
+    pub fn update_content_metadata(
+        ctx: Context<UpdateContentMetadata>,
+        title: Option<String>,
+        description: Option<String>,
+        content_url: Option<String>,
+        content_type: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::UPDATE_CONTENT_METADATA)?;
+        
+        let content = &mut ctx.accounts.content;
+        
+        // Only creator can update metadata
+        require!(
+            content.creator == ctx.accounts.creator.key(),
+            ContentError::Unauthorized
+        );
+        
+        if let Some(title) = title {
+            content.title = title;
+        }
+        if let Some(description) = description {
+            content.description = description;
+        }
+        if let Some(content_url) = content_url {
+            content.content_url = content_url;
+        }
+        if let Some(content_type) = content_type {
+            content.content_type = content_type;
+        }
+        
+        // Move the content between categories for any changed tags; remaining
+        // accounts are (category, category index) pairs for each removed tag,
+        // then each added tag
+        if let Some(tags) = tags {
+            validate_tags(&tags)?;
+            
+            let content_key = content.key();
+            let removed: Vec<&String> = content.tags.iter().filter(|tag| !tags.contains(tag)).collect();
+            let added: Vec<&String> = tags.iter().filter(|tag| !content.tags.contains(tag)).collect();
+            require!(
+                ctx.remaining_accounts.len() == 2 * (removed.len() + added.len()),
+                ErrorCode::AccountNotEnoughKeys
+            );
+            
+            let mut tag_accounts = ctx.remaining_accounts.chunks(2);
+            for tag in removed {
+                let accounts = tag_accounts.next().unwrap();
+                remove_content_tag(
+                    &ctx.accounts.creator.to_account_info(),
+                    content_key,
+                    tag,
+                    &accounts[0],
+                    &accounts[1],
+                )?;
+            }
+            for tag in added {
+                let accounts = tag_accounts.next().unwrap();
+                add_content_tag(
+                    &ctx.accounts.creator.to_account_info(),
+                    &ctx.accounts.system_program.to_account_info(),
+                    content_key,
+                    tag,
+                    &accounts[0],
+                    &accounts[1],
+                )?;
+            }
+            
+            content.tags = tags;
+        }
+        
+        // Resize the account to fit the new metadata
+        let new_space = 8 + content.current_space();
+        resize_account(
+            &ctx.accounts.content.to_account_info(),
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_space,
+        )?;
+        
+        msg!("Content metadata updated successfully");
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct UpdateAnalytics<'info> {
-    pub authority: Signer<'info>,
-    
-    #[account(mut)]
-    pub content: Account<'info, Content>,
-}
+    pub fn close_content(ctx: Context<CloseContent>) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::CLOSE_CONTENT)?;
+        
+        let content = &ctx.accounts.content;
+        
+        // Only creator can close content
+        require!(
+            content.creator == ctx.accounts.creator.key(),
+            ContentError::Unauthorized
+        );
+        
+        // Content with a minted NFT can only be closed once the NFT is burned
+        if let Some(nft_mint) = content.nft_mint {
+            let mint = ctx
+                .accounts
+                .nft_mint
+                .as_ref()
+                .ok_or(ContentError::NftNotBurned)?;
+            require_keys_eq!(mint.key(), nft_mint, ContentError::NftNotBurned);
+            require!(mint.supply == 0, ContentError::NftNotBurned);
+        }
+        
+        // Close the analytics history along with the content
+        if !ctx.accounts.history.data_is_empty() {
+            let history = Account::<AnalyticsHistory>::try_from(&ctx.accounts.history)?;
+            history.close(ctx.accounts.creator.to_account_info())?;
+        }
+        
+        // Remove the content from each tagged category; remaining accounts are
+        // (category, category index) pairs in tag order
+        require!(
+            ctx.remaining_accounts.len() == 2 * content.tags.len(),
+            ErrorCode::AccountNotEnoughKeys
+        );
+        for (tag, tag_accounts) in content.tags.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            remove_content_tag(
+                &ctx.accounts.creator.to_account_info(),
+                content.key(),
+                tag,
+                &tag_accounts[0],
+                &tag_accounts[1],
+            )?;
+        }
+        
+        // The account itself is zeroed and closed to the creator by the `close` constraint
+        msg!("Content closed successfully");
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct MintNft<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    
-    #[account(
-        mut,
-        seeds = [b"content", creator.key().as_ref(), content.content_id.as_bytes()],
-        bump = content.bump
-    )]
-    pub content: Account<'info, Content>,
-    
-    #[account(
-        init,
-        payer = creator,
-        mint::decimals = 0,
-        mint::authority = creator,
-    )]
-    pub mint: Account<'info, Mint>,
-    
-    #[account(
-        init_if_needed,
-        payer = creator,
-        associated_token::mint = mint,
-        associated_token::authority = creator,
-    )]
-    pub token_account: Account<'info, TokenAccount>,
-    
-    /// CHECK: Metadata account
-    #[account(
-        mut,
-        seeds = [
-            b"metadata",
-            metadata_program_id.to_bytes().as_ref(),
-            mint.key().as_ref()
-        ],
-        bump,
-        seeds::program = metadata_program_id
-    )]
-    pub metadata: UncheckedAccount<'info>,
-    
-    /// CHECK: Master edition account
-    #[account(
-        mut,
-        seeds = [
-            b"metadata",
-            metadata_program_id.to_bytes().as_ref(),
-            mint.key().as_ref(),
-            b"edition"
-        ],
-        bump,
-        seeds::program = metadata_program_id
-    )]
-    pub master_edition: UncheckedAccount<'info>,
-    
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
+    pub fn migrate_content_address(ctx: Context<MigrateContentAddress>) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::MIGRATE_CONTENT)?;
+        
+        let legacy_content = &ctx.accounts.legacy_content;
+        
+        // Only creator can migrate content
+        require!(
+            legacy_content.creator == ctx.accounts.creator.key(),
+            ContentError::Unauthorized
+        );
+        
+        // Copy the content to its hashed-seed address; the legacy account is
+        // closed to the creator by the `close` constraint
+        let mut content = (**legacy_content).clone();
+        content.bump = ctx.bumps.content;
+        ctx.accounts.content.set_inner(content);
+        
+        msg!("Content address migrated successfully");
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct SetRoyaltyDistribution<'info> {
-    pub creator: Signer<'info>,
-    
-    #[account(
-        mut,
-        seeds = [b"content", creator.key().as_ref(), content.content_id.as_bytes()],
-        bump = content.bump
-    )]
-    pub content: Account<'info, Content>,
-}
+    /// Grows content written before `watch_metrics` or `engagement_score`
+    /// existed and sized exactly to its data, so it can be loaded again.
+    /// Content with room to spare already reads them zeroed and is left as is
+    pub fn migrate_content_analytics(ctx: Context<MigrateContentAnalytics>) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::MIGRATE_CONTENT)?;
+        
+        let content_info = ctx.accounts.content.to_account_info();
+        let (legacy_content, legacy_space) = {
+            let data = content_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == <Content as anchor_lang::Discriminator>::DISCRIMINATOR,
+                ErrorCode::AccountDiscriminatorMismatch
+            );
+            let mut fields: &[u8] = &data[8..];
+            let legacy_content = LegacyContent::deserialize(&mut fields)
+                .map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+            (legacy_content, data.len() - fields.len())
+        };
+        
+        // Only creator can migrate content, and only from its PDA
+        require!(
+            legacy_content.creator == ctx.accounts.creator.key(),
+            ContentError::Unauthorized
+        );
+        let content_address = Pubkey::create_program_address(
+            &[
+                b"content",
+                legacy_content.creator.as_ref(),
+                content_id_seed(&legacy_content.content_id).as_ref(),
+                &[legacy_content.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::ConstraintSeeds)?;
+        require_keys_eq!(content_address, content_info.key(), ErrorCode::ConstraintSeeds);
+        
+        let old_space = content_info.data_len();
+        let new_space = legacy_space + WatchMetrics::SPACE + 8;
+        if old_space >= new_space {
+            msg!("Content already has room for its analytics");
+            return Ok(());
+        }
+        
+        // Bytes already past the legacy fields hold watch metrics to keep
+        resize_account(
+            &content_info,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_space,
+        )?;
+        content_info.try_borrow_mut_data()?[old_space..].fill(0);
+        
+        msg!("Content analytics migrated successfully");
+        Ok(())
+    }
 
-#[account]
-pub struct Content {
-    pub creator: Pubkey,
-    pub content_id: String,
-    pub title: String,
-    pub description: String,
-    pub content_url: String,
-    pub content_type: String,
-    pub created_at: u64,
-    pub nft_mint: Option<Pubkey>,
-    pub analytics: ContentAnalytics,
-    pub royalty_recipients: Vec<RoyaltyRecipient>,
-    pub bump: u8,
-}
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        treasury: Pubkey,
+        platform_fee_bps: u16,
+        features: u64,
+    ) -> Result<()> {
+        require!(
+            platform_fee_bps <= MAX_PLATFORM_FEE_BPS,
+            ContentError::InvalidPlatformFee
+        );
+        
+        // The upgrade authority check is done by the account constraints
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.pending_admin = None;
+        config.treasury = treasury;
+        config.platform_fee_bps = platform_fee_bps;
+        config.features = features;
+        config.paused = false;
+        config.paused_instructions = 0;
+        config.analytics_oracles = Vec::new();
+        config.allow_creator_analytics = true;
+        config.min_analytics_interval = 0;
+        config.engagement_weights = EngagementWeights::default();
+        config.view_reward = 0;
+        config.emission_schedule = EmissionSchedule::default();
+        config.emitted_total = 0;
+        config.emission_epoch = 0;
+        config.emitted_in_epoch = 0;
+        config.bump = ctx.bumps.config;
+        
+        msg!("Platform config initialized successfully");
+        Ok(())
+    }
 
-impl Content {
-    pub fn space(content_id: &str) -> usize {
-        // Space calculation
-        // creator: 32 bytes
-        // content_id: 4 + len (String)
-        // title: 4 + len (String)
-        // description: 4 + len (String)
-        // content_url: 4 + len (String)
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        treasury: Option<Pubkey>,
+        platform_fee_bps: Option<u16>,
+        features: Option<u64>,
+        min_analytics_interval: Option<u64>,
+        engagement_weights: Option<EngagementWeights>,
+        view_reward: Option<u64>,
+        emission_schedule: Option<EmissionSchedule>,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        
+        // Only admin can update the config
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ContentError::Unauthorized
+        );
+        
+        if let Some(treasury) = treasury {
+            config.treasury = treasury;
+        }
+        if let Some(platform_fee_bps) = platform_fee_bps {
+            require!(
+                platform_fee_bps <= MAX_PLATFORM_FEE_BPS,
+                ContentError::InvalidPlatformFee
+            );
+            config.platform_fee_bps = platform_fee_bps;
+        }
+        if let Some(features) = features {
+            config.features = features;
+        }
+        if let Some(min_analytics_interval) = min_analytics_interval {
+            config.min_analytics_interval = min_analytics_interval;
+        }
+        // Scores are recomputed with new weights on each content's next analytics change
+        if let Some(engagement_weights) = engagement_weights {
+            config.engagement_weights = engagement_weights;
+        }
+        if let Some(view_reward) = view_reward {
+            config.view_reward = view_reward;
+        }
+        // Tokens emitted so far keep counting against the new cap
+        if let Some(emission_schedule) = emission_schedule {
+            config.emission_schedule = emission_schedule;
+        }
+        
+        msg!("Platform config updated successfully");
+        Ok(())
+    }
+
+    pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        
+        // Only admin can nominate a new admin
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ContentError::Unauthorized
+        );
+        
+        config.pending_admin = Some(new_admin);
+        
+        msg!("Platform admin transfer started");
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        
+        // Only the nominated admin can accept
+        require!(
+            config.pending_admin == Some(ctx.accounts.pending_admin.key()),
+            ContentError::Unauthorized
+        );
+        
+        config.admin = ctx.accounts.pending_admin.key();
+        config.pending_admin = None;
+        
+        msg!("Platform admin transfer completed");
+        Ok(())
+    }
+
+    pub fn set_pause_state(
+        ctx: Context<SetPauseState>,
+        paused: bool,
+        paused_instructions: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        
+        // Only admin can pause or unpause
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ContentError::Unauthorized
+        );
+        
+        config.paused = paused;
+        config.paused_instructions = paused_instructions;
+        
+        msg!("Platform pause state set: paused={}, paused_instructions={:#x}", paused, paused_instructions);
+        Ok(())
+    }
+
+    pub fn initialize_analytics_history(
+        ctx: Context<InitializeAnalyticsHistory>,
+        granularity: HistoryGranularity,
+    ) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::INITIALIZE_ANALYTICS_HISTORY)?;
+        
+        // Only creator can create the analytics history
+        require!(
+            ctx.accounts.content.creator == ctx.accounts.creator.key(),
+            ContentError::Unauthorized
+        );
+        
+        let history = &mut ctx.accounts.history;
+        history.content = ctx.accounts.content.key();
+        history.granularity = granularity;
+        history.buckets = vec![AnalyticsBucket::default(); ANALYTICS_HISTORY_BUCKETS];
+        history.bump = ctx.bumps.history;
+        
+        msg!("Analytics history initialized successfully");
+        Ok(())
+    }
+
+    pub fn set_analytics_oracles(
+        ctx: Context<SetAnalyticsOracles>,
+        oracles: Vec<Pubkey>,
+        allow_creator_analytics: bool,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        
+        // Only admin can designate analytics oracles
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ContentError::Unauthorized
+        );
+        
+        require!(
+            oracles.len() <= MAX_ANALYTICS_ORACLES,
+            ContentError::TooManyAnalyticsOracles
+        );
+        
+        config.analytics_oracles = oracles;
+        config.allow_creator_analytics = allow_creator_analytics;
+        
+        msg!("Analytics oracles set: {} oracles, creator analytics allowed={}", config.analytics_oracles.len(), allow_creator_analytics);
+        Ok(())
+    }
+
+    pub fn create_category(ctx: Context<CreateCategory>, name: String) -> Result<()> {
+        // Only admin can create categories
+        require!(
+            ctx.accounts.config.admin == ctx.accounts.admin.key(),
+            ContentError::Unauthorized
+        );
+        
+        require!(Category::is_valid_name(&name), ContentError::InvalidCategoryName);
+        
+        let category = &mut ctx.accounts.category;
+        category.name = name;
+        category.active = true;
+        category.content_count = 0;
+        category.bump = ctx.bumps.category;
+        
+        msg!("Category created successfully");
+        Ok(())
+    }
+
+    pub fn set_category_active(
+        ctx: Context<SetCategoryActive>,
+        _name: String,
+        active: bool,
+    ) -> Result<()> {
+        // Only admin can activate or deactivate categories
+        require!(
+            ctx.accounts.config.admin == ctx.accounts.admin.key(),
+            ContentError::Unauthorized
+        );
+        
+        ctx.accounts.category.active = active;
+        
+        msg!("Category active state set to {}", active);
+        Ok(())
+    }
+
+    pub fn publish(ctx: Context<UpdateContentStatus>, publish_at: Option<u64>) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::PUBLISH_CONTENT)?;
+        
+        let content = &mut ctx.accounts.content;
+        
+        // Only creator can publish content
+        require!(
+            content.creator == ctx.accounts.creator.key(),
+            ContentError::Unauthorized
+        );
+        
+        // Only content that is not yet live or is unlisted can be published
+        let now = Clock::get()?.unix_timestamp as u64;
+        require!(
+            matches!(
+                content.effective_status(now),
+                ContentStatus::Draft | ContentStatus::Scheduled | ContentStatus::Unlisted
+            ),
+            ContentError::InvalidStatusTransition
+        );
+        
+        // Publish now, or schedule for later
+        match publish_at {
+            Some(publish_at) if publish_at > now => {
+                content.status = ContentStatus::Scheduled;
+                content.publish_at = publish_at;
+            }
+            _ => {
+                content.status = ContentStatus::Published;
+                content.publish_at = now;
+            }
+        }
+        
+        msg!("Content status set to {:?}", content.status);
+        Ok(())
+    }
+
+    pub fn unlist(ctx: Context<UpdateContentStatus>) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::UNLIST_CONTENT)?;
+        
+        let content = &mut ctx.accounts.content;
+        
+        // Only creator can unlist content
+        require!(
+            content.creator == ctx.accounts.creator.key(),
+            ContentError::Unauthorized
+        );
+        
+        // Only published content can be unlisted
+        require!(
+            content.is_published(Clock::get()?.unix_timestamp as u64),
+            ContentError::InvalidStatusTransition
+        );
+        content.status = ContentStatus::Unlisted;
+        
+        msg!("Content unlisted successfully");
+        Ok(())
+    }
+
+    pub fn archive(ctx: Context<UpdateContentStatus>) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::ARCHIVE_CONTENT)?;
+        
+        let content = &mut ctx.accounts.content;
+        
+        // Only creator can archive content
+        require!(
+            content.creator == ctx.accounts.creator.key(),
+            ContentError::Unauthorized
+        );
+        
+        // Archiving is final
+        require!(
+            content.status != ContentStatus::Archived,
+            ContentError::InvalidStatusTransition
+        );
+        content.status = ContentStatus::Archived;
+        
+        msg!("Content archived successfully");
+        Ok(())
+    }
+
+    /// Commits the Merkle root of every engagement event in a window that is
+    /// already over as a new engagement epoch
+    pub fn commit_engagement_epoch(
+        ctx: Context<CommitEngagementEpoch>,
+        epoch: u64,
+        start_time: u64,
+        end_time: u64,
+        merkle_root: [u8; 32],
+        event_count: u64,
+    ) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::COMMIT_ENGAGEMENT_EPOCH)?;
+        
+        // Only analytics oracles can commit engagement
+        require!(
+            config.analytics_oracles.contains(&ctx.accounts.authority.key()),
+            ContentError::Unauthorized
+        );
+        
+        let now = Clock::get()?.unix_timestamp as u64;
+        require!(
+            start_time < end_time && end_time <= now,
+            ContentError::InvalidEpochWindow
+        );
+        
+        let engagement_epoch = &mut ctx.accounts.engagement_epoch;
+        engagement_epoch.authority = ctx.accounts.authority.key();
+        engagement_epoch.epoch = epoch;
+        engagement_epoch.start_time = start_time;
+        engagement_epoch.end_time = end_time;
+        engagement_epoch.merkle_root = merkle_root;
+        engagement_epoch.event_count = event_count;
+        engagement_epoch.committed_at = now;
+        engagement_epoch.bump = ctx.bumps.engagement_epoch;
+        
+        msg!("Engagement epoch {} committed successfully", epoch);
+        Ok(())
+    }
+
+    /// Records a content's engagement totals for an epoch, by the oracle that
+    /// committed it
+    pub fn record_epoch_totals(
+        ctx: Context<RecordEpochTotals>,
+        views: u64,
+        likes: u64,
+        shares: u64,
+        comments: u64,
+    ) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::RECORD_EPOCH_TOTALS)?;
+        
+        require!(
+            ctx.accounts.engagement_epoch.authority == ctx.accounts.authority.key(),
+            ContentError::Unauthorized
+        );
+        
+        let totals = &mut ctx.accounts.totals;
+        totals.epoch = ctx.accounts.engagement_epoch.key();
+        totals.content = ctx.accounts.content.key();
+        totals.views = views;
+        totals.likes = likes;
+        totals.shares = shares;
+        totals.comments = comments;
+        totals.bump = ctx.bumps.totals;
+        
+        msg!("Engagement epoch totals recorded successfully");
+        Ok(())
+    }
+
+    /// Fails unless `event` is in the epoch's window and `proof` leads from
+    /// its leaf to the epoch's Merkle root
+    pub fn verify_engagement(
+        ctx: Context<VerifyEngagement>,
+        event: EngagementEvent,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let engagement_epoch = &ctx.accounts.engagement_epoch;
+        require!(
+            engagement_epoch.start_time <= event.timestamp && event.timestamp < engagement_epoch.end_time,
+            ContentError::EngagementOutsideEpoch
+        );
+        require!(
+            verify_merkle_proof(event.leaf(), &proof, &engagement_epoch.merkle_root),
+            ContentError::InvalidMerkleProof
+        );
+        
+        msg!("Engagement verified in epoch {}", engagement_epoch.epoch);
+        Ok(())
+    }
+
+    /// Creates the reward token mint. Its mint authority is a program PDA, so
+    /// reward tokens can only be minted through the program
+    pub fn initialize_reward_mint(ctx: Context<InitializeRewardMint>) -> Result<()> {
+        // Only admin can create the reward mint
+        require!(
+            ctx.accounts.config.admin == ctx.accounts.admin.key(),
+            ContentError::Unauthorized
+        );
+        
+        msg!("Reward mint initialized successfully");
+        Ok(())
+    }
+
+    /// Mints reward tokens to a token account of the reward mint. Only
+    /// analytics oracles can mint, never creators or viewers
+    pub fn mint_rewards(ctx: Context<MintRewards>, amount: u64) -> Result<()> {
+        ctx.accounts.config.check_not_paused(pause_flags::MINT_REWARDS)?;
+        
+        require!(
+            ctx.accounts.config.analytics_oracles.contains(&ctx.accounts.authority.key()),
+            ContentError::Unauthorized
+        );
+        require!(amount > 0, ContentError::InvalidRewardAmount);
+        
+        mint_reward_tokens(
+            &mut ctx.accounts.config,
+            &ctx.accounts.reward_mint,
+            &ctx.accounts.recipient,
+            &ctx.accounts.reward_mint_authority,
+            &ctx.accounts.token_program,
+            ctx.bumps.reward_mint_authority,
+            amount,
+        )?;
+        
+        msg!("Rewards minted successfully");
+        Ok(())
+    }
+
+    /// Accrues reward credits to a user for engagement reported off-chain.
+    /// Only analytics oracles can accrue credits
+    pub fn accrue_rewards(ctx: Context<AccrueRewards>, amount: u64) -> Result<()> {
+        ctx.accounts.config.check_not_paused(pause_flags::ACCRUE_REWARDS)?;
+        
+        require!(
+            ctx.accounts.config.analytics_oracles.contains(&ctx.accounts.authority.key()),
+            ContentError::Unauthorized
+        );
+        require!(amount > 0, ContentError::InvalidRewardAmount);
+        
+        let user_rewards = &mut ctx.accounts.user_rewards;
+        user_rewards.user = ctx.accounts.user.key();
+        user_rewards.bump = ctx.bumps.user_rewards;
+        user_rewards.accrue(amount, Clock::get()?.unix_timestamp as u64)?;
+        
+        msg!("Rewards accrued successfully");
+        Ok(())
+    }
+
+    /// Mints all of the signing user's unclaimed reward credits to their
+    /// associated token account of the reward mint
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        ctx.accounts.config.check_not_paused(pause_flags::CLAIM_REWARDS)?;
+        
+        let user_rewards = &mut ctx.accounts.user_rewards;
+        let amount = user_rewards.claimable();
+        require!(amount > 0, ContentError::NoRewardsToClaim);
+        user_rewards.claimed = user_rewards.accrued;
+        
+        mint_reward_tokens(
+            &mut ctx.accounts.config,
+            &ctx.accounts.reward_mint,
+            &ctx.accounts.token_account,
+            &ctx.accounts.reward_mint_authority,
+            &ctx.accounts.token_program,
+            ctx.bumps.reward_mint_authority,
+            amount,
+        )?;
+        
+        msg!("Rewards claimed successfully");
+        Ok(())
+    }
+
+    /// Creates the reward distributor of an epoch from the Merkle root of its
+    /// payouts, moving their total from the authority's tokens into a vault
+    /// the distributor owns. Only analytics oracles can create distributors
+    pub fn create_reward_distributor(
+        ctx: Context<CreateRewardDistributor>,
+        epoch: u64,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_recipients: u32,
+        expires_at: u64,
+    ) -> Result<()> {
+        ctx.accounts.config.check_not_paused(pause_flags::CREATE_REWARD_DISTRIBUTOR)?;
+        
+        require!(
+            ctx.accounts.config.analytics_oracles.contains(&ctx.accounts.authority.key()),
+            ContentError::Unauthorized
+        );
+        
+        let now = Clock::get()?.unix_timestamp as u64;
+        require!(
+            total_amount > 0
+                && num_recipients > 0
+                && num_recipients <= MAX_DISTRIBUTOR_RECIPIENTS
+                && expires_at > now,
+            ContentError::InvalidDistributor
+        );
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.source.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            total_amount,
+        )?;
+        
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.authority = ctx.accounts.authority.key();
+        distributor.epoch = epoch;
+        distributor.merkle_root = merkle_root;
+        distributor.total_amount = total_amount;
+        distributor.claimed_amount = 0;
+        distributor.num_recipients = num_recipients;
+        distributor.expires_at = expires_at;
+        distributor.clawed_back = false;
+        distributor.claimed_bitmap = vec![0; RewardDistributor::bitmap_len(num_recipients)];
+        distributor.bump = ctx.bumps.distributor;
+        
+        msg!("Reward distributor for epoch {} created successfully", epoch);
+        Ok(())
+    }
+
+    /// Claims the user's entry of a reward distributor with a Merkle proof
+    pub fn claim_distribution(
+        ctx: Context<ClaimDistribution>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::CLAIM_DISTRIBUTION)?;
+        
+        let now = Clock::get()?.unix_timestamp as u64;
+        let distributor = &mut ctx.accounts.distributor;
+        require!(
+            !distributor.clawed_back && !distributor.is_expired(now),
+            ContentError::DistributionExpired
+        );
+        require!(index < distributor.num_recipients, ContentError::InvalidDistributionEntry);
+        require!(!distributor.is_claimed(index), ContentError::DistributionAlreadyClaimed);
+        
+        let entry = DistributionEntry {
+            index,
+            user: ctx.accounts.user.key(),
+            amount,
+        };
+        require!(
+            verify_merkle_proof(entry.leaf(), &proof, &distributor.merkle_root),
+            ContentError::InvalidMerkleProof
+        );
+        
+        // Mark the entry claimed before paying it out
+        distributor.set_claimed(index);
+        distributor.claimed_amount = distributor
+            .claimed_amount
+            .checked_add(amount)
+            .filter(|claimed| *claimed <= distributor.total_amount)
+            .ok_or(ContentError::RewardOverflow)?;
+        
+        let epoch = distributor.epoch;
+        let bump = distributor.bump;
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.token_account.to_account_info(),
+                    authority: ctx.accounts.distributor.to_account_info(),
+                },
+                &[&[b"reward_distributor", epoch.to_le_bytes().as_ref(), &[bump]]],
+            ),
+            amount,
+        )?;
+        
+        msg!("Distribution entry {} claimed successfully", index);
+        Ok(())
+    }
+
+    /// Moves the unclaimed tokens of an expired reward distributor out of its
+    /// vault. Only the distributor's authority can claw back, and only once
+    pub fn clawback_distribution(ctx: Context<ClawbackDistribution>) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::CLAWBACK_DISTRIBUTION)?;
+        
+        let now = Clock::get()?.unix_timestamp as u64;
+        let distributor = &mut ctx.accounts.distributor;
+        require!(
+            distributor.authority == ctx.accounts.authority.key(),
+            ContentError::Unauthorized
+        );
+        require!(
+            !distributor.clawed_back && distributor.is_expired(now),
+            ContentError::DistributionNotClawable
+        );
+        distributor.clawed_back = true;
+        
+        let epoch = distributor.epoch;
+        let bump = distributor.bump;
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.distributor.to_account_info(),
+                },
+                &[&[b"reward_distributor", epoch.to_le_bytes().as_ref(), &[bump]]],
+            ),
+            ctx.accounts.vault.amount,
+        )?;
+        
+        msg!("Reward distributor for epoch {} clawed back successfully", epoch);
+        Ok(())
+    }
+
+    /// Credits a content's earnings, such as view rewards or campaign payouts
+    /// reported off-chain, as reward credits of its payees, split by their
+    /// royalty basis points. `remaining_accounts` holds the user rewards
+    /// account of each payee, in the order of `Content::payees`
+    pub fn accrue_creator_earnings(ctx: Context<AccrueCreatorEarnings>, amount: u64) -> Result<()> {
+        ctx.accounts.config.check_not_paused(pause_flags::ACCRUE_CREATOR_EARNINGS)?;
+        
+        require!(
+            ctx.accounts.config.analytics_oracles.contains(&ctx.accounts.authority.key()),
+            ContentError::Unauthorized
+        );
+        require!(amount > 0, ContentError::InvalidRewardAmount);
+        
+        let content = &ctx.accounts.content;
+        let payees = content.payees();
+        require!(
+            ctx.remaining_accounts.len() == payees.len(),
+            ErrorCode::AccountNotEnoughKeys
+        );
+        
+        let now = Clock::get()?.unix_timestamp as u64;
+        let shares = content.split_earnings(amount)?;
+        let payee_accounts = payees.iter().zip(shares).zip(ctx.remaining_accounts);
+        for ((payee, share), rewards_info) in payee_accounts {
+            if share == 0 {
+                continue;
+            }
+            accrue_user_rewards(
+                &ctx.accounts.authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                *payee,
+                rewards_info,
+                share,
+                now,
+            )?;
+        }
+        
+        msg!("Creator earnings accrued successfully");
+        Ok(())
+    }
+
+    /// Tips a published content in reward tokens, split between its payees by
+    /// their royalty basis points. `remaining_accounts` holds the associated
+    /// token account of the reward mint of each payee, in the order of
+    /// `Content::payees`
+    pub fn tip_content(ctx: Context<TipContent>, amount: u64) -> Result<()> {
+        let config = PlatformConfig::load_or_default(&ctx.accounts.config)?;
+        config.check_not_paused(pause_flags::TIP_CONTENT)?;
+        
+        require!(amount > 0, ContentError::InvalidRewardAmount);
+        
+        let content = &ctx.accounts.content;
+        let now = Clock::get()?.unix_timestamp as u64;
+        require!(content.is_published(now), ContentError::ContentNotPublished);
+        
+        let payees = content.payees();
+        require!(
+            ctx.remaining_accounts.len() == payees.len(),
+            ErrorCode::AccountNotEnoughKeys
+        );
+        
+        let shares = content.split_earnings(amount)?;
+        let reward_mint = ctx.accounts.reward_mint.key();
+        let payee_accounts = payees.iter().zip(shares).zip(ctx.remaining_accounts);
+        for ((payee, share), payee_token_account) in payee_accounts {
+            // Shares only go to the payee's own associated token account
+            require_keys_eq!(
+                payee_token_account.key(),
+                get_associated_token_address(payee, &reward_mint),
+                ErrorCode::ConstraintSeeds
+            );
+            if share == 0 {
+                continue;
+            }
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.source.to_account_info(),
+                        to: payee_token_account.clone(),
+                        authority: ctx.accounts.tipper.to_account_info(),
+                    },
+                ),
+                share,
+            )?;
+        }
+        
+        msg!("Content tipped successfully");
+        Ok(())
+    }
+}
+
+/// Resizes `account` to `new_space` bytes, charging the rent difference to
+/// `payer` when it grows and refunding it when it shrinks
+fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_space: usize,
+) -> Result<()> {
+    let rent = Rent::get()?;
+    let required_lamports = rent.minimum_balance(new_space);
+    let current_lamports = account.lamports();
+    
+    if required_lamports > current_lamports {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            required_lamports - current_lamports,
+        )?;
+    } else if current_lamports > required_lamports {
+        let refund = current_lamports - required_lamports;
+        **account.try_borrow_mut_lamports()? -= refund;
+        **payer.try_borrow_mut_lamports()? += refund;
+    }
+    
+    account.realloc(new_space, true)?;
+    Ok(())
+}
+
+/// Checks a content's tag list is within `MAX_CONTENT_TAGS` and has no duplicates
+fn validate_tags(tags: &[String]) -> Result<()> {
+    require!(tags.len() <= MAX_CONTENT_TAGS, ContentError::TooManyTags);
+    for (i, tag) in tags.iter().enumerate() {
+        // Tags name categories, whose names must fit in a seed
+        require!(Category::is_valid_name(tag), ContentError::InvalidCategoryName);
+        require!(!tags[..i].contains(tag), ContentError::DuplicateTag);
+    }
+    Ok(())
+}
+
+/// Accrues `amount` credits at `now` to `user`'s rewards account, creating it
+/// with rent paid by `payer` on the first accrual
+fn accrue_user_rewards<'info>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    user: Pubkey,
+    user_rewards_info: &AccountInfo<'info>,
+    amount: u64,
+    now: u64,
+) -> Result<()> {
+    let (user_rewards_key, user_rewards_bump) =
+        Pubkey::find_program_address(&[b"user_rewards", user.as_ref()], &crate::ID);
+    require_keys_eq!(user_rewards_info.key(), user_rewards_key, ErrorCode::ConstraintSeeds);
+    
+    if user_rewards_info.data_is_empty() {
+        create_pda_account(
+            payer,
+            system_program,
+            user_rewards_info,
+            8 + UserRewards::SPACE,
+            &[b"user_rewards", user.as_ref(), &[user_rewards_bump]],
+        )?;
+        let mut user_rewards = UserRewards {
+            user,
+            accrued: 0,
+            claimed: 0,
+            last_accrued_at: 0,
+            bump: user_rewards_bump,
+        };
+        user_rewards.accrue(amount, now)?;
+        return user_rewards.try_serialize(&mut &mut user_rewards_info.try_borrow_mut_data()?[..]);
+    }
+    
+    let mut user_rewards = Account::<UserRewards>::try_from(user_rewards_info)?;
+    user_rewards.accrue(amount, now)?;
+    user_rewards.exit(&crate::ID)
+}
+
+/// Loads the category account for `name`, checking its address
+fn load_category<'info>(category_info: &AccountInfo<'info>, name: &str) -> Result<Account<'info, Category>> {
+    let category = Account::<Category>::try_from(category_info)?;
+    let (category_key, _) = Pubkey::find_program_address(&[b"category", name.as_bytes()], &crate::ID);
+    require_keys_eq!(category_info.key(), category_key, ErrorCode::ConstraintSeeds);
+    Ok(category)
+}
+
+/// Creates the program-owned PDA `account` with `space` bytes, even if it already holds lamports
+fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let missing_lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    if missing_lamports > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            missing_lamports,
+        )?;
+    }
+    
+    // Fails if the account is already in use
+    anchor_lang::system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        space as u64,
+    )?;
+    anchor_lang::system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Assign {
+                account_to_assign: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        &crate::ID,
+    )
+}
+
+/// Tags `content` with the active category `tag`, creating its category index
+/// with rent paid by `payer`
+fn add_content_tag<'info>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    content: Pubkey,
+    tag: &str,
+    category_info: &AccountInfo<'info>,
+    category_index_info: &AccountInfo<'info>,
+) -> Result<()> {
+    let mut category = load_category(category_info, tag)?;
+    require!(category.active, ContentError::CategoryInactive);
+    
+    // Create the category index at its PDA
+    let (category_index_key, category_index_bump) = Pubkey::find_program_address(
+        &[b"category_index", category_info.key.as_ref(), content.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(category_index_info.key(), category_index_key, ErrorCode::ConstraintSeeds);
+    create_pda_account(
+        payer,
+        system_program,
+        category_index_info,
+        8 + CategoryIndex::SPACE,
+        &[
+            b"category_index",
+            category_info.key.as_ref(),
+            content.as_ref(),
+            &[category_index_bump],
+        ],
+    )?;
+    let category_index = CategoryIndex {
+        category: category_info.key(),
+        content,
+        bump: category_index_bump,
+    };
+    category_index.try_serialize(&mut &mut category_index_info.try_borrow_mut_data()?[..])?;
+    
+    category.content_count += 1;
+    category.exit(&crate::ID)
+}
+
+/// Removes the tag `tag` from `content`, closing its category index and
+/// returning the rent to `destination`
+fn remove_content_tag<'info>(
+    destination: &AccountInfo<'info>,
+    content: Pubkey,
+    tag: &str,
+    category_info: &AccountInfo<'info>,
+    category_index_info: &AccountInfo<'info>,
+) -> Result<()> {
+    let mut category = load_category(category_info, tag)?;
+    
+    let category_index = Account::<CategoryIndex>::try_from(category_index_info)?;
+    require_keys_eq!(category_index.category, category_info.key(), ErrorCode::ConstraintSeeds);
+    require_keys_eq!(category_index.content, content, ErrorCode::ConstraintSeeds);
+    category_index.close(destination.clone())?;
+    
+    category.content_count = category.content_count.saturating_sub(1);
+    category.exit(&crate::ID)
+}
+
+/// Records the growth from the `previous` to the `current` analytics in a
+/// content's history. Content without a history is left as is
+fn record_analytics_history<'info>(
+    history_info: &AccountInfo<'info>,
+    now: u64,
+    previous: &ContentAnalytics,
+    current: &ContentAnalytics,
+) -> Result<()> {
+    if history_info.data_is_empty() {
+        return Ok(());
+    }
+    
+    let mut history = Account::<AnalyticsHistory>::try_from(history_info)?;
+    history.record(now, previous, current);
+    history.exit(&crate::ID)
+}
+
+/// Checks that the instruction before the current one is an Ed25519 program
+/// instruction verifying an analytics oracle's signature over `attestation`
+fn verify_analytics_attestation(
+    instructions: &AccountInfo,
+    config: &PlatformConfig,
+    attestation: &AnalyticsAttestation,
+) -> Result<()> {
+    // The Ed25519 program fails the transaction on a bad signature, so only
+    // who signed and what was signed are left to check
+    let current_index = sysvar_instructions::load_current_index_checked(instructions)?;
+    require!(current_index > 0, ContentError::InvalidAttestation);
+    let verify_instruction =
+        sysvar_instructions::load_instruction_at_checked(current_index as usize - 1, instructions)?;
+    require_keys_eq!(
+        verify_instruction.program_id,
+        solana_program::ed25519_program::ID,
+        ContentError::InvalidAttestation
+    );
+    
+    let (oracle, message) = parse_ed25519_instruction(&verify_instruction.data)
+        .ok_or(ContentError::InvalidAttestation)?;
+    require!(
+        message == attestation.message().as_slice(),
+        ContentError::InvalidAttestation
+    );
+    require!(
+        config.analytics_oracles.contains(&oracle),
+        ContentError::Unauthorized
+    );
+    Ok(())
+}
+
+/// Returns the public key and message of an Ed25519 program instruction
+/// verifying a single signature over data in the instruction itself
+fn parse_ed25519_instruction(data: &[u8]) -> Option<(Pubkey, &[u8])> {
+    // Signature count and padding, then seven little-endian u16 offsets and
+    // instruction indexes
+    if data.len() < 2 + 14 || data[0] != 1 {
+        return None;
+    }
+    let offset = |i: usize| u16::from_le_bytes([data[2 + 2 * i], data[3 + 2 * i]]);
+    
+    // Signature, public key and message must all come from this instruction
+    if offset(1) != u16::MAX || offset(3) != u16::MAX || offset(6) != u16::MAX {
+        return None;
+    }
+    
+    let public_key_offset = offset(2) as usize;
+    let message_offset = offset(4) as usize;
+    let message_size = offset(5) as usize;
+    let public_key = data.get(public_key_offset..public_key_offset + 32)?;
+    let message = data.get(message_offset..message_offset + message_size)?;
+    Some((Pubkey::try_from(public_key).ok()?, message))
+}
+
+/// Hashes serialized data into a Merkle leaf. Leaves and inner nodes use
+/// distinct prefixes so a leaf can never pass for an inner node
+fn hash_merkle_leaf(data: &[u8]) -> [u8; 32] {
+    solana_program::hash::hashv(&[&[0], data]).to_bytes()
+}
+
+/// Hashes two children into their parent, in sorted order so a proof is
+/// just the siblings from the leaf up
+fn hash_merkle_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if left <= right { (left, right) } else { (right, left) };
+    solana_program::hash::hashv(&[&[1], first, second]).to_bytes()
+}
+
+/// Checks that `leaf` is in the tree with `root`, given its siblings from the
+/// bottom of the tree up
+fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| hash_merkle_node(&node, sibling)) == *root
+}
+
+/// Mints `amount` reward tokens to `recipient`, signing as the reward mint
+/// authority with `authority_bump`, and counts them against the emission
+/// schedule in `config`. Every reward-minting path goes through here
+fn mint_reward_tokens<'info>(
+    config: &mut Account<'info, PlatformConfig>,
+    reward_mint: &Account<'info, Mint>,
+    recipient: &Account<'info, TokenAccount>,
+    reward_mint_authority: &UncheckedAccount<'info>,
+    token_program: &Program<'info, Token>,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp as u64;
+    config.record_emission(amount, now)?;
+    
+    token::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::MintTo {
+                mint: reward_mint.to_account_info(),
+                to: recipient.to_account_info(),
+                authority: reward_mint_authority.to_account_info(),
+            },
+            &[&[b"reward_mint_authority", &[authority_bump]]],
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(
+    content_id: String,
+    title: String,
+    description: String,
+    content_url: String,
+    content_type: String,
+    tags: Vec<String>
+)]
+pub struct InitializeContent<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Content::space(&content_id, &tags),
+        seeds = [b"content", creator.key().as_ref(), content_id_seed(&content_id).as_ref()],
+        bump
+    )]
+    pub content: Account<'info, Content>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + CreatorProfile::SPACE,
+        seeds = [b"creator_profile", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + ContentIndex::SPACE,
+        seeds = [
+            b"content_index",
+            creator.key().as_ref(),
+            creator_profile.content_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub content_index: Account<'info, ContentIndex>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAnalytics<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub content: Account<'info, Content>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    
+    /// CHECK: the content's analytics history PDA, recorded to once initialized
+    #[account(mut, seeds = [b"analytics_history", content.key().as_ref()], bump)]
+    pub history: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecordView<'info> {
+    #[account(mut)]
+    pub viewer: Signer<'info>,
+    
+    #[account(mut)]
+    pub content: Account<'info, Content>,
+    
+    #[account(
+        init_if_needed,
+        payer = viewer,
+        space = 8 + ViewReceipt::SPACE,
+        seeds = [b"view_receipt", content.key().as_ref(), viewer.key().as_ref()],
+        bump
+    )]
+    pub view_receipt: Account<'info, ViewReceipt>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    
+    /// CHECK: the content's analytics history PDA, recorded to once initialized
+    #[account(mut, seeds = [b"analytics_history", content.key().as_ref()], bump)]
+    pub history: UncheckedAccount<'info>,
+    
+    /// CHECK: the viewer's rewards PDA, only created once a view earns rewards
+    #[account(mut, seeds = [b"user_rewards", viewer.key().as_ref()], bump)]
+    pub user_rewards: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct CommitEngagementEpoch<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + EngagementEpoch::SPACE,
+        seeds = [b"engagement_epoch", epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub engagement_epoch: Account<'info, EngagementEpoch>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecordEpochTotals<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(seeds = [b"engagement_epoch", engagement_epoch.epoch.to_le_bytes().as_ref()], bump = engagement_epoch.bump)]
+    pub engagement_epoch: Account<'info, EngagementEpoch>,
+    
+    pub content: Account<'info, Content>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + EpochContentTotals::SPACE,
+        seeds = [b"epoch_totals", engagement_epoch.key().as_ref(), content.key().as_ref()],
+        bump
+    )]
+    pub totals: Account<'info, EpochContentTotals>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyEngagement<'info> {
+    #[account(seeds = [b"engagement_epoch", engagement_epoch.epoch.to_le_bytes().as_ref()], bump = engagement_epoch.bump)]
+    pub engagement_epoch: Account<'info, EngagementEpoch>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateContentAnalytics<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    /// CHECK: content that `Account<Content>` may fail to load until migrated;
+    /// its discriminator, creator and address are checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub content: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BatchUpdateAnalytics<'info> {
+    pub authority: Signer<'info>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MintNft<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"content", creator.key().as_ref(), content_id_seed(&content.content_id).as_ref()],
+        bump = content.bump
+    )]
+    pub content: Account<'info, Content>,
+    
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = 0,
+        mint::authority = creator,
+    )]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = mint,
+        associated_token::authority = creator,
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Metadata account
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program_id.to_bytes().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = metadata_program_id
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    
+    /// CHECK: Master edition account
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program_id.to_bytes().as_ref(),
+            mint.key().as_ref(),
+            b"edition"
+        ],
+        bump,
+        seeds::program = metadata_program_id
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRoyaltyDistribution<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"content", creator.key().as_ref(), content_id_seed(&content.content_id).as_ref()],
+        bump = content.bump
+    )]
+    pub content: Account<'info, Content>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateContentMetadata<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"content", creator.key().as_ref(), content_id_seed(&content.content_id).as_ref()],
+        bump = content.bump
+    )]
+    pub content: Account<'info, Content>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseContent<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"content", creator.key().as_ref(), content_id_seed(&content.content_id).as_ref()],
+        bump = content.bump
+    )]
+    pub content: Account<'info, Content>,
+    
+    /// Mint of the content's NFT, required only when one has been minted
+    pub nft_mint: Option<Account<'info, Mint>>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    
+    /// CHECK: the content's analytics history PDA, closed if initialized
+    #[account(mut, seeds = [b"analytics_history", content.key().as_ref()], bump)]
+    pub history: UncheckedAccount<'info>,
+}
+
+/// Moves content created under the old `[b"content", creator, content_id]`
+/// derivation to its hashed-seed address
+#[derive(Accounts)]
+pub struct MigrateContentAddress<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"content", creator.key().as_ref(), legacy_content.content_id.as_bytes()],
+        bump = legacy_content.bump
+    )]
+    pub legacy_content: Account<'info, Content>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + legacy_content.current_space(),
+        seeds = [b"content", creator.key().as_ref(), content_id_seed(&legacy_content.content_id).as_ref()],
+        bump
+    )]
+    pub content: Account<'info, Content>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PlatformConfig::SPACE,
+        seeds = [b"platform_config"],
+        bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Blocktok>,
+    
+    /// Only the program's upgrade authority may create the config
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ContentError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(mut, seeds = [b"platform_config"], bump = config.bump)]
+    pub config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAdmin<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(mut, seeds = [b"platform_config"], bump = config.bump)]
+    pub config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+    
+    #[account(mut, seeds = [b"platform_config"], bump = config.bump)]
+    pub config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetPauseState<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(mut, seeds = [b"platform_config"], bump = config.bump)]
+    pub config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAnalyticsHistory<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub content: Account<'info, Content>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AnalyticsHistory::SPACE,
+        seeds = [b"analytics_history", content.key().as_ref()],
+        bump
+    )]
+    pub history: Account<'info, AnalyticsHistory>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAnalyticsOracles<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(mut, seeds = [b"platform_config"], bump = config.bump)]
+    pub config: Account<'info, PlatformConfig>,
+}
+
+/// Accounts for `publish`, `unlist` and `archive`
+#[derive(Accounts)]
+pub struct UpdateContentStatus<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"content", creator.key().as_ref(), content_id_seed(&content.content_id).as_ref()],
+        bump = content.bump
+    )]
+    pub content: Account<'info, Content>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateCategory<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    // The name is checked here, before the category's seeds are derived from it
+    #[account(
+        seeds = [b"platform_config"],
+        bump = config.bump,
+        constraint = Category::is_valid_name(&name) @ ContentError::InvalidCategoryName
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Category::space(&name),
+        seeds = [b"category", name.as_bytes()],
+        bump
+    )]
+    pub category: Account<'info, Category>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct SetCategoryActive<'info> {
+    pub admin: Signer<'info>,
+    
+    // The name is checked here, before the category's seeds are derived from it
+    #[account(
+        seeds = [b"platform_config"],
+        bump = config.bump,
+        constraint = Category::is_valid_name(&name) @ ContentError::InvalidCategoryName
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(mut, seeds = [b"category", name.as_bytes()], bump = category.bump)]
+    pub category: Account<'info, Category>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRewardMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(seeds = [b"platform_config"], bump = config.bump)]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"reward_mint"],
+        bump,
+        mint::decimals = REWARD_TOKEN_DECIMALS,
+        mint::authority = reward_mint_authority,
+    )]
+    pub reward_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA only signing as the reward mint authority; holds no data
+    #[account(seeds = [b"reward_mint_authority"], bump)]
+    pub reward_mint_authority: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct MintRewards<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(mut, seeds = [b"platform_config"], bump = config.bump)]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(mut, seeds = [b"reward_mint"], bump)]
+    pub reward_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA only signing as the reward mint authority; holds no data
+    #[account(seeds = [b"reward_mint_authority"], bump)]
+    pub reward_mint_authority: UncheckedAccount<'info>,
+    
+    #[account(mut, token::mint = reward_mint)]
+    pub recipient: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AccrueRewards<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(seeds = [b"platform_config"], bump = config.bump)]
+    pub config: Account<'info, PlatformConfig>,
+    
+    /// CHECK: only the key of the user credited
+    pub user: UncheckedAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + UserRewards::SPACE,
+        seeds = [b"user_rewards", user.key().as_ref()],
+        bump
+    )]
+    pub user_rewards: Account<'info, UserRewards>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(mut, seeds = [b"platform_config"], bump = config.bump)]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(mut, seeds = [b"user_rewards", user.key().as_ref()], bump = user_rewards.bump)]
+    pub user_rewards: Account<'info, UserRewards>,
+    
+    #[account(mut, seeds = [b"reward_mint"], bump)]
+    pub reward_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA only signing as the reward mint authority; holds no data
+    #[account(seeds = [b"reward_mint_authority"], bump)]
+    pub reward_mint_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64, merkle_root: [u8; 32], total_amount: u64, num_recipients: u32)]
+pub struct CreateRewardDistributor<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(seeds = [b"platform_config"], bump = config.bump)]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RewardDistributor::space(num_recipients),
+        seeds = [b"reward_distributor", epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distributor: Account<'info, RewardDistributor>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"distributor_vault", distributor.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = distributor,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(seeds = [b"reward_mint"], bump)]
+    pub reward_mint: Account<'info, Mint>,
+    
+    #[account(mut, token::mint = reward_mint, token::authority = authority)]
+    pub source: Account<'info, TokenAccount>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDistribution<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_distributor", distributor.epoch.to_le_bytes().as_ref()], bump = distributor.bump)]
+    pub distributor: Account<'info, RewardDistributor>,
+    
+    #[account(mut, seeds = [b"distributor_vault", distributor.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(seeds = [b"reward_mint"], bump)]
+    pub reward_mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct ClawbackDistribution<'info> {
+    pub authority: Signer<'info>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_distributor", distributor.epoch.to_le_bytes().as_ref()], bump = distributor.bump)]
+    pub distributor: Account<'info, RewardDistributor>,
+    
+    #[account(mut, seeds = [b"distributor_vault", distributor.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, token::mint = vault.mint)]
+    pub destination: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AccrueCreatorEarnings<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(seeds = [b"platform_config"], bump = config.bump)]
+    pub config: Account<'info, PlatformConfig>,
+    
+    pub content: Account<'info, Content>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TipContent<'info> {
+    pub tipper: Signer<'info>,
+    
+    /// CHECK: the platform config PDA, read with `PlatformConfig::load_or_default`
+    #[account(seeds = [b"platform_config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    
+    pub content: Account<'info, Content>,
+    
+    #[account(mut, token::mint = reward_mint, token::authority = tipper)]
+    pub source: Account<'info, TokenAccount>,
+    
+    #[account(seeds = [b"reward_mint"], bump)]
+    pub reward_mint: Account<'info, Mint>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+pub struct Content {
+    pub creator: Pubkey,
+    pub content_id: String,
+    pub title: String,
+    pub description: String,
+    pub content_url: String,
+    pub content_type: String,
+    pub created_at: u64,
+    pub nft_mint: Option<Pubkey>,
+    pub analytics: ContentAnalytics,
+    pub royalty_recipients: Vec<RoyaltyRecipient>,
+    pub bump: u8,
+    /// Names of the categories the content is tagged with
+    pub tags: Vec<String>,
+    pub status: ContentStatus,
+    /// Unix timestamp a `Scheduled` content becomes published at
+    pub publish_at: u64,
+    /// Nonce of the last analytics attestation applied, to reject replays
+    pub analytics_nonce: u64,
+    /// Watch time, completion and replays. Kept last, unlike the native
+    /// program's `ContentAnalytics::watch`, so older content only needs to
+    /// grow to load it; see `migrate_content_analytics`
+    pub watch_metrics: WatchMetrics,
+    /// Weighted, time-decayed engagement, recomputed from the config's
+    /// `engagement_weights` whenever the analytics change
+    pub engagement_score: u64,
+}
+
+/// `Content` as laid out before `watch_metrics`, to migrate older accounts
+#[derive(AnchorDeserialize)]
+struct LegacyContent {
+    creator: Pubkey,
+    content_id: String,
+    _title: String,
+    _description: String,
+    _content_url: String,
+    _content_type: String,
+    _created_at: u64,
+    _nft_mint: Option<Pubkey>,
+    _analytics: ContentAnalytics,
+    _royalty_recipients: Vec<RoyaltyRecipient>,
+    bump: u8,
+    _tags: Vec<String>,
+    _status: ContentStatus,
+    _publish_at: u64,
+    _analytics_nonce: u64,
+}
+
+impl Content {
+    pub fn space(content_id: &str, tags: &[String]) -> usize {
+        // Space calculation
+        // creator: 32 bytes
+        // content_id: 4 + len (String)
+        // title: 4 + len (String)
+        // description: 4 + len (String)
+        // content_url: 4 + len (String)
         // content_type: 4 + len (String)
         // created_at: 8 bytes
         // nft_mint: 1 + 32 bytes (Option<Pubkey>)
         // analytics: size of ContentAnalytics
-        // royalty_recipients: 4 + (estimated avg number of recipients * RoyaltyRecipient size)
+        // royalty_recipients: 4 (starts empty, resized by set_royalty_distribution)
         // bump: 1 byte
+        // tags: 4 + (4 + len) per tag (Vec<String>)
+        // status: 1 byte
+        // publish_at: 8 bytes
+        // analytics_nonce: 8 bytes
+        // watch_metrics: size of WatchMetrics
+        // engagement_score: 8 bytes
         32 + 
         (4 + content_id.len()) + 
         (4 + 50) + // title - assume 50 chars max
@@ -356,8 +2612,550 @@ impl Content {
         8 + 
         (1 + 32) + 
         ContentAnalytics::space() + 
-        (4 + 5 * RoyaltyRecipient::space()) + // assume 5 recipients max
-        1
+        4 +
+        1 +
+        Self::tags_space(tags) +
+        1 +
+        8 +
+        8 +
+        WatchMetrics::SPACE +
+        8
+    }
+
+    /// Space taken by the content as currently populated (without the discriminator)
+    pub fn current_space(&self) -> usize {
+        32 +
+        (4 + self.content_id.len()) +
+        (4 + self.title.len()) +
+        (4 + self.description.len()) +
+        (4 + self.content_url.len()) +
+        (4 + self.content_type.len()) +
+        8 +
+        (1 + 32) +
+        ContentAnalytics::space() +
+        (4 + self.royalty_recipients.len() * RoyaltyRecipient::space()) +
+        1 +
+        Self::tags_space(&self.tags) +
+        1 +
+        8 +
+        8 +
+        WatchMetrics::SPACE +
+        8
+    }
+    
+    /// Status as of `now`, treating scheduled content whose `publish_at` has
+    /// passed as published
+    pub fn effective_status(&self, now: u64) -> ContentStatus {
+        match self.status {
+            ContentStatus::Scheduled if self.publish_at <= now => ContentStatus::Published,
+            status => status,
+        }
+    }
+    
+    pub fn is_published(&self, now: u64) -> bool {
+        self.effective_status(now) == ContentStatus::Published
+    }
+    
+    /// Adds a viewer's newly watched part of the content to its completion
+    pub fn add_completion(&mut self, completion_bps: u16) -> Result<()> {
+        self.watch_metrics.total_completion_bps = self
+            .watch_metrics
+            .total_completion_bps
+            .checked_add(completion_bps as u64)
+            .ok_or(ContentError::AnalyticsOverflow)?;
+        Ok(())
+    }
+    
+    /// Average completion rate of a view, in basis points
+    pub fn average_completion_bps(&self) -> u64 {
+        self.watch_metrics.total_completion_bps.checked_div(self.analytics.views).unwrap_or(0)
+    }
+    
+    /// Recomputes the engagement score from the analytics as of `now`
+    pub fn refresh_engagement_score(&mut self, weights: &EngagementWeights, now: u64) {
+        self.engagement_score =
+            weights.score(&self.analytics, &self.watch_metrics, self.created_at, now);
+    }
+    
+    /// Accounts the content's earnings are paid to: the royalty recipients in
+    /// order, or the creator alone when there are none
+    pub fn payees(&self) -> Vec<Pubkey> {
+        if self.royalty_recipients.is_empty() {
+            return vec![self.creator];
+        }
+        self.royalty_recipients.iter().map(|recipient| recipient.pubkey).collect()
+    }
+    
+    /// Splits `amount` between the payees by their basis points. Each share is
+    /// rounded down, then the units left over go one each to the recipients
+    /// with the largest remainders, earlier recipients first on ties, so the
+    /// shares always add up to `amount`. That only holds when the basis points
+    /// add up to 100%, so any other split is rejected
+    pub fn split_earnings(&self, amount: u64) -> Result<Vec<u64>> {
+        if self.royalty_recipients.is_empty() {
+            return Ok(vec![amount]);
+        }
+        let total_percentage: u32 = self.royalty_recipients.iter().map(|r| r.percentage as u32).sum();
+        require!(total_percentage == 10000, ContentError::InvalidRoyaltyPercentage);
+        
+        let mut shares = Vec::with_capacity(self.royalty_recipients.len());
+        let mut remainders = Vec::with_capacity(self.royalty_recipients.len());
+        for recipient in &self.royalty_recipients {
+            let exact = amount as u128 * recipient.percentage as u128;
+            shares.push((exact / 10000) as u64);
+            remainders.push(exact % 10000);
+        }
+        
+        let mut order: Vec<usize> = (0..shares.len()).collect();
+        order.sort_by(|a, b| remainders[*b].cmp(&remainders[*a]).then(a.cmp(b)));
+        let leftover = amount.saturating_sub(shares.iter().sum());
+        for index in order.iter().cycle().take(leftover as usize) {
+            shares[*index] += 1;
+        }
+        Ok(shares)
+    }
+    
+    fn tags_space(tags: &[String]) -> usize {
+        4 + tags.iter().map(|tag| 4 + tag.len()).sum::<usize>()
+    }
+}
+
+/// Visibility of a content. Content starts as a `Draft`; `publish` moves it to
+/// `Published`, or to `Scheduled` until `publish_at`; `unlist` hides published
+/// content and `archive` retires content for good.
+///
+/// `Published` is encoded as 0 so that content written before statuses
+/// existed, whose status byte is zeroed spare space, stays published.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ContentStatus {
+    Published,
+    Draft,
+    Scheduled,
+    Unlisted,
+    Archived,
+}
+
+/// Per-creator profile, created with the creator's first content
+#[account]
+pub struct CreatorProfile {
+    pub creator: Pubkey,
+    /// Number of contents initialized; indexes `0..content_count` each have a `ContentIndex`
+    pub content_count: u64,
+    pub bump: u8,
+}
+
+impl CreatorProfile {
+    // creator: 32 bytes, content_count: 8 bytes, bump: 1 byte
+    pub const SPACE: usize = 32 + 8 + 1;
+}
+
+/// Pointer from a position in a creator's catalogue to a content account.
+/// Pointers are never removed, so closed content leaves a dangling index.
+#[account]
+pub struct ContentIndex {
+    pub creator: Pubkey,
+    pub index: u64,
+    pub content: Pubkey,
+    pub bump: u8,
+}
+
+impl ContentIndex {
+    // creator: 32 bytes, index: 8 bytes, content: 32 bytes, bump: 1 byte
+    pub const SPACE: usize = 32 + 8 + 32 + 1;
+}
+
+/// A category content can be tagged with, created by the platform admin
+#[account]
+pub struct Category {
+    pub name: String,
+    /// Whether content may currently be tagged with this category
+    pub active: bool,
+    /// Number of contents currently tagged with this category
+    pub content_count: u64,
+    pub bump: u8,
+}
+
+impl Category {
+    pub fn space(name: &str) -> usize {
+        // name: 4 + len (String), active: 1 byte, content_count: 8 bytes, bump: 1 byte
+        (4 + name.len()) + 1 + 8 + 1
+    }
+    
+    /// Whether `name` is non-empty and short enough to be a seed
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && name.len() <= MAX_CATEGORY_NAME_LEN
+    }
+}
+
+/// Marks a content as tagged with a category, one per (category, content)
+/// pair while the tag is set. A category's contents can be listed by
+/// filtering program accounts on `category`.
+#[account]
+pub struct CategoryIndex {
+    pub category: Pubkey,
+    pub content: Pubkey,
+    pub bump: u8,
+}
+
+impl CategoryIndex {
+    // category: 32 bytes, content: 32 bytes, bump: 1 byte
+    pub const SPACE: usize = 32 + 32 + 1;
+}
+
+/// Commitment by an analytics oracle to every engagement event in a window,
+/// as the Merkle root of the events' `EngagementEvent::leaf` hashes. Events
+/// stay off-chain, and any of them can be proven with `verify_engagement`.
+#[account]
+pub struct EngagementEpoch {
+    pub authority: Pubkey,
+    pub epoch: u64,
+    /// Start of the window (inclusive)
+    pub start_time: u64,
+    /// End of the window (exclusive)
+    pub end_time: u64,
+    pub merkle_root: [u8; 32],
+    pub event_count: u64,
+    pub committed_at: u64,
+    pub bump: u8,
+}
+
+impl EngagementEpoch {
+    // authority: 32 bytes, epoch: 8 bytes, start_time: 8 bytes, end_time: 8 bytes,
+    // merkle_root: 32 bytes, event_count: 8 bytes, committed_at: 8 bytes, bump: 1 byte
+    pub const SPACE: usize = 32 + 8 + 8 + 8 + 32 + 8 + 8 + 1;
+}
+
+/// Engagement totals of a single content over an engagement epoch
+#[account]
+pub struct EpochContentTotals {
+    pub epoch: Pubkey,
+    pub content: Pubkey,
+    pub views: u64,
+    pub likes: u64,
+    pub shares: u64,
+    pub comments: u64,
+    pub bump: u8,
+}
+
+impl EpochContentTotals {
+    // epoch: 32 bytes, content: 32 bytes, views, likes, shares, comments: 8 bytes each,
+    // bump: 1 byte
+    pub const SPACE: usize = 32 + 32 + 8 * 4 + 1;
+}
+
+/// Proof that a viewer has viewed a content, created on their first view, so
+/// the content's views grow once per viewer
+#[account]
+pub struct ViewReceipt {
+    pub content: Pubkey,
+    pub viewer: Pubkey,
+    pub first_seen_at: u64,
+    /// Furthest watch progress reported, in basis points of the content
+    pub watch_progress_bps: u16,
+    pub bump: u8,
+}
+
+impl ViewReceipt {
+    // content: 32 bytes, viewer: 32 bytes, first_seen_at: 8 bytes,
+    // watch_progress_bps: 2 bytes, bump: 1 byte
+    pub const SPACE: usize = 32 + 32 + 8 + 2 + 1;
+}
+
+/// Reward credits of a user, accrued from verified engagement and paid out in
+/// reward tokens by `claim_rewards`
+#[account]
+pub struct UserRewards {
+    pub user: Pubkey,
+    /// Total credits accrued, in reward token base units
+    pub accrued: u64,
+    /// Total credits already claimed
+    pub claimed: u64,
+    pub last_accrued_at: u64,
+    pub bump: u8,
+}
+
+impl UserRewards {
+    // user: 32 bytes, accrued, claimed, last_accrued_at: 8 bytes each, bump: 1 byte
+    pub const SPACE: usize = 32 + 3 * 8 + 1;
+    
+    /// Credits accrued but not yet claimed
+    pub fn claimable(&self) -> u64 {
+        self.accrued - self.claimed
+    }
+    
+    /// Accrues `amount` credits at `now`
+    pub fn accrue(&mut self, amount: u64, now: u64) -> Result<()> {
+        self.accrued = self.accrued.checked_add(amount).ok_or(ContentError::RewardOverflow)?;
+        self.last_accrued_at = now;
+        Ok(())
+    }
+}
+
+/// Reward payouts of one epoch, committed as the Merkle root of their
+/// `DistributionEntry::leaf` hashes and backed by a token vault the
+/// distributor owns. Each recipient claims their own entry with a proof
+#[account]
+pub struct RewardDistributor {
+    pub authority: Pubkey,
+    pub epoch: u64,
+    pub merkle_root: [u8; 32],
+    /// Reward tokens deposited in the vault, in base units
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub num_recipients: u32,
+    /// Claims are refused, and the rest of the vault can be clawed back, from here on
+    pub expires_at: u64,
+    pub clawed_back: bool,
+    /// One bit per entry, set once the entry is claimed
+    pub claimed_bitmap: Vec<u8>,
+    pub bump: u8,
+}
+
+impl RewardDistributor {
+    // authority: 32 bytes, epoch: 8 bytes, merkle_root: 32 bytes, total_amount,
+    // claimed_amount: 8 bytes each, num_recipients: 4 bytes, expires_at: 8 bytes,
+    // clawed_back: 1 byte, claimed_bitmap: 4 + 1 bit per entry, bump: 1 byte
+    pub fn space(num_recipients: u32) -> usize {
+        32 + 8 + 32 + 8 + 8 + 4 + 8 + 1 + (4 + Self::bitmap_len(num_recipients)) + 1
+    }
+    
+    /// Bytes of a claim bitmap with a bit per recipient
+    pub fn bitmap_len(num_recipients: u32) -> usize {
+        (num_recipients as usize + 7) / 8
+    }
+    
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed_bitmap[index as usize / 8] & (1 << (index % 8)) != 0
+    }
+    
+    pub fn set_claimed(&mut self, index: u32) {
+        self.claimed_bitmap[index as usize / 8] |= 1 << (index % 8);
+    }
+    
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Per-content ring buffer of analytics deltas in fixed time buckets. A
+/// bucket's slot is derived from its start time, so each update lands in the
+/// slot for the current bucket, clearing it first if it holds an older one.
+#[account]
+pub struct AnalyticsHistory {
+    pub content: Pubkey,
+    pub granularity: HistoryGranularity,
+    /// Always `ANALYTICS_HISTORY_BUCKETS` long
+    pub buckets: Vec<AnalyticsBucket>,
+    pub bump: u8,
+}
+
+impl AnalyticsHistory {
+    // content: 32 bytes, granularity: 1 byte,
+    // buckets: 4 + ANALYTICS_HISTORY_BUCKETS * bucket bytes, bump: 1 byte
+    pub const SPACE: usize = 32 + 1 + (4 + ANALYTICS_HISTORY_BUCKETS * AnalyticsBucket::SPACE) + 1;
+    
+    /// Adds the growth from the `previous` to the `current` counters to the
+    /// bucket containing `now`
+    pub fn record(&mut self, now: u64, previous: &ContentAnalytics, current: &ContentAnalytics) {
+        let bucket_seconds = self.granularity.seconds();
+        let start = now - now % bucket_seconds;
+        let slot = (now / bucket_seconds) as usize % self.buckets.len();
+        
+        let bucket = &mut self.buckets[slot];
+        if bucket.start != start {
+            *bucket = AnalyticsBucket {
+                start,
+                ..AnalyticsBucket::default()
+            };
+        }
+        bucket.views = bucket.views.saturating_add(current.views.saturating_sub(previous.views));
+        bucket.likes = bucket.likes.saturating_add(current.likes.saturating_sub(previous.likes));
+        bucket.shares = bucket.shares.saturating_add(current.shares.saturating_sub(previous.shares));
+        bucket.comments = bucket
+            .comments
+            .saturating_add(current.comments.saturating_sub(previous.comments));
+    }
+    
+    /// The recorded buckets as a time series, oldest first. Buckets without
+    /// any updates are left out
+    pub fn time_series(&self) -> Vec<AnalyticsBucket> {
+        let mut buckets: Vec<AnalyticsBucket> = self
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.start != 0)
+            .copied()
+            .collect();
+        buckets.sort_by_key(|bucket| bucket.start);
+        buckets
+    }
+}
+
+/// Length of the buckets of an analytics history
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HistoryGranularity {
+    Hourly,
+    Daily,
+}
+
+impl HistoryGranularity {
+    /// Length of a bucket in seconds
+    pub fn seconds(self) -> u64 {
+        match self {
+            HistoryGranularity::Hourly => 60 * 60,
+            HistoryGranularity::Daily => 24 * 60 * 60,
+        }
+    }
+}
+
+/// Analytics deltas recorded during one bucket of an analytics history
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct AnalyticsBucket {
+    /// Unix timestamp the bucket starts at, or zero if the slot was never used
+    pub start: u64,
+    pub views: u64,
+    pub likes: u64,
+    pub shares: u64,
+    pub comments: u64,
+}
+
+impl AnalyticsBucket {
+    // start, views, likes, shares, comments: 8 bytes each
+    pub const SPACE: usize = 5 * 8;
+}
+
+/// Global platform settings, stored in a singleton PDA
+#[account]
+pub struct PlatformConfig {
+    pub admin: Pubkey,
+    /// Admin nominated by `transfer_admin`, until they accept
+    pub pending_admin: Option<Pubkey>,
+    /// Account that receives platform fees
+    pub treasury: Pubkey,
+    pub platform_fee_bps: u16,
+    /// Bitmask of enabled platform features
+    pub features: u64,
+    /// Pauses every non-admin instruction
+    pub paused: bool,
+    /// Bitmask of individually paused instructions, see `pause_flags`
+    pub paused_instructions: u64,
+    /// Authorities trusted to report analytics for any content
+    pub analytics_oracles: Vec<Pubkey>,
+    /// Whether creators may still report analytics for their own content
+    pub allow_creator_analytics: bool,
+    /// Minimum seconds between analytics updates of a content
+    pub min_analytics_interval: u64,
+    /// Weights of the content engagement score
+    pub engagement_weights: EngagementWeights,
+    /// Reward credits a viewer accrues for each view backed by a new view receipt
+    pub view_reward: u64,
+    /// Limits on minting reward tokens
+    pub emission_schedule: EmissionSchedule,
+    /// Reward tokens minted so far, in base units
+    pub emitted_total: u64,
+    /// Emission epoch counted by `emitted_in_epoch`
+    pub emission_epoch: u64,
+    /// Reward tokens minted during `emission_epoch`
+    pub emitted_in_epoch: u64,
+    pub bump: u8,
+}
+
+impl PlatformConfig {
+    // admin: 32 bytes, pending_admin: 1 + 32 bytes, treasury: 32 bytes,
+    // platform_fee_bps: 2 bytes, features: 8 bytes, paused: 1 byte,
+    // paused_instructions: 8 bytes, analytics_oracles: 4 + 8 * 32 bytes,
+    // allow_creator_analytics: 1 byte, min_analytics_interval: 8 bytes,
+    // engagement_weights: size of EngagementWeights, view_reward: 8 bytes,
+    // emission_schedule: size of EmissionSchedule, emitted_total, emission_epoch,
+    // emitted_in_epoch: 8 bytes each, bump: 1 byte
+    pub const SPACE: usize = 32 + (1 + 32) + 32 + 2 + 8 + 1 + 8
+        + (4 + MAX_ANALYTICS_ORACLES * 32) + 1 + 8 + EngagementWeights::SPACE + 8
+        + EmissionSchedule::SPACE + 3 * 8 + 1;
+    
+    /// Loads the platform config, reading it while still uninitialized as
+    /// a config with nothing paused, no analytics oracles, creator analytics
+    /// allowed and no view rewards, as the native program does
+    pub fn load_or_default<'info>(config_info: &AccountInfo<'info>) -> Result<PlatformConfig> {
+        if config_info.data_is_empty() {
+            return Ok(PlatformConfig {
+                admin: Pubkey::default(),
+                pending_admin: None,
+                treasury: Pubkey::default(),
+                platform_fee_bps: 0,
+                features: 0,
+                paused: false,
+                paused_instructions: 0,
+                analytics_oracles: Vec::new(),
+                allow_creator_analytics: true,
+                min_analytics_interval: 0,
+                engagement_weights: EngagementWeights::default(),
+                view_reward: 0,
+                emission_schedule: EmissionSchedule::default(),
+                emitted_total: 0,
+                emission_epoch: 0,
+                emitted_in_epoch: 0,
+                bump: 0,
+            });
+        }
+        
+        // `Account` checks the owner and discriminator
+        Ok(Account::<PlatformConfig>::try_from(config_info)?.into_inner())
+    }
+    
+    /// Checks whether all bits of `feature` are enabled
+    pub fn has_feature(&self, feature: u64) -> bool {
+        self.features & feature == feature
+    }
+    
+    /// Fails with `ProgramPaused` if the program, or the instruction
+    /// identified by `pause_flag`, is paused
+    pub fn check_not_paused(&self, pause_flag: u64) -> Result<()> {
+        require!(
+            !self.paused && self.paused_instructions & pause_flag == 0,
+            ContentError::ProgramPaused
+        );
+        Ok(())
+    }
+    
+    /// Checks whether `authority` may report analytics for content owned by `creator`
+    pub fn can_report_analytics(&self, authority: Pubkey, creator: Pubkey) -> bool {
+        self.analytics_oracles.contains(&authority)
+            || (self.allow_creator_analytics && authority == creator)
+    }
+    
+    /// Fails with `AnalyticsUpdateTooFrequent` unless analytics last updated at
+    /// `last_update` may be updated again at `now`
+    pub fn check_analytics_interval(&self, last_update: u64, now: u64) -> Result<()> {
+        require!(
+            now >= last_update.saturating_add(self.min_analytics_interval),
+            ContentError::AnalyticsUpdateTooFrequent
+        );
+        Ok(())
+    }
+    
+    /// Counts `amount` reward tokens minted at `now` against the emission
+    /// schedule, failing with `EmissionLimitExceeded` past the current epoch's
+    /// allowance or the total cap
+    pub fn record_emission(&mut self, amount: u64, now: u64) -> Result<()> {
+        let epoch = self
+            .emission_schedule
+            .epoch_at(now)
+            .ok_or(ContentError::EmissionLimitExceeded)?;
+        if epoch != self.emission_epoch {
+            self.emission_epoch = epoch;
+            self.emitted_in_epoch = 0;
+        }
+        
+        self.emitted_in_epoch = self
+            .emitted_in_epoch
+            .checked_add(amount)
+            .filter(|emitted| *emitted <= self.emission_schedule.epoch_allowance(epoch))
+            .ok_or(ContentError::EmissionLimitExceeded)?;
+        self.emitted_total = self
+            .emitted_total
+            .checked_add(amount)
+            .filter(|emitted| *emitted <= self.emission_schedule.total_cap)
+            .ok_or(ContentError::EmissionLimitExceeded)?;
+        Ok(())
     }
 }
 
@@ -375,6 +3173,252 @@ impl ContentAnalytics {
         // views, likes, shares, comments, updated_at: 8 bytes each
         5 * 8
     }
+    
+    /// Adds deltas to the counters, failing without changes if any would overflow
+    pub fn increment(&mut self, views: u64, likes: u64, shares: u64, comments: u64) -> Result<()> {
+        let views = self.views.checked_add(views).ok_or(ContentError::AnalyticsOverflow)?;
+        let likes = self.likes.checked_add(likes).ok_or(ContentError::AnalyticsOverflow)?;
+        let shares = self.shares.checked_add(shares).ok_or(ContentError::AnalyticsOverflow)?;
+        let comments = self.comments.checked_add(comments).ok_or(ContentError::AnalyticsOverflow)?;
+        
+        self.views = views;
+        self.likes = likes;
+        self.shares = shares;
+        self.comments = comments;
+        Ok(())
+    }
+    
+    /// Sets the counters, failing without changes if any would decrease
+    pub fn set(&mut self, views: u64, likes: u64, shares: u64, comments: u64) -> Result<()> {
+        require!(
+            views >= self.views
+                && likes >= self.likes
+                && shares >= self.shares
+                && comments >= self.comments,
+            ContentError::AnalyticsDecreased
+        );
+        
+        self.views = views;
+        self.likes = likes;
+        self.shares = shares;
+        self.comments = comments;
+        Ok(())
+    }
+}
+
+/// Watch metrics of short-form video, kept as totals so they can only grow
+/// like the other counters
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct WatchMetrics {
+    /// Total seconds watched across all views
+    pub watch_seconds: u64,
+    /// Sum of each view's completion in basis points, so the average
+    /// completion rate is this over the number of views
+    pub total_completion_bps: u64,
+    /// Number of views by viewers that had already viewed the content
+    pub replays: u64,
+}
+
+impl WatchMetrics {
+    // watch_seconds, total_completion_bps, replays: 8 bytes each
+    pub const SPACE: usize = 3 * 8;
+    
+    /// Adds `other` to each metric, or `None` if any would overflow
+    pub fn checked_add(&self, other: &WatchMetrics) -> Option<WatchMetrics> {
+        Some(WatchMetrics {
+            watch_seconds: self.watch_seconds.checked_add(other.watch_seconds)?,
+            total_completion_bps: self.total_completion_bps.checked_add(other.total_completion_bps)?,
+            replays: self.replays.checked_add(other.replays)?,
+        })
+    }
+    
+    /// Whether every metric is at least the one in `other`
+    pub fn covers(&self, other: &WatchMetrics) -> bool {
+        self.watch_seconds >= other.watch_seconds
+            && self.total_completion_bps >= other.total_completion_bps
+            && self.replays >= other.replays
+    }
+}
+
+/// Weights of the engagement score, set by the platform admin. The score is
+/// the weighted sum of the analytics counters, halved every `decay_half_life`
+/// seconds since the content was created
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct EngagementWeights {
+    pub views: u64,
+    pub likes: u64,
+    pub shares: u64,
+    pub comments: u64,
+    /// Points per second watched
+    pub watch_seconds: u64,
+    /// Seconds after which the score halves, or zero for no decay
+    pub decay_half_life: u64,
+}
+
+impl EngagementWeights {
+    // views, likes, shares, comments, watch_seconds, decay_half_life: 8 bytes each
+    pub const SPACE: usize = 6 * 8;
+    
+    /// Engagement score of content created at `created_at`, as of `now`. The
+    /// weighted sum saturates at `u64::MAX`; decay halves it once per whole
+    /// half-life and interpolates linearly within the current one
+    pub fn score(
+        &self,
+        analytics: &ContentAnalytics,
+        watch: &WatchMetrics,
+        created_at: u64,
+        now: u64,
+    ) -> u64 {
+        let weighted = analytics.views as u128 * self.views as u128
+            + analytics.likes as u128 * self.likes as u128
+            + analytics.shares as u128 * self.shares as u128
+            + analytics.comments as u128 * self.comments as u128
+            + watch.watch_seconds as u128 * self.watch_seconds as u128;
+        let score = weighted.min(u64::MAX as u128);
+        
+        if self.decay_half_life == 0 {
+            return score as u64;
+        }
+        let half_life = self.decay_half_life as u128;
+        let age = now.saturating_sub(created_at) as u128;
+        let halvings = age / half_life;
+        if halvings >= 64 {
+            return 0;
+        }
+        let halved = score >> halvings;
+        (halved - halved * (age % half_life) / (2 * half_life)) as u64
+    }
+}
+
+/// Schedule capping reward token minting, set by the platform admin. Time is
+/// cut into epochs of `epoch_duration` seconds from `start_time`; each epoch
+/// may mint `tokens_per_epoch`, halved every `halving_interval` epochs, and
+/// all epochs together at most `total_cap`. The default schedule mints nothing
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct EmissionSchedule {
+    pub start_time: u64,
+    /// Seconds per epoch, or zero to mint nothing
+    pub epoch_duration: u64,
+    pub tokens_per_epoch: u64,
+    /// Epochs after which the per-epoch allowance halves, or zero to never halve
+    pub halving_interval: u64,
+    pub total_cap: u64,
+}
+
+impl EmissionSchedule {
+    // start_time, epoch_duration, tokens_per_epoch, halving_interval, total_cap: 8 bytes each
+    pub const SPACE: usize = 5 * 8;
+    
+    /// Epoch `now` falls in, or `None` before the first epoch or when the
+    /// schedule mints nothing
+    pub fn epoch_at(&self, now: u64) -> Option<u64> {
+        if self.epoch_duration == 0 || now < self.start_time {
+            return None;
+        }
+        Some((now - self.start_time) / self.epoch_duration)
+    }
+    
+    /// Tokens epoch `epoch` may mint, after halving
+    pub fn epoch_allowance(&self, epoch: u64) -> u64 {
+        if self.halving_interval == 0 {
+            return self.tokens_per_epoch;
+        }
+        let halvings = epoch / self.halving_interval;
+        if halvings >= 64 {
+            return 0;
+        }
+        self.tokens_per_epoch >> halvings
+    }
+}
+
+/// New counters of one content in a `batch_update_analytics`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ContentAnalyticsUpdate {
+    /// Content account the counters are for, which must match the account
+    /// passed for the update
+    pub content: Pubkey,
+    pub views: u64,
+    pub likes: u64,
+    pub shares: u64,
+    pub comments: u64,
+    pub watch: WatchMetrics,
+}
+
+/// Analytics counters signed off-chain by an analytics oracle, relayed with
+/// `update_analytics`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AnalyticsAttestation {
+    /// Content account the counters are for
+    pub content: Pubkey,
+    pub views: u64,
+    pub likes: u64,
+    pub shares: u64,
+    pub comments: u64,
+    pub watch: WatchMetrics,
+    /// Must be greater than the content's `analytics_nonce`
+    pub nonce: u64,
+    /// Unix timestamp after which the attestation is rejected
+    pub expiry: u64,
+}
+
+impl AnalyticsAttestation {
+    /// Prefix of every attestation message, so oracle signatures over other
+    /// data can never be mistaken for an attestation
+    pub const MESSAGE_PREFIX: &'static [u8] = b"blocktok:analytics-attestation";
+    
+    /// The message the analytics oracle signs
+    pub fn message(&self) -> Vec<u8> {
+        let mut message = Self::MESSAGE_PREFIX.to_vec();
+        message.extend_from_slice(&self.try_to_vec().unwrap());
+        message
+    }
+}
+
+/// A single engagement event, one leaf of an engagement epoch's Merkle tree
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EngagementEvent {
+    pub content: Pubkey,
+    pub user: Pubkey,
+    pub kind: EngagementKind,
+    pub timestamp: u64,
+}
+
+impl EngagementEvent {
+    /// The event's Merkle leaf
+    pub fn leaf(&self) -> [u8; 32] {
+        hash_merkle_leaf(&self.try_to_vec().unwrap())
+    }
+}
+
+/// A single payout, one leaf of a reward distributor's Merkle tree
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DistributionEntry {
+    pub index: u32,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+impl DistributionEntry {
+    /// The entry's Merkle leaf
+    pub fn leaf(&self) -> [u8; 32] {
+        hash_merkle_leaf(&self.try_to_vec().unwrap())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EngagementKind {
+    View,
+    Like,
+    Share,
+    Comment,
+}
+
+/// Nonce and expiry an analytics oracle signed counters with; the rest of the
+/// `AnalyticsAttestation` comes from the `update_analytics` call
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct AttestationTerms {
+    pub nonce: u64,
+    pub expiry: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -404,4 +3448,100 @@ pub enum ContentError {
     
     #[msg("Invalid royalty distribution data")]
     InvalidRoyaltyData,
+    
+    #[msg("Content NFT must be burned before the content can be closed")]
+    NftNotBurned,
+    
+    #[msg("Too many royalty recipients")]
+    TooManyRoyaltyRecipients,
+    
+    #[msg("Platform fee cannot exceed 100%")]
+    InvalidPlatformFee,
+    
+    #[msg("Program is paused")]
+    ProgramPaused,
+    
+    #[msg("Category name must be 1 to 32 bytes")]
+    InvalidCategoryName,
+    
+    #[msg("Too many tags")]
+    TooManyTags,
+    
+    #[msg("Content is already tagged with this category")]
+    DuplicateTag,
+    
+    #[msg("Category is inactive")]
+    CategoryInactive,
+    
+    #[msg("Content is not published")]
+    ContentNotPublished,
+    
+    #[msg("Content status cannot change this way")]
+    InvalidStatusTransition,
+    
+    #[msg("Analytics counter overflow")]
+    AnalyticsOverflow,
+    
+    #[msg("Too many analytics oracles")]
+    TooManyAnalyticsOracles,
+    
+    #[msg("Invalid analytics attestation")]
+    InvalidAttestation,
+    
+    #[msg("Analytics attestation expired")]
+    AttestationExpired,
+    
+    #[msg("Analytics attestation already applied")]
+    AttestationReplayed,
+    
+    #[msg("Analytics counters cannot decrease")]
+    AnalyticsDecreased,
+    
+    #[msg("Analytics updated too frequently")]
+    AnalyticsUpdateTooFrequent,
+    
+    #[msg("Invalid watch progress")]
+    InvalidWatchProgress,
+    
+    #[msg("Invalid engagement epoch window")]
+    InvalidEpochWindow,
+    
+    #[msg("Engagement event outside epoch")]
+    EngagementOutsideEpoch,
+    
+    #[msg("Invalid Merkle proof")]
+    InvalidMerkleProof,
+    
+    #[msg("Too many batch updates")]
+    TooManyBatchUpdates,
+    
+    #[msg("Batch account does not match its update")]
+    BatchAccountMismatch,
+    
+    #[msg("Reward amount must be greater than zero")]
+    InvalidRewardAmount,
+    
+    #[msg("Reward credits would overflow")]
+    RewardOverflow,
+    
+    #[msg("No rewards to claim")]
+    NoRewardsToClaim,
+    
+    #[msg("Invalid reward distributor")]
+    InvalidDistributor,
+    
+    #[msg("Invalid distribution entry")]
+    InvalidDistributionEntry,
+    
+    #[msg("Distribution already claimed")]
+    DistributionAlreadyClaimed,
+    
+    #[msg("Distribution expired")]
+    DistributionExpired,
+    
+    #[msg("Distribution not clawable")]
+    DistributionNotClawable,
+    
+    #[msg("Emission limit exceeded")]
+    EmissionLimitExceeded,
 } 
\ No newline at end of file
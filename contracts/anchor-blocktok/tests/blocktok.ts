@@ -2,7 +2,16 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Blocktok } from "../target/types/blocktok";
 import { expect } from "chai";
-import { Keypair, PublicKey } from "@solana/web3.js";
+import { Ed25519Program, Keypair, PublicKey, SYSVAR_INSTRUCTIONS_PUBKEY } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+import { createHash } from "crypto";
+import { analyticsTimeSeries, fetchAnalyticsTimeSeries } from "../app/analytics-history";
 
 describe("blocktok", () => {
   // Configure the client to use the local cluster
@@ -16,17 +25,59 @@ describe("blocktok", () => {
   const description = "Test content description";
   const contentUrl = "https://example.com/content";
   const contentType = "video";
+  const noWatch = { watchSeconds: new anchor.BN(0), totalCompletionBps: new anchor.BN(0), replays: new anchor.BN(0) };
 
   // Create content account PDA
   const [contentPda] = PublicKey.findProgramAddressSync(
     [
       Buffer.from("content"),
       creator.publicKey.toBuffer(),
-      Buffer.from(contentId),
+      createHash("sha256").update(contentId).digest(),
     ],
     program.programId
   );
 
+  // Creator profile PDA and the catalogue index PDA for the first content
+  const [creatorProfilePda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("creator_profile"), creator.publicKey.toBuffer()],
+    program.programId
+  );
+  const [contentIndexPda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("content_index"),
+      creator.publicKey.toBuffer(),
+      new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+    ],
+    program.programId
+  );
+
+  // Platform config PDA and the program data account holding the upgrade authority
+  const [configPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("platform_config")],
+    program.programId
+  );
+  // Category used to tag the content, and the index marking the content as tagged
+  const category = "Tech";
+  const [categoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("category"), Buffer.from(category)],
+    program.programId
+  );
+  const [categoryIndexPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("category_index"), categoryPda.toBuffer(), contentPda.toBuffer()],
+    program.programId
+  );
+
+  // Analytics history of the content
+  const [historyPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("analytics_history"), contentPda.toBuffer()],
+    program.programId
+  );
+
+  const [programDataPda] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
+
   it("Airdrop SOL to creator", async () => {
     const airdropSignature = await provider.connection.requestAirdrop(
       creator.publicKey,
@@ -39,6 +90,156 @@ describe("blocktok", () => {
     expect(balance).to.be.greaterThan(0);
   });
 
+  it("Content instructions run before the config is initialized", async () => {
+    // An uninitialized config reads as nothing paused, like in the native program
+    expect(await provider.connection.getAccountInfo(configPda)).to.equal(null);
+
+    const wallet = provider.wallet.publicKey;
+    const preConfigContentId = "pre-config-content";
+    const [preConfigContentPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("content"),
+        wallet.toBuffer(),
+        createHash("sha256").update(preConfigContentId).digest(),
+      ],
+      program.programId
+    );
+    const [walletProfilePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("creator_profile"), wallet.toBuffer()],
+      program.programId
+    );
+    const [walletContentIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("content_index"), wallet.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .initializeContent(preConfigContentId, title, description, contentUrl, contentType, [])
+      .accounts({
+        creator: wallet,
+        content: preConfigContentPda,
+        creatorProfile: walletProfilePda,
+        contentIndex: walletContentIndexPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        config: configPda,
+      })
+      .rpc();
+    await program.methods
+      .publish(null)
+      .accounts({ creator: wallet, content: preConfigContentPda, config: configPda })
+      .rpc();
+
+    const contentAccount = await program.account.content.fetch(preConfigContentPda);
+    expect(contentAccount.status).to.deep.equal({ published: {} });
+  });
+
+  it("Initialize platform config", async () => {
+    const initializeConfig = (admin: PublicKey) =>
+      program.methods
+        .initializeConfig(provider.wallet.publicKey, 250, new anchor.BN(0))
+        .accounts({
+          admin,
+          config: configPda,
+          program: program.programId,
+          programData: programDataPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        });
+
+    // Only the upgrade authority may create the config
+    try {
+      await initializeConfig(creator.publicKey).signers([creator]).rpc();
+      expect.fail("initializing the config without the upgrade authority should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("Unauthorized");
+    }
+
+    // The provider wallet deployed the program, so it is the upgrade authority
+    await initializeConfig(provider.wallet.publicKey).rpc();
+
+    const config = await program.account.platformConfig.fetch(configPda);
+    expect(config.admin.toString()).to.equal(provider.wallet.publicKey.toString());
+    expect(config.treasury.toString()).to.equal(provider.wallet.publicKey.toString());
+    expect(config.platformFeeBps).to.equal(250);
+    expect(config.paused).to.equal(false);
+  });
+
+  it("Update config and transfer admin", async () => {
+    const updateFee = (admin: Keypair | null, platformFeeBps: number) => {
+      const builder = program.methods
+        .updateConfig(null, platformFeeBps, null, null, null, null, null)
+        .accounts({
+          admin: admin ? admin.publicKey : provider.wallet.publicKey,
+          config: configPda,
+        });
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    };
+
+    // Only the admin can update the config
+    try {
+      await updateFee(creator, 500);
+      expect.fail("updating the config as a non-admin should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("Unauthorized");
+    }
+    await updateFee(null, 300);
+    let config = await program.account.platformConfig.fetch(configPda);
+    expect(config.platformFeeBps).to.equal(300);
+
+    const transferAdmin = (admin: Keypair | null, newAdmin: PublicKey) => {
+      const builder = program.methods.transferAdmin(newAdmin).accounts({
+        admin: admin ? admin.publicKey : provider.wallet.publicKey,
+        config: configPda,
+      });
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    };
+    const acceptAdmin = (pendingAdmin: Keypair) =>
+      program.methods
+        .acceptAdmin()
+        .accounts({
+          pendingAdmin: pendingAdmin.publicKey,
+          config: configPda,
+        })
+        .signers([pendingAdmin])
+        .rpc();
+
+    // The nominated admin takes over only once they accept
+    const newAdmin = Keypair.generate();
+    await transferAdmin(null, newAdmin.publicKey);
+    config = await program.account.platformConfig.fetch(configPda);
+    expect(config.admin.toString()).to.equal(provider.wallet.publicKey.toString());
+    expect(config.pendingAdmin.toString()).to.equal(newAdmin.publicKey.toString());
+
+    try {
+      await acceptAdmin(creator);
+      expect.fail("accepting as someone other than the nominee should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("Unauthorized");
+    }
+    await acceptAdmin(newAdmin);
+    config = await program.account.platformConfig.fetch(configPda);
+    expect(config.admin.toString()).to.equal(newAdmin.publicKey.toString());
+    expect(config.pendingAdmin).to.equal(null);
+
+    // The previous admin lost their rights; hand them back for the remaining tests
+    try {
+      await updateFee(null, 250);
+      expect.fail("the previous admin should no longer update the config");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("Unauthorized");
+    }
+    await updateFee(newAdmin, 250);
+    await transferAdmin(newAdmin, provider.wallet.publicKey);
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        pendingAdmin: provider.wallet.publicKey,
+        config: configPda,
+      })
+      .rpc();
+    config = await program.account.platformConfig.fetch(configPda);
+    expect(config.admin.toString()).to.equal(provider.wallet.publicKey.toString());
+  });
+
   it("Initialize content", async () => {
     await program.methods
       .initializeContent(
@@ -46,12 +247,16 @@ describe("blocktok", () => {
         title,
         description,
         contentUrl,
-        contentType
+        contentType,
+        []
       )
       .accounts({
         creator: creator.publicKey,
         content: contentPda,
+        creatorProfile: creatorProfilePda,
+        contentIndex: contentIndexPda,
         systemProgram: anchor.web3.SystemProgram.programId,
+        config: configPda,
       })
       .signers([creator])
       .rpc();
@@ -61,6 +266,7 @@ describe("blocktok", () => {
     
     // Verify the content data
     expect(contentAccount.creator.toString()).to.equal(creator.publicKey.toString());
+    expect(contentAccount.status).to.deep.equal({ draft: {} });
     expect(contentAccount.contentId).to.equal(contentId);
     expect(contentAccount.title).to.equal(title);
     expect(contentAccount.description).to.equal(description);
@@ -71,6 +277,45 @@ describe("blocktok", () => {
     expect(contentAccount.analytics.likes.toString()).to.equal("0");
     expect(contentAccount.analytics.shares.toString()).to.equal("0");
     expect(contentAccount.analytics.comments.toString()).to.equal("0");
+
+    // The content should be the first entry in the creator's catalogue
+    const creatorProfile = await program.account.creatorProfile.fetch(creatorProfilePda);
+    expect(creatorProfile.contentCount.toString()).to.equal("1");
+    const contentIndex = await program.account.contentIndex.fetch(contentIndexPda);
+    expect(contentIndex.content.toString()).to.equal(contentPda.toString());
+  });
+
+  it("Publish content", async () => {
+    await program.methods
+      .publish(null)
+      .accounts({
+        creator: creator.publicKey,
+        content: contentPda,
+        config: configPda,
+      })
+      .signers([creator])
+      .rpc();
+
+    const contentAccount = await program.account.content.fetch(contentPda);
+    expect(contentAccount.status).to.deep.equal({ published: {} });
+  });
+
+  it("Initialize analytics history", async () => {
+    await program.methods
+      .initializeAnalyticsHistory({ hourly: {} })
+      .accounts({
+        creator: creator.publicKey,
+        content: contentPda,
+        history: historyPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        config: configPda,
+      })
+      .signers([creator])
+      .rpc();
+
+    const historyAccount = await program.account.analyticsHistory.fetch(historyPda);
+    expect(historyAccount.granularity).to.deep.equal({ hourly: {} });
+    expect(historyAccount.buckets.every((bucket) => bucket.start.isZero())).to.be.true;
   });
 
   it("Update analytics", async () => {
@@ -78,17 +323,26 @@ describe("blocktok", () => {
     const likes = new anchor.BN(500);
     const shares = new anchor.BN(200);
     const comments = new anchor.BN(100);
+    const watch = {
+      watchSeconds: new anchor.BN(12000),
+      totalCompletionBps: new anchor.BN(6_500_000),
+      replays: new anchor.BN(150),
+    };
     
     await program.methods
       .updateAnalytics(
         views,
         likes,
         shares,
-        comments
+        comments,
+        watch,
+        null
       )
       .accounts({
         authority: creator.publicKey,
         content: contentPda,
+        config: configPda,
+        history: historyPda,
       })
       .signers([creator])
       .rpc();
@@ -101,6 +355,1181 @@ describe("blocktok", () => {
     expect(contentAccount.analytics.likes.toString()).to.equal(likes.toString());
     expect(contentAccount.analytics.shares.toString()).to.equal(shares.toString());
     expect(contentAccount.analytics.comments.toString()).to.equal(comments.toString());
+    expect(contentAccount.watchMetrics.watchSeconds.toNumber()).to.equal(12000);
+    expect(contentAccount.watchMetrics.totalCompletionBps.toNumber()).to.equal(6_500_000);
+    expect(contentAccount.watchMetrics.replays.toNumber()).to.equal(150);
+  });
+
+  it("Increment analytics", async () => {
+    await program.methods
+      .incrementAnalytics(new anchor.BN(10), new anchor.BN(5), new anchor.BN(0), new anchor.BN(1), {
+        watchSeconds: new anchor.BN(30),
+        totalCompletionBps: new anchor.BN(0),
+        replays: new anchor.BN(1),
+      })
+      .accounts({
+        authority: creator.publicKey,
+        content: contentPda,
+        config: configPda,
+        history: historyPda,
+      })
+      .signers([creator])
+      .rpc();
+
+    // Deltas are added on top of the previously set counters
+    const contentAccount = await program.account.content.fetch(contentPda);
+    expect(contentAccount.analytics.views.toNumber()).to.equal(1010);
+    expect(contentAccount.analytics.likes.toNumber()).to.equal(505);
+    expect(contentAccount.analytics.shares.toNumber()).to.equal(200);
+    expect(contentAccount.analytics.comments.toNumber()).to.equal(101);
+    expect(contentAccount.watchMetrics.watchSeconds.toNumber()).to.equal(12030);
+    expect(contentAccount.watchMetrics.replays.toNumber()).to.equal(151);
+
+    // Both updates so far were recorded in the history's hourly buckets
+    const historyAccount = await program.account.analyticsHistory.fetch(historyPda);
+    const total = (field: string) =>
+      historyAccount.buckets.reduce((sum, bucket) => sum + bucket[field].toNumber(), 0);
+    expect(total("views")).to.equal(1010);
+    expect(total("comments")).to.equal(101);
+  });
+
+  it("Decode the analytics history into a time series", async () => {
+    // Only slots holding a bucket are returned, oldest first, on hour boundaries
+    const series = await fetchAnalyticsTimeSeries(program, contentPda);
+    expect(series.length).to.be.greaterThan(0);
+    series.forEach((point, i) => {
+      expect(point.start % 3600).to.equal(0);
+      if (i > 0) {
+        expect(point.start).to.be.greaterThan(series[i - 1].start);
+      }
+    });
+    expect(series.reduce((sum, point) => sum + point.views.toNumber(), 0)).to.equal(1010);
+
+    // Slots are ordered by bucket start rather than by their place in the ring
+    const bucket = (start: number, views: number) => ({
+      start: new anchor.BN(start),
+      views: new anchor.BN(views),
+      likes: new anchor.BN(0),
+      shares: new anchor.BN(0),
+      comments: new anchor.BN(0),
+    });
+    const decoded = analyticsTimeSeries([bucket(7200, 3), bucket(0, 0), bucket(3600, 2)]);
+    expect(decoded.map((point) => [point.start, point.views.toNumber()])).to.deep.equal([
+      [3600, 2],
+      [7200, 3],
+    ]);
+
+    // Content without a history has no time series
+    expect(await fetchAnalyticsTimeSeries(program, Keypair.generate().publicKey)).to.equal(null);
+  });
+
+  it("Analytics oracle reports analytics", async () => {
+    const setAnalyticsOracles = (oracles: anchor.web3.PublicKey[], allowCreatorAnalytics: boolean) =>
+      program.methods
+        .setAnalyticsOracles(oracles, allowCreatorAnalytics)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: configPda,
+        })
+        .rpc();
+
+    // Designate the provider wallet as the only analytics authority
+    await setAnalyticsOracles([provider.wallet.publicKey], false);
+
+    await program.methods
+      .incrementAnalytics(new anchor.BN(1), new anchor.BN(0), new anchor.BN(0), new anchor.BN(0), noWatch)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        content: contentPda,
+        config: configPda,
+        history: historyPda,
+      })
+      .rpc();
+
+    const contentAccount = await program.account.content.fetch(contentPda);
+    expect(contentAccount.analytics.views.toNumber()).to.equal(1011);
+
+    // The creator can no longer self-report
+    try {
+      await program.methods
+        .incrementAnalytics(new anchor.BN(1), new anchor.BN(0), new anchor.BN(0), new anchor.BN(0), noWatch)
+        .accounts({
+          authority: creator.publicKey,
+          content: contentPda,
+          config: configPda,
+          history: historyPda,
+        })
+        .signers([creator])
+        .rpc();
+      expect.fail("creator should not be able to report analytics");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("Unauthorized");
+    }
+
+    // Restore creator reporting so the remaining tests can run
+    await setAnalyticsOracles([], true);
+    const config = await program.account.platformConfig.fetch(configPda);
+    expect(config.analyticsOracles).to.be.empty;
+    expect(config.allowCreatorAnalytics).to.be.true;
+  });
+
+  it("Relay a signed analytics attestation", async () => {
+    const oracle = Keypair.generate();
+    const setAnalyticsOracles = (oracles: PublicKey[], allowCreatorAnalytics: boolean) =>
+      program.methods
+        .setAnalyticsOracles(oracles, allowCreatorAnalytics)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: configPda,
+        })
+        .rpc();
+
+    await setAnalyticsOracles([oracle.publicKey], true);
+
+    // Borsh `AnalyticsAttestation` behind the message prefix, signed by the oracle
+    const u64 = (value: number) => new anchor.BN(value).toArrayLike(Buffer, "le", 8);
+    const nonce = 1;
+    const expiry = Math.floor(Date.now() / 1000) + 600;
+    const message = Buffer.concat([
+      Buffer.from("blocktok:analytics-attestation"),
+      contentPda.toBuffer(),
+      u64(2000),
+      u64(600),
+      u64(250),
+      u64(120),
+      u64(20000),
+      u64(13_000_000),
+      u64(300),
+      u64(nonce),
+      u64(expiry),
+    ]);
+    const verifyInstruction = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: oracle.secretKey,
+      message,
+    });
+
+    // The provider wallet relays it without being an oracle itself
+    await program.methods
+      .updateAnalytics(
+        new anchor.BN(2000),
+        new anchor.BN(600),
+        new anchor.BN(250),
+        new anchor.BN(120),
+        { watchSeconds: new anchor.BN(20000), totalCompletionBps: new anchor.BN(13_000_000), replays: new anchor.BN(300) },
+        { nonce: new anchor.BN(nonce), expiry: new anchor.BN(expiry) }
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        content: contentPda,
+        config: configPda,
+        history: historyPda,
+      })
+      .remainingAccounts([
+        { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isWritable: false, isSigner: false },
+      ])
+      .preInstructions([verifyInstruction])
+      .rpc();
+
+    const contentAccount = await program.account.content.fetch(contentPda);
+    expect(contentAccount.analytics.views.toNumber()).to.equal(2000);
+    expect(contentAccount.analyticsNonce.toNumber()).to.equal(nonce);
+
+    await setAnalyticsOracles([], true);
+  });
+
+  it("Analytics counters cannot decrease", async () => {
+    try {
+      await program.methods
+        .updateAnalytics(new anchor.BN(1), new anchor.BN(1), new anchor.BN(1), new anchor.BN(1), noWatch, null)
+        .accounts({
+          authority: creator.publicKey,
+          content: contentPda,
+          config: configPda,
+          history: historyPda,
+        })
+        .signers([creator])
+        .rpc();
+      expect.fail("update analytics should not decrease counters");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("AnalyticsDecreased");
+    }
+  });
+
+  it("Record a view once per viewer", async () => {
+    const viewer = provider.wallet.publicKey;
+    const [viewReceiptPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("view_receipt"), contentPda.toBuffer(), viewer.toBuffer()],
+      program.programId
+    );
+    const [userRewardsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_rewards"), viewer.toBuffer()],
+      program.programId
+    );
+    const recordView = (watchProgressBps: number) =>
+      program.methods
+        .recordView(watchProgressBps)
+        .accounts({
+          viewer,
+          content: contentPda,
+          viewReceipt: viewReceiptPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          config: configPda,
+          history: historyPda,
+          userRewards: userRewardsPda,
+        })
+        .rpc();
+
+    const before = await program.account.content.fetch(contentPda);
+
+    // Only the first view counts, later ones are replays that advance the watch progress
+    await recordView(2500);
+    await recordView(7500);
+
+    const contentAccount = await program.account.content.fetch(contentPda);
+    expect(contentAccount.analytics.views.toNumber()).to.equal(before.analytics.views.toNumber() + 1);
+    expect(contentAccount.watchMetrics.replays.toNumber()).to.equal(before.watchMetrics.replays.toNumber() + 1);
+    expect(contentAccount.watchMetrics.totalCompletionBps.toNumber()).to.equal(
+      before.watchMetrics.totalCompletionBps.toNumber() + 7500
+    );
+
+    const receipt = await program.account.viewReceipt.fetch(viewReceiptPda);
+    expect(receipt.viewer.toString()).to.equal(viewer.toString());
+    expect(receipt.firstSeenAt.toNumber()).to.be.greaterThan(0);
+    expect(receipt.watchProgressBps).to.equal(7500);
+
+    // Views earn nothing by default, so no rewards account is created
+    expect(await provider.connection.getAccountInfo(userRewardsPda)).to.equal(null);
+
+    try {
+      await recordView(10001);
+      expect.fail("record view should reject progress past the end");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidWatchProgress");
+    }
+  });
+
+  it("Load content written before statuses existed", async () => {
+    // Loaded from tests/fixtures/legacy-content.json, in the original layout without a status
+    const legacyContent = new PublicKey("A9jxeQZv9YL5A4mAXF5XSD4SB8AxurAcRkwRVYwAzWNa");
+    const viewer = provider.wallet.publicKey;
+    const [viewReceiptPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("view_receipt"), legacyContent.toBuffer(), viewer.toBuffer()],
+      program.programId
+    );
+    const [legacyHistoryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("analytics_history"), legacyContent.toBuffer()],
+      program.programId
+    );
+    const [userRewardsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_rewards"), viewer.toBuffer()],
+      program.programId
+    );
+
+    let contentAccount = await program.account.content.fetch(legacyContent);
+    expect(contentAccount.status).to.deep.equal({ published: {} });
+    expect(contentAccount.tags).to.deep.equal([]);
+    expect(contentAccount.analytics.views.toNumber()).to.equal(42);
+
+    // Published content takes views
+    await program.methods
+      .recordView(5000)
+      .accounts({
+        viewer,
+        content: legacyContent,
+        viewReceipt: viewReceiptPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        config: configPda,
+        history: legacyHistoryPda,
+        userRewards: userRewardsPda,
+      })
+      .rpc();
+
+    contentAccount = await program.account.content.fetch(legacyContent);
+    expect(contentAccount.status).to.deep.equal({ published: {} });
+    expect(contentAccount.analytics.views.toNumber()).to.equal(43);
+  });
+
+  it("Batch update analytics", async () => {
+    const before = await program.account.content.fetch(contentPda);
+    const update = {
+      content: contentPda,
+      views: before.analytics.views.addn(10),
+      likes: before.analytics.likes,
+      shares: before.analytics.shares,
+      comments: before.analytics.comments.addn(2),
+      watch: before.watchMetrics,
+    };
+
+    await program.methods
+      .batchUpdateAnalytics([update])
+      .accounts({
+        authority: creator.publicKey,
+        config: configPda,
+      })
+      .remainingAccounts([
+        { pubkey: contentPda, isSigner: false, isWritable: true },
+        { pubkey: historyPda, isSigner: false, isWritable: true },
+      ])
+      .signers([creator])
+      .rpc();
+
+    const contentAccount = await program.account.content.fetch(contentPda);
+    expect(contentAccount.analytics.views.toNumber()).to.equal(before.analytics.views.toNumber() + 10);
+    expect(contentAccount.analytics.comments.toNumber()).to.equal(before.analytics.comments.toNumber() + 2);
+
+    // The accounts must follow the updates
+    try {
+      await program.methods
+        .batchUpdateAnalytics([{ ...update, content: historyPda }])
+        .accounts({
+          authority: creator.publicKey,
+          config: configPda,
+        })
+        .remainingAccounts([
+          { pubkey: contentPda, isSigner: false, isWritable: true },
+          { pubkey: historyPda, isSigner: false, isWritable: true },
+        ])
+        .signers([creator])
+        .rpc();
+      expect.fail("batch update should reject mismatched accounts");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("BatchAccountMismatch");
+    }
+  });
+
+  it("Score engagement with configurable weights", async () => {
+    const weights = {
+      views: new anchor.BN(1),
+      likes: new anchor.BN(5),
+      shares: new anchor.BN(10),
+      comments: new anchor.BN(3),
+      watchSeconds: new anchor.BN(1),
+      decayHalfLife: new anchor.BN(0),
+    };
+    await program.methods
+      .updateConfig(null, null, null, null, weights, null, null)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config: configPda,
+      })
+      .rpc();
+
+    // Scores are recomputed on the next analytics change
+    await program.methods
+      .incrementAnalytics(new anchor.BN(1), new anchor.BN(0), new anchor.BN(0), new anchor.BN(0), noWatch)
+      .accounts({
+        authority: creator.publicKey,
+        content: contentPda,
+        config: configPda,
+        history: historyPda,
+      })
+      .signers([creator])
+      .rpc();
+
+    const contentAccount = await program.account.content.fetch(contentPda);
+    const { views, likes, shares, comments } = contentAccount.analytics;
+    const expected = views
+      .add(likes.muln(5))
+      .add(shares.muln(10))
+      .add(comments.muln(3))
+      .add(contentAccount.watchMetrics.watchSeconds);
+    expect(contentAccount.engagementScore.toString()).to.equal(expected.toString());
+  });
+
+  it("Commit and verify an engagement epoch", async () => {
+    const setAnalyticsOracles = (oracles: PublicKey[], allowCreatorAnalytics: boolean) =>
+      program.methods
+        .setAnalyticsOracles(oracles, allowCreatorAnalytics)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: configPda,
+        })
+        .rpc();
+    await setAnalyticsOracles([provider.wallet.publicKey], true);
+
+    // Leaves are sha256(0 || borsh(event)), nodes sha256(1 || sorted children)
+    const leaf = (user: PublicKey, kind: number, timestamp: number) => {
+      const timestampBytes = Buffer.alloc(8);
+      timestampBytes.writeBigUInt64LE(BigInt(timestamp));
+      return createHash("sha256")
+        .update(Buffer.concat([Buffer.from([0]), contentPda.toBuffer(), user.toBuffer(), Buffer.from([kind]), timestampBytes]))
+        .digest();
+    };
+    const node = (left: Buffer, right: Buffer) =>
+      createHash("sha256")
+        .update(Buffer.concat([Buffer.from([1]), ...[left, right].sort(Buffer.compare)]))
+        .digest();
+
+    const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+    const startTime = now - 3600;
+    const endTime = now - 60;
+    const user = Keypair.generate().publicKey;
+    const viewLeaf = leaf(user, 0, startTime + 10);
+    const likeLeaf = leaf(user, 1, startTime + 20);
+    const merkleRoot = node(viewLeaf, likeLeaf);
+
+    const epoch = new anchor.BN(1);
+    const [engagementEpochPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("engagement_epoch"), epoch.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [totalsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("epoch_totals"), engagementEpochPda.toBuffer(), contentPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .commitEngagementEpoch(epoch, new anchor.BN(startTime), new anchor.BN(endTime), [...merkleRoot], new anchor.BN(2))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        engagementEpoch: engagementEpochPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        config: configPda,
+      })
+      .rpc();
+    await program.methods
+      .recordEpochTotals(new anchor.BN(1), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        engagementEpoch: engagementEpochPda,
+        content: contentPda,
+        totals: totalsPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        config: configPda,
+      })
+      .rpc();
+
+    const totals = await program.account.epochContentTotals.fetch(totalsPda);
+    expect(totals.views.toNumber()).to.equal(1);
+    expect(totals.likes.toNumber()).to.equal(1);
+
+    // The like is proven by its sibling, the view
+    const likeEvent = { content: contentPda, user, kind: { like: {} }, timestamp: new anchor.BN(startTime + 20) };
+    await program.methods
+      .verifyEngagement(likeEvent, [[...viewLeaf]])
+      .accounts({ engagementEpoch: engagementEpochPda })
+      .rpc();
+
+    try {
+      await program.methods
+        .verifyEngagement({ ...likeEvent, kind: { share: {} } }, [[...viewLeaf]])
+        .accounts({ engagementEpoch: engagementEpochPda })
+        .rpc();
+      expect.fail("verify engagement should reject an altered event");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidMerkleProof");
+    }
+
+    await setAnalyticsOracles([], true);
+  });
+
+  it("Update content metadata", async () => {
+    const newTitle = "Updated Test Content";
+    const newContentUrl = "https://example.com/content/v2";
+
+    await program.methods
+      .updateContentMetadata(newTitle, null, newContentUrl, null, null)
+      .accounts({
+        creator: creator.publicKey,
+        content: contentPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        config: configPda,
+      })
+      .signers([creator])
+      .rpc();
+
+    // Fetch the updated content account
+    const contentAccount = await program.account.content.fetch(contentPda);
+
+    // Only the provided fields should change
+    expect(contentAccount.title).to.equal(newTitle);
+    expect(contentAccount.description).to.equal(description);
+    expect(contentAccount.contentUrl).to.equal(newContentUrl);
+    expect(contentAccount.contentType).to.equal(contentType);
+  });
+
+  it("Tag content with a category", async () => {
+    await program.methods
+      .createCategory(category)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config: configPda,
+        category: categoryPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .updateContentMetadata(null, null, null, null, [category])
+      .accounts({
+        creator: creator.publicKey,
+        content: contentPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        config: configPda,
+      })
+      .remainingAccounts([
+        { pubkey: categoryPda, isWritable: true, isSigner: false },
+        { pubkey: categoryIndexPda, isWritable: true, isSigner: false },
+      ])
+      .signers([creator])
+      .rpc();
+
+    const contentAccount = await program.account.content.fetch(contentPda);
+    expect(contentAccount.tags).to.deep.equal([category]);
+
+    const categoryAccount = await program.account.category.fetch(categoryPda);
+    expect(categoryAccount.contentCount.toNumber()).to.equal(1);
+
+    const categoryIndex = await program.account.categoryIndex.fetch(categoryIndexPda);
+    expect(categoryIndex.content.toString()).to.equal(contentPda.toString());
+
+    // Names longer than a seed are rejected rather than derived
+    const longName = "x".repeat(33);
+    try {
+      await program.methods
+        .updateContentMetadata(null, null, null, null, [category, longName])
+        .accounts({
+          creator: creator.publicKey,
+          content: contentPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          config: configPda,
+        })
+        .signers([creator])
+        .rpc();
+      expect.fail("tagging with an over-long name should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidCategoryName");
+    }
+    try {
+      await program.methods
+        .setCategoryActive(longName, false)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: configPda,
+          category: categoryPda,
+        })
+        .rpc();
+      expect.fail("deactivating an over-long name should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidCategoryName");
+    }
+  });
+
+  it("Set royalty distribution", async () => {
+    const maxRoyaltyRecipients = 16;
+    const recipients = [Keypair.generate().publicKey, Keypair.generate().publicKey];
+    const setRoyaltyDistribution = (percentages: number[], accounts: PublicKey[]) =>
+      program.methods
+        .setRoyaltyDistribution(percentages)
+        .accounts({
+          creator: creator.publicKey,
+          content: contentPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          config: configPda,
+        })
+        .remainingAccounts(accounts.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false })))
+        .signers([creator])
+        .rpc();
+
+    // The account grows to fit two recipients, then shrinks back for one
+    await setRoyaltyDistribution([7000, 3000], recipients);
+    let contentAccount = await program.account.content.fetch(contentPda);
+    expect(contentAccount.royaltyRecipients.map((recipient) => recipient.percentage)).to.deep.equal([7000, 3000]);
+    const grownSize = (await provider.connection.getAccountInfo(contentPda)).data.length;
+
+    await setRoyaltyDistribution([10000], [recipients[1]]);
+    contentAccount = await program.account.content.fetch(contentPda);
+    expect(contentAccount.royaltyRecipients.length).to.equal(1);
+    expect(contentAccount.royaltyRecipients[0].pubkey.toString()).to.equal(recipients[1].toString());
+    const shrunkSize = (await provider.connection.getAccountInfo(contentPda)).data.length;
+    expect(shrunkSize).to.be.lessThan(grownSize);
+
+    try {
+      const tooMany = Array.from({ length: maxRoyaltyRecipients + 1 }, () => Keypair.generate().publicKey);
+      await setRoyaltyDistribution(new Array(tooMany.length).fill(0), tooMany);
+      expect.fail("setting more than the maximum recipients should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("TooManyRoyaltyRecipients");
+    }
+  });
+
+  it("Pause blocks content instructions", async () => {
+    const setPauseState = (paused: boolean, pausedInstructions: number) =>
+      program.methods
+        .setPauseState(paused, new anchor.BN(pausedInstructions))
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: configPda,
+        })
+        .rpc();
+
+    // Pause only analytics updates (bit 2)
+    await setPauseState(false, 1 << 2);
+
+    try {
+      await program.methods
+        .updateAnalytics(new anchor.BN(1), new anchor.BN(1), new anchor.BN(1), new anchor.BN(1), noWatch, null)
+        .accounts({
+          authority: creator.publicKey,
+          content: contentPda,
+          config: configPda,
+          history: historyPda,
+        })
+        .signers([creator])
+        .rpc();
+      expect.fail("update analytics should fail while paused");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("ProgramPaused");
+    }
+
+    // Unpause so the remaining tests can run
+    await setPauseState(false, 0);
+    const config = await program.account.platformConfig.fetch(configPda);
+    expect(config.pausedInstructions.toNumber()).to.equal(0);
+  });
+
+  it("Close content", async () => {
+    const balanceBefore = await provider.connection.getBalance(creator.publicKey);
+
+    await program.methods
+      .closeContent()
+      .accounts({
+        creator: creator.publicKey,
+        content: contentPda,
+        nftMint: null,
+        config: configPda,
+        history: historyPda,
+      })
+      .remainingAccounts([
+        { pubkey: categoryPda, isWritable: true, isSigner: false },
+        { pubkey: categoryIndexPda, isWritable: true, isSigner: false },
+      ])
+      .signers([creator])
+      .rpc();
+
+    // The content account should be gone and its rent returned to the creator
+    const contentAccount = await provider.connection.getAccountInfo(contentPda);
+    expect(contentAccount).to.equal(null);
+
+    const balanceAfter = await provider.connection.getBalance(creator.publicKey);
+    expect(balanceAfter).to.be.greaterThan(balanceBefore);
+
+    // The content should no longer be counted in its category
+    const categoryAccount = await program.account.category.fetch(categoryPda);
+    expect(categoryAccount.contentCount.toNumber()).to.equal(0);
+  });
+
+  it("Mint rewards only through an analytics oracle", async () => {
+    const [rewardMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_mint")],
+      program.programId
+    );
+    const [rewardMintAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_mint_authority")],
+      program.programId
+    );
+    const setAnalyticsOracles = (oracles: PublicKey[], allowCreatorAnalytics: boolean) =>
+      program.methods
+        .setAnalyticsOracles(oracles, allowCreatorAnalytics)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: configPda,
+        })
+        .rpc();
+
+    await program.methods
+      .initializeRewardMint()
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config: configPda,
+        rewardMint: rewardMintPda,
+        rewardMintAuthority: rewardMintAuthorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    const creatorTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      (provider.wallet as anchor.Wallet).payer,
+      rewardMintPda,
+      creator.publicKey
+    );
+    const mintRewards = (authority: PublicKey) =>
+      program.methods.mintRewards(new anchor.BN(500)).accounts({
+        authority,
+        config: configPda,
+        rewardMint: rewardMintPda,
+        rewardMintAuthority: rewardMintAuthorityPda,
+        recipient: creatorTokenAccount.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      });
+
+    // Users cannot mint rewards to themselves
+    try {
+      await mintRewards(creator.publicKey).signers([creator]).rpc();
+      expect.fail("a creator should not be able to mint rewards");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("Unauthorized");
+    }
+
+    await setAnalyticsOracles([provider.wallet.publicKey], true);
+
+    // Nothing is minted until the admin sets an emission schedule
+    try {
+      await mintRewards(provider.wallet.publicKey).rpc();
+      expect.fail("minting without an emission schedule should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("EmissionLimitExceeded");
+    }
+
+    const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+    const emissionSchedule = {
+      startTime: new anchor.BN(now - 60),
+      epochDuration: new anchor.BN(86400),
+      tokensPerEpoch: new anchor.BN(2000),
+      halvingInterval: new anchor.BN(0),
+      totalCap: new anchor.BN(1250),
+    };
+    await program.methods
+      .updateConfig(null, null, null, null, null, null, emissionSchedule)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config: configPda,
+      })
+      .rpc();
+    await mintRewards(provider.wallet.publicKey).rpc();
+
+    let tokenAccount = await getAccount(provider.connection, creatorTokenAccount.address);
+    expect(Number(tokenAccount.amount)).to.equal(500);
+
+    // Credits accrued by an oracle are claimed into the user's associated token account
+    const [userRewardsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_rewards"), creator.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .accrueRewards(new anchor.BN(250))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        config: configPda,
+        user: creator.publicKey,
+        userRewards: userRewardsPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    await setAnalyticsOracles([], true);
+
+    const claimRewards = () =>
+      program.methods
+        .claimRewards()
+        .accounts({
+          user: creator.publicKey,
+          config: configPda,
+          userRewards: userRewardsPda,
+          rewardMint: rewardMintPda,
+          rewardMintAuthority: rewardMintAuthorityPda,
+          tokenAccount: creatorTokenAccount.address,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([creator])
+        .rpc();
+    await claimRewards();
+
+    tokenAccount = await getAccount(provider.connection, creatorTokenAccount.address);
+    expect(Number(tokenAccount.amount)).to.equal(750);
+    const userRewards = await program.account.userRewards.fetch(userRewardsPda);
+    expect(userRewards.claimed.toNumber()).to.equal(250);
+    const config = await program.account.platformConfig.fetch(configPda);
+    expect(config.emittedTotal.toNumber()).to.equal(750);
+
+    try {
+      await claimRewards();
+      expect.fail("claiming twice should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("NoRewardsToClaim");
+    }
+  });
+
+  it("Claim epoch payouts from a Merkle distributor", async () => {
+    const [rewardMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_mint")],
+      program.programId
+    );
+    const [rewardMintAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_mint_authority")],
+      program.programId
+    );
+    const setAnalyticsOracles = (oracles: PublicKey[], allowCreatorAnalytics: boolean) =>
+      program.methods
+        .setAnalyticsOracles(oracles, allowCreatorAnalytics)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: configPda,
+        })
+        .rpc();
+    await setAnalyticsOracles([provider.wallet.publicKey], true);
+
+    // Fund the oracle's own token account to deposit the payouts from
+    const oracleTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      (provider.wallet as anchor.Wallet).payer,
+      rewardMintPda,
+      provider.wallet.publicKey
+    );
+    await program.methods
+      .mintRewards(new anchor.BN(500))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        config: configPda,
+        rewardMint: rewardMintPda,
+        rewardMintAuthority: rewardMintAuthorityPda,
+        recipient: oracleTokenAccount.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    // Leaves are sha256(0 || borsh(entry)), nodes sha256(1 || sorted children)
+    const leaf = (index: number, user: PublicKey, amount: number) => {
+      const indexBytes = Buffer.alloc(4);
+      indexBytes.writeUInt32LE(index);
+      const amountBytes = Buffer.alloc(8);
+      amountBytes.writeBigUInt64LE(BigInt(amount));
+      return createHash("sha256")
+        .update(Buffer.concat([Buffer.from([0]), indexBytes, user.toBuffer(), amountBytes]))
+        .digest();
+    };
+    const node = (left: Buffer, right: Buffer) =>
+      createHash("sha256")
+        .update(Buffer.concat([Buffer.from([1]), ...[left, right].sort(Buffer.compare)]))
+        .digest();
+    // The deposit used up the rest of the emission schedule's total cap
+    const config = await program.account.platformConfig.fetch(configPda);
+    expect(config.emittedTotal.toNumber()).to.equal(1250);
+    try {
+      await program.methods
+        .mintRewards(new anchor.BN(1))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          config: configPda,
+          rewardMint: rewardMintPda,
+          rewardMintAuthority: rewardMintAuthorityPda,
+          recipient: oracleTokenAccount.address,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      expect.fail("minting past the total cap should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("EmissionLimitExceeded");
+    }
+
+    const creatorLeaf = leaf(0, creator.publicKey, 300);
+    const otherLeaf = leaf(1, Keypair.generate().publicKey, 200);
+    const merkleRoot = node(creatorLeaf, otherLeaf);
+
+    const epoch = new anchor.BN(1);
+    const [distributorPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_distributor"), epoch.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("distributor_vault"), distributorPda.toBuffer()],
+      program.programId
+    );
+    const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+    const expiresAt = now + 5;
+
+    await program.methods
+      .createRewardDistributor(epoch, [...merkleRoot], new anchor.BN(500), 2, new anchor.BN(expiresAt))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        config: configPda,
+        distributor: distributorPda,
+        vault: vaultPda,
+        rewardMint: rewardMintPda,
+        source: oracleTokenAccount.address,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    await setAnalyticsOracles([], true);
+
+    const creatorTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      (provider.wallet as anchor.Wallet).payer,
+      rewardMintPda,
+      creator.publicKey
+    );
+    const before = Number((await getAccount(provider.connection, creatorTokenAccount.address)).amount);
+    const claimDistribution = () =>
+      program.methods
+        .claimDistribution(0, new anchor.BN(300), [[...otherLeaf]])
+        .accounts({
+          user: creator.publicKey,
+          config: configPda,
+          distributor: distributorPda,
+          vault: vaultPda,
+          rewardMint: rewardMintPda,
+          tokenAccount: creatorTokenAccount.address,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([creator])
+        .rpc();
+    await claimDistribution();
+
+    const tokenAccount = await getAccount(provider.connection, creatorTokenAccount.address);
+    expect(Number(tokenAccount.amount) - before).to.equal(300);
+
+    try {
+      await claimDistribution();
+      expect.fail("claiming twice should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("DistributionAlreadyClaimed");
+    }
+
+    // The unclaimed 200 return to the oracle once the distributor expires
+    const clawback = () =>
+      program.methods
+        .clawbackDistribution()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          config: configPda,
+          distributor: distributorPda,
+          vault: vaultPda,
+          destination: oracleTokenAccount.address,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    try {
+      await clawback();
+      expect.fail("clawing back before expiry should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("DistributionNotClawable");
+    }
+
+    while ((await provider.connection.getBlockTime(await provider.connection.getSlot())) < expiresAt + 1) {
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+    }
+    await clawback();
+
+    const oracleAccount = await getAccount(provider.connection, oracleTokenAccount.address);
+    expect(Number(oracleAccount.amount)).to.equal(200);
+    const distributor = await program.account.rewardDistributor.fetch(distributorPda);
+    expect(distributor.clawedBack).to.equal(true);
+    expect(distributor.claimedAmount.toNumber()).to.equal(300);
+  });
+
+  it("Split creator earnings between royalty recipients", async () => {
+    const [rewardMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_mint")],
+      program.programId
+    );
+    const [rewardMintAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_mint_authority")],
+      program.programId
+    );
+    const aliceKeypair = Keypair.generate();
+    const alice = aliceKeypair.publicKey;
+    const bob = Keypair.generate().publicKey;
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(alice, anchor.web3.LAMPORTS_PER_SOL)
+    );
+
+    // A second content, published and split 50% / 33.33% / 16.67%
+    const splitContentId = "split-content-id";
+    const [splitContentPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("content"),
+        creator.publicKey.toBuffer(),
+        createHash("sha256").update(splitContentId).digest(),
+      ],
+      program.programId
+    );
+    const [splitContentIndexPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("content_index"),
+        creator.publicKey.toBuffer(),
+        new anchor.BN(1).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .initializeContent(splitContentId, title, description, contentUrl, contentType, [])
+      .accounts({
+        creator: creator.publicKey,
+        content: splitContentPda,
+        creatorProfile: creatorProfilePda,
+        contentIndex: splitContentIndexPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        config: configPda,
+      })
+      .signers([creator])
+      .rpc();
+    await program.methods
+      .publish(null)
+      .accounts({
+        creator: creator.publicKey,
+        content: splitContentPda,
+        config: configPda,
+      })
+      .signers([creator])
+      .rpc();
+    await program.methods
+      .setRoyaltyDistribution([5000, 3333, 1667])
+      .accounts({
+        creator: creator.publicKey,
+        content: splitContentPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        config: configPda,
+      })
+      .remainingAccounts(
+        [creator.publicKey, alice, bob].map((pubkey) => ({ pubkey, isWritable: false, isSigner: false }))
+      )
+      .signers([creator])
+      .rpc();
+    const payees = [creator.publicKey, alice, bob];
+
+    // Alice views the content without earning a view reward
+    const [aliceReceiptPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("view_receipt"), splitContentPda.toBuffer(), alice.toBuffer()],
+      program.programId
+    );
+    const [splitHistoryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("analytics_history"), splitContentPda.toBuffer()],
+      program.programId
+    );
+    const [aliceRewardsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_rewards"), alice.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .recordView(10000)
+      .accounts({
+        viewer: alice,
+        content: splitContentPda,
+        viewReceipt: aliceReceiptPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        config: configPda,
+        history: splitHistoryPda,
+        userRewards: aliceRewardsPda,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+
+    // Oracle-reported earnings become reward credits of each payee
+    const userRewardsPdas = payees.map(
+      (payee) =>
+        PublicKey.findProgramAddressSync([Buffer.from("user_rewards"), payee.toBuffer()], program.programId)[0]
+    );
+    const accruedBefore = (await program.account.userRewards.fetch(userRewardsPdas[0])).accrued.toNumber();
+    await program.methods
+      .setAnalyticsOracles([provider.wallet.publicKey], true)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config: configPda,
+      })
+      .rpc();
+    await program.methods
+      .accrueCreatorEarnings(new anchor.BN(100))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        config: configPda,
+        content: splitContentPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(
+        userRewardsPdas.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+      )
+      .rpc();
+
+    const accrued = await Promise.all(
+      userRewardsPdas.map(async (pda) => (await program.account.userRewards.fetch(pda)).accrued.toNumber())
+    );
+    expect(accrued).to.deep.equal([accruedBefore + 50, 33, 17]);
+
+    // Alice's credits are claimable, with the cap raised to make room for them
+    const { emissionSchedule, emittedTotal } = await program.account.platformConfig.fetch(configPda);
+    await program.methods
+      .updateConfig(null, null, null, null, null, null, {
+        ...emissionSchedule,
+        totalCap: emittedTotal.addn(33),
+      })
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config: configPda,
+      })
+      .rpc();
+    const aliceTokenAccount = getAssociatedTokenAddressSync(rewardMintPda, alice);
+    await program.methods
+      .claimRewards()
+      .accounts({
+        user: alice,
+        config: configPda,
+        userRewards: aliceRewardsPda,
+        rewardMint: rewardMintPda,
+        rewardMintAuthority: rewardMintAuthorityPda,
+        tokenAccount: aliceTokenAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    expect(Number((await getAccount(provider.connection, aliceTokenAccount)).amount)).to.equal(33);
+
+    // Tips go straight to the payees' reward token accounts, dust to the largest remainder
+    const payeeTokenAccounts = await Promise.all(
+      payees.map(
+        async (payee) =>
+          (
+            await getOrCreateAssociatedTokenAccount(
+              provider.connection,
+              (provider.wallet as anchor.Wallet).payer,
+              rewardMintPda,
+              payee
+            )
+          ).address
+      )
+    );
+    const balances = () =>
+      Promise.all(
+        payeeTokenAccounts.map(async (address) => Number((await getAccount(provider.connection, address)).amount))
+      );
+    const before = await balances();
+    const tip = (tokenAccounts: PublicKey[]) =>
+      program.methods
+        .tipContent(new anchor.BN(10))
+        .accounts({
+          tipper: creator.publicKey,
+          config: configPda,
+          content: splitContentPda,
+          source: payeeTokenAccounts[0],
+          rewardMint: rewardMintPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          tokenAccounts.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+        )
+        .signers([creator])
+        .rpc();
+
+    try {
+      await tip([payeeTokenAccounts[1], payeeTokenAccounts[0], payeeTokenAccounts[2]]);
+      expect.fail("tipping into the wrong payee accounts should fail");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("ConstraintSeeds");
+    }
+    await tip(payeeTokenAccounts);
+
+    // The creator tips themselves their own 5, so only the collaborators' shares leave
+    const after = await balances();
+    expect(after.map((balance, i) => balance - before[i])).to.deep.equal([-5, 3, 2]);
   });
 
   // Additional tests for NFT minting and royalty distribution can be added here
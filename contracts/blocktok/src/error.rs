@@ -31,6 +31,148 @@ pub enum BlockTokError {
     /// Invalid content data
     #[error("Invalid content data")]
     InvalidContentData,
+
+    /// Content NFT must be burned before the content can be closed
+    #[error("Content NFT has not been burned")]
+    NftNotBurned,
+
+    /// Account was written under an older layout and must be migrated first
+    #[error("Account needs migration")]
+    AccountNeedsMigration,
+
+    /// Account layout version is not known to this program
+    #[error("Unsupported account version")]
+    UnsupportedAccountVersion,
+
+    /// More royalty recipients than the program allows
+    #[error("Too many royalty recipients")]
+    TooManyRoyaltyRecipients,
+
+    /// Platform fee above 100%
+    #[error("Invalid platform fee")]
+    InvalidPlatformFee,
+
+    /// Program or instruction is paused by the platform admin
+    #[error("Program paused")]
+    ProgramPaused,
+
+    /// Platform config account was not passed to the instruction
+    #[error("Missing platform config account")]
+    MissingPlatformConfig,
+
+    /// Category name is empty or longer than a seed
+    #[error("Invalid category name")]
+    InvalidCategoryName,
+
+    /// Content tagged with more than `MAX_CONTENT_TAGS` categories
+    #[error("Too many tags")]
+    TooManyTags,
+
+    /// Content tagged with the same category twice
+    #[error("Duplicate tag")]
+    DuplicateTag,
+
+    /// Content tagged with a deactivated category
+    #[error("Category inactive")]
+    CategoryInactive,
+
+    /// Instruction requires published content
+    #[error("Content not published")]
+    ContentNotPublished,
+
+    /// Content status cannot change this way from its current status
+    #[error("Invalid content status transition")]
+    InvalidStatusTransition,
+
+    /// Analytics counter would overflow
+    #[error("Analytics overflow")]
+    AnalyticsOverflow,
+
+    /// More than `MAX_ANALYTICS_ORACLES` analytics oracles
+    #[error("Too many analytics oracles")]
+    TooManyAnalyticsOracles,
+
+    /// Analytics attestation missing, malformed or not matching the instruction
+    #[error("Invalid analytics attestation")]
+    InvalidAttestation,
+
+    /// Analytics attestation used after its expiry
+    #[error("Analytics attestation expired")]
+    AttestationExpired,
+
+    /// Analytics attestation nonce not greater than the content's last nonce
+    #[error("Analytics attestation replayed")]
+    AttestationReplayed,
+
+    /// Analytics update would decrease a counter
+    #[error("Analytics decreased")]
+    AnalyticsDecreased,
+
+    /// Analytics updated again before the platform's minimum interval passed
+    #[error("Analytics updated too frequently")]
+    AnalyticsUpdateTooFrequent,
+
+    /// Watch progress above `MAX_WATCH_PROGRESS_BPS`
+    #[error("Invalid watch progress")]
+    InvalidWatchProgress,
+
+    /// Engagement epoch window empty or not yet over
+    #[error("Invalid engagement epoch window")]
+    InvalidEpochWindow,
+
+    /// Engagement event outside its epoch's window
+    #[error("Engagement event outside epoch")]
+    EngagementOutsideEpoch,
+
+    /// Merkle proof not leading to the committed root
+    #[error("Invalid Merkle proof")]
+    InvalidMerkleProof,
+
+    /// Batch holds more than `MAX_BATCH_ANALYTICS_UPDATES` updates
+    #[error("Too many batch updates")]
+    TooManyBatchUpdates,
+
+    /// Batch account not matching the content of its update
+    #[error("Batch account mismatch")]
+    BatchAccountMismatch,
+
+    /// Reward amount of zero
+    #[error("Invalid reward amount")]
+    InvalidRewardAmount,
+
+    /// Reward credits would overflow
+    #[error("Reward overflow")]
+    RewardOverflow,
+
+    /// Claim without any unclaimed reward credits
+    #[error("No rewards to claim")]
+    NoRewardsToClaim,
+
+    /// Reward distributor without recipients or tokens, with too many
+    /// recipients, or already expired
+    #[error("Invalid reward distributor")]
+    InvalidDistributor,
+
+    /// Distribution entry index past the distributor's recipients
+    #[error("Invalid distribution entry")]
+    InvalidDistributionEntry,
+
+    /// Distribution entry already claimed
+    #[error("Distribution already claimed")]
+    DistributionAlreadyClaimed,
+
+    /// Claim after the distributor expired or was clawed back
+    #[error("Distribution expired")]
+    DistributionExpired,
+
+    /// Clawback before the distributor expired, or after a previous clawback
+    #[error("Distribution not clawable")]
+    DistributionNotClawable,
+
+    /// Reward minting beyond the emission schedule's epoch allowance or
+    /// total cap
+    #[error("Emission limit exceeded")]
+    EmissionLimitExceeded,
 }
 
 impl From<BlockTokError> for ProgramError {
@@ -1,19 +1,45 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
+    bpf_loader_upgradeable, ed25519_program,
     instruction::{AccountMeta, Instruction},
-    pubkey::Pubkey,
+    pubkey::{Pubkey, PUBKEY_BYTES},
     system_program, sysvar,
 };
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::state::{
+    find_reward_mint_address, find_reward_mint_authority_address, pause_flags, tag_changes,
+    AnalyticsAttestation, AnalyticsHistory, Category, CategoryIndex, Content, ContentIndex,
+    CreatorProfile, EmissionSchedule, EngagementEpoch, EngagementEvent, EngagementWeights,
+    EpochContentTotals, HistoryGranularity, PlatformConfig, RewardDistributor, UserRewards,
+    ViewReceipt, WatchMetrics,
+};
+
+/// Start of the signature offsets in Ed25519 program instruction data, after
+/// the signature count and a padding byte
+pub const ED25519_OFFSETS_START: usize = 2;
+
+/// Size of one signature's offsets in Ed25519 program instruction data: seven
+/// little-endian u16 offsets and instruction indexes
+pub const ED25519_OFFSETS_SIZE: usize = 14;
 
 /// Instructions for the BlockTok Program
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
 pub enum BlockTokInstruction {
-    /// Initialize a new content account
+    /// Initialize a new content account and add it to the creator's catalogue
     /// 
     /// Accounts expected:
-    /// 0. `[signer]` Creator account (fee payer and authority)
+    /// 0. `[signer, writable]` Creator account (fee payer and authority)
     /// 1. `[writable]` Content account (PDA, to be initialized)
     /// 2. `[]` System program
+    /// 3. `[writable]` Creator profile account (PDA, created on first content)
+    /// 4. `[writable]` Content index account (PDA at the profile's current `content_count`, to be initialized)
+    /// 5. `[]` Platform config account (PDA, checked for pause state)
+    /// 6. `[]` Content account at the legacy address (`Content::find_legacy_address`,
+    ///    must not hold content). Omitted for IDs longer than a seed
+    /// 7+ For each tag, in order:
+    ///    `[writable]` Category account (PDA, must be active)
+    ///    `[writable]` Category index account (PDA, to be initialized)
     InitializeContent {
         /// Unique content ID (hash of the content or URL)
         content_id: String,
@@ -25,8 +51,8 @@ pub enum BlockTokInstruction {
         content_url: String,
         /// Content type (video, audio, etc.)
         content_type: String,
-        /// Timestamp when the content was created
-        created_at: u64,
+        /// Names of the categories to tag the content with, at most `MAX_CONTENT_TAGS`
+        tags: Vec<String>,
     },
 
     /// Mint NFT for content
@@ -42,6 +68,7 @@ pub enum BlockTokInstruction {
     /// 7. `[]` Token program
     /// 8. `[]` Token metadata program
     /// 9. `[]` Associated token account program
+    /// 10. `[]` Platform config account (PDA, checked for pause state)
     MintNFT {
         /// NFT name
         name: String,
@@ -53,11 +80,21 @@ pub enum BlockTokInstruction {
         royalty_basis_points: u16,
     },
 
-    /// Update content analytics
+    /// Update content analytics. Counters cannot decrease, and updates must be
+    /// at least the config's `min_analytics_interval` apart
+    /// 
+    /// With an attestation, the counters must be signed by an analytics oracle
+    /// in an Ed25519 program instruction immediately before this one (see
+    /// `verify_analytics_attestation`), and any account can relay them.
     /// 
     /// Accounts expected:
-    /// 0. `[signer]` Authority account (must be the creator)
+    /// 0. `[signer]` Authority account (an analytics oracle, or the creator
+    ///    unless the config disallows creator reports; any account when
+    ///    relaying an attestation)
     /// 1. `[writable]` Content account (PDA, already initialized)
+    /// 2. `[]` Platform config account (PDA, checked for pause state and oracles)
+    /// 3. `[writable]` Analytics history account (PDA, recorded to once initialized)
+    /// 4. `[]` Instructions sysvar (only with an attestation)
     UpdateAnalytics {
         /// Number of views
         views: u64,
@@ -67,25 +104,583 @@ pub enum BlockTokInstruction {
         shares: u64,
         /// Number of comments
         comments: u64,
-        /// Timestamp of the update
-        updated_at: u64,
+        /// Watch time, completion and replays
+        watch: WatchMetrics,
+        /// Nonce and expiry the analytics oracle signed the counters with
+        attestation: Option<AttestationTerms>,
     },
 
     /// Set content royalties distribution
     /// 
     /// Accounts expected:
-    /// 0. `[signer]` Creator account (fee payer and authority)
+    /// 0. `[signer, writable]` Creator account (pays or receives the rent difference)
     /// 1. `[writable]` Content account (PDA, already initialized)
-    /// 2+ `[]` Variable number of royalty recipient accounts
+    /// 2. `[]` System program
+    /// 3. `[]` Platform config account (PDA, checked for pause state)
+    /// 4+ `[]` One royalty recipient account per entry, in the same order
     SetRoyaltyDistribution {
-        /// Vector of (pubkey as string, percentage basis points)
-        /// Total should sum to 10000 (100%)
+        /// Vector of (base58 pubkey, percentage basis points), at most
+        /// `MAX_ROYALTY_RECIPIENTS` entries. Total should sum to 10000 (100%)
         royalty_recipients: Vec<(String, u16)>,
     },
+
+    /// Update content metadata, resizing the content account to fit
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Creator account (pays or receives the rent difference)
+    /// 1. `[writable]` Content account (PDA, already initialized)
+    /// 2. `[]` System program
+    /// 3. `[]` Platform config account (PDA, checked for pause state)
+    /// 4+ For each tag removed, then each tag added, in order (see `tag_changes`):
+    ///    `[writable]` Category account (PDA, must be active when adding)
+    ///    `[writable]` Category index account (PDA, closed when removing, initialized when adding)
+    UpdateContentMetadata {
+        /// New content title, or `None` to keep the current one
+        title: Option<String>,
+        /// New content description, or `None` to keep the current one
+        description: Option<String>,
+        /// New content URL, or `None` to keep the current one
+        content_url: Option<String>,
+        /// New content type, or `None` to keep the current one
+        content_type: Option<String>,
+        /// New category tags, or `None` to keep the current ones
+        tags: Option<Vec<String>>,
+    },
+
+    /// Close a content account and return its rent to the creator
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Creator account (receives the reclaimed rent)
+    /// 1. `[writable]` Content account (PDA, already initialized)
+    /// 2. `[]` Platform config account (PDA, checked for pause state)
+    /// 3. `[writable]` Analytics history account (PDA, closed if initialized)
+    /// 4. `[]` NFT mint account (only if an NFT was minted; its supply must be zero)
+    /// 4+ / 5+ For each of the content's tags, in order:
+    ///    `[writable]` Category account (PDA)
+    ///    `[writable]` Category index account (PDA, closed)
+    CloseContent,
+
+    /// Upgrade a content account to the current layout version in place
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Creator account (pays or receives the rent difference)
+    /// 1. `[writable]` Content account (PDA, any supported layout version)
+    /// 2. `[]` System program
+    /// 3. `[]` Platform config account (PDA, checked for pause state)
+    MigrateContent,
+
+    /// Create the platform config. Only the program's upgrade authority may
+    /// do this, and it becomes the first admin
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Upgrade authority account (fee payer and initial admin)
+    /// 1. `[writable]` Platform config account (PDA, to be initialized)
+    /// 2. `[]` Program data account of this program
+    /// 3. `[]` System program
+    InitializeConfig {
+        /// Account that receives platform fees
+        treasury: Pubkey,
+        /// Platform fee in basis points
+        platform_fee_bps: u16,
+        /// Bitmask of enabled platform features
+        features: u64,
+    },
+
+    /// Update platform settings
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Admin account (pays or receives any rent difference)
+    /// 1. `[writable]` Platform config account (PDA, already initialized)
+    /// 2. `[]` System program
+    UpdateConfig {
+        /// New treasury, or `None` to keep the current one
+        treasury: Option<Pubkey>,
+        /// New platform fee in basis points, or `None` to keep the current one
+        platform_fee_bps: Option<u16>,
+        /// New feature bitmask, or `None` to keep the current one
+        features: Option<u64>,
+        /// New minimum seconds between analytics updates of a content, or
+        /// `None` to keep the current one
+        min_analytics_interval: Option<u64>,
+        /// New engagement score weights, or `None` to keep the current ones.
+        /// Scores are recomputed with them on each content's next analytics change
+        engagement_weights: Option<EngagementWeights>,
+        /// New reward credits per receipt-backed view, or `None` to keep the
+        /// current ones
+        view_reward: Option<u64>,
+        /// New reward token emission schedule, or `None` to keep the current
+        /// one. Tokens emitted so far keep counting against the new cap
+        emission_schedule: Option<EmissionSchedule>,
+    },
+
+    /// Nominate a new admin. The transfer completes once they call `AcceptAdmin`
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Admin account (pays or receives any rent difference)
+    /// 1. `[writable]` Platform config account (PDA, already initialized)
+    /// 2. `[]` System program
+    TransferAdmin {
+        /// Admin to nominate
+        new_admin: Pubkey,
+    },
+
+    /// Accept a pending admin nomination
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Pending admin account (pays or receives any rent difference)
+    /// 1. `[writable]` Platform config account (PDA, already initialized)
+    /// 2. `[]` System program
+    AcceptAdmin,
+
+    /// Pause or unpause the program, as a whole or per instruction
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Admin account (pays or receives any rent difference)
+    /// 1. `[writable]` Platform config account (PDA, already initialized)
+    /// 2. `[]` System program
+    SetPauseState {
+        /// Pause every non-admin instruction
+        paused: bool,
+        /// Bitmask of individually paused instructions, see `pause_flags`
+        paused_instructions: u64,
+    },
+
+    /// Register a new category content can be tagged with
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Admin account (fee payer)
+    /// 1. `[]` Platform config account (PDA, already initialized)
+    /// 2. `[writable]` Category account (PDA, to be initialized)
+    /// 3. `[]` System program
+    CreateCategory {
+        /// Category name, at most `MAX_CATEGORY_NAME_LEN` bytes
+        name: String,
+    },
+
+    /// Activate or deactivate a category. Deactivated categories keep their
+    /// existing tags but cannot be added to content
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer]` Admin account
+    /// 1. `[]` Platform config account (PDA, already initialized)
+    /// 2. `[writable]` Category account (PDA, already initialized)
+    SetCategoryActive {
+        /// Category name
+        name: String,
+        /// Whether content may be tagged with the category
+        active: bool,
+    },
+
+    /// Publish a draft, scheduled or unlisted content, now or at a later time
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer]` Creator account
+    /// 1. `[writable]` Content account (PDA, already initialized)
+    /// 2. `[]` Platform config account (PDA, checked for pause state)
+    Publish {
+        /// Unix timestamp to publish at, or `None` to publish immediately.
+        /// Content is `Scheduled` until then
+        publish_at: Option<u64>,
+    },
+
+    /// Hide published content from listings while keeping it reachable
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer]` Creator account
+    /// 1. `[writable]` Content account (PDA, already initialized)
+    /// 2. `[]` Platform config account (PDA, checked for pause state)
+    Unlist,
+
+    /// Retire content for good
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer]` Creator account
+    /// 1. `[writable]` Content account (PDA, already initialized)
+    /// 2. `[]` Platform config account (PDA, checked for pause state)
+    Archive,
+
+    /// Add deltas to the content analytics counters. Unlike `UpdateAnalytics`,
+    /// concurrent reporters do not overwrite each other. Increments must be at
+    /// least the config's `min_analytics_interval` apart
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer]` Authority account (an analytics oracle, or the creator
+    ///    unless the config disallows creator reports)
+    /// 1. `[writable]` Content account (PDA, already initialized)
+    /// 2. `[]` Platform config account (PDA, checked for pause state and oracles)
+    /// 3. `[writable]` Analytics history account (PDA, recorded to once initialized)
+    IncrementAnalytics {
+        /// Views to add
+        views: u64,
+        /// Likes to add
+        likes: u64,
+        /// Shares to add
+        shares: u64,
+        /// Comments to add
+        comments: u64,
+        /// Watch time, completion and replays to add
+        watch: WatchMetrics,
+    },
+
+    /// Set the analytics oracles and whether creators may still report their
+    /// own analytics
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Admin account (pays or receives any rent difference)
+    /// 1. `[writable]` Platform config account (PDA, already initialized)
+    /// 2. `[]` System program
+    SetAnalyticsOracles {
+        /// Oracle authorities, at most `MAX_ANALYTICS_ORACLES`
+        oracles: Vec<Pubkey>,
+        /// Whether creators may report analytics for their own content
+        allow_creator_analytics: bool,
+    },
+
+    /// Create the analytics history of a content, after which every analytics
+    /// update is also recorded in its time buckets
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Creator account (fee payer)
+    /// 1. `[]` Content account (PDA, already initialized)
+    /// 2. `[writable]` Analytics history account (PDA, to be initialized)
+    /// 3. `[]` System program
+    /// 4. `[]` Platform config account (PDA, checked for pause state)
+    InitializeAnalyticsHistory {
+        /// Length of each history bucket
+        granularity: HistoryGranularity,
+    },
+
+    /// Record a view of published content by the signing viewer. The first
+    /// view creates the viewer's receipt and counts towards the content's
+    /// views; later ones only advance the receipt's watch progress
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Viewer account (fee payer)
+    /// 1. `[writable]` Content account (PDA, already initialized)
+    /// 2. `[writable]` View receipt account (PDA, created on the first view)
+    /// 3. `[]` System program
+    /// 4. `[]` Platform config account (PDA, checked for pause state)
+    /// 5. `[writable]` Analytics history account (PDA, recorded to once initialized)
+    /// 6. `[writable]` Viewer's user rewards account (PDA, created on the first
+    ///    view rewarded with the config's `view_reward`)
+    RecordView {
+        /// Watch progress in basis points of the content, at most `MAX_WATCH_PROGRESS_BPS`
+        watch_progress_bps: u16,
+    },
+
+    /// Commit the Merkle root of every engagement event in a closed time
+    /// window as a new engagement epoch. Only analytics oracles can commit
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Analytics oracle account (fee payer)
+    /// 1. `[writable]` Engagement epoch account (PDA, to be initialized)
+    /// 2. `[]` System program
+    /// 3. `[]` Platform config account (PDA)
+    CommitEngagementEpoch {
+        /// Epoch number, unique across the platform
+        epoch: u64,
+        /// Unix timestamp of the start of the window (inclusive)
+        start_time: u64,
+        /// Unix timestamp of the end of the window (exclusive), not in the future
+        end_time: u64,
+        /// Merkle root of the `EngagementEvent::leaf` hashes of the window's events
+        merkle_root: [u8; 32],
+        /// Number of events in the window
+        event_count: u64,
+    },
+
+    /// Record a content's engagement totals for an engagement epoch. Only
+    /// the oracle that committed the epoch can record its totals
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Analytics oracle account (fee payer)
+    /// 1. `[]` Engagement epoch account (PDA)
+    /// 2. `[]` Content account (PDA)
+    /// 3. `[writable]` Epoch content totals account (PDA, to be initialized)
+    /// 4. `[]` System program
+    /// 5. `[]` Platform config account (PDA, checked for pause state)
+    RecordEpochTotals {
+        /// Number of views in the epoch
+        views: u64,
+        /// Number of likes in the epoch
+        likes: u64,
+        /// Number of shares in the epoch
+        shares: u64,
+        /// Number of comments in the epoch
+        comments: u64,
+    },
+
+    /// Prove that an engagement event is included in an engagement epoch.
+    /// Fails unless the event is in the epoch's window and the proof leads
+    /// to its Merkle root
+    /// 
+    /// Accounts expected:
+    /// 0. `[]` Engagement epoch account (PDA)
+    VerifyEngagement {
+        /// The engagement event
+        event: EngagementEvent,
+        /// Sibling hashes from the event's leaf up to the root
+        proof: Vec<[u8; 32]>,
+    },
+
+    /// Update the analytics of many contents at once, as one `UpdateAnalytics`
+    /// per content without attestations. Either every update applies or none
+    /// does
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer]` Authority account (an analytics oracle, or the creator of
+    ///    every content unless the config disallows creator reports)
+    /// 1. `[]` Platform config account (PDA, checked for pause state and oracles)
+    /// 2+ For each update, in order:
+    ///    `[writable]` Content account (PDA, already initialized)
+    ///    `[writable]` Analytics history account (PDA, recorded to once initialized)
+    BatchUpdateAnalytics {
+        /// Updates, at most `MAX_BATCH_ANALYTICS_UPDATES`
+        updates: Vec<ContentAnalyticsUpdate>,
+    },
+
+    /// Create the reward token mint. Its mint authority is a program PDA, so
+    /// reward tokens can only be minted through the program
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Admin account (pays for the mint)
+    /// 1. `[]` Platform config account (PDA, already initialized)
+    /// 2. `[writable]` Reward mint account (PDA, uninitialized)
+    /// 3. `[]` Reward mint authority account (PDA)
+    /// 4. `[]` System program
+    /// 5. `[]` Token program
+    InitializeRewardMint,
+
+    /// Mint reward tokens to a token account of the reward mint, within the
+    /// platform's emission schedule
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer]` Authority account (an analytics oracle)
+    /// 1. `[writable]` Platform config account (PDA, checked for pause state and
+    ///    oracles, counts the emission)
+    /// 2. `[writable]` Reward mint account (PDA, already initialized)
+    /// 3. `[]` Reward mint authority account (PDA)
+    /// 4. `[writable]` Recipient token account of the reward mint
+    /// 5. `[]` Token program
+    MintRewards {
+        /// Amount of reward tokens, in base units
+        amount: u64,
+    },
+
+    /// Accrue reward credits to a user for engagement reported off-chain
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Authority account (an analytics oracle, pays
+    ///    for the user rewards account)
+    /// 1. `[]` Platform config account (PDA, checked for pause state and oracles)
+    /// 2. `[]` User account
+    /// 3. `[writable]` User rewards account (PDA, created on the first accrual)
+    /// 4. `[]` System program
+    AccrueRewards {
+        /// Reward credits, in reward token base units
+        amount: u64,
+    },
+
+    /// Mint all of the signing user's unclaimed reward credits to their
+    /// associated token account of the reward mint, creating it if needed.
+    /// Fails while the platform's emission schedule does not allow the mint
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` User account (pays for the token account)
+    /// 1. `[writable]` Platform config account (PDA, checked for pause state,
+    ///    counts the emission)
+    /// 2. `[writable]` User rewards account (PDA, already initialized)
+    /// 3. `[writable]` Reward mint account (PDA, already initialized)
+    /// 4. `[]` Reward mint authority account (PDA)
+    /// 5. `[writable]` User's associated token account of the reward mint
+    /// 6. `[]` System program
+    /// 7. `[]` Token program
+    /// 8. `[]` Associated token account program
+    ClaimRewards,
+
+    /// Create the reward distributor of an epoch, depositing its total into
+    /// a vault it owns. Recipients then claim with `ClaimDistribution`
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Authority account (an analytics oracle, pays
+    ///    for the distributor and vault, and owns the source token account)
+    /// 1. `[]` Platform config account (PDA, checked for pause state and oracles)
+    /// 2. `[writable]` Reward distributor account (PDA, uninitialized)
+    /// 3. `[writable]` Distributor vault account (PDA, uninitialized)
+    /// 4. `[]` Reward mint account (PDA)
+    /// 5. `[writable]` Source token account of the reward mint
+    /// 6. `[]` System program
+    /// 7. `[]` Token program
+    CreateRewardDistributor {
+        /// Epoch number, unique across the platform
+        epoch: u64,
+        /// Merkle root of the `DistributionEntry::leaf` hashes of the payouts
+        merkle_root: [u8; 32],
+        /// Reward tokens deposited for the payouts, in base units
+        total_amount: u64,
+        /// Number of payouts, at most `MAX_DISTRIBUTOR_RECIPIENTS`
+        num_recipients: u32,
+        /// Unix timestamp from which claims are refused
+        expires_at: u64,
+    },
+
+    /// Claim the signing user's payout from a reward distributor into their
+    /// associated token account of the reward mint, creating it if needed
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` User account (pays for the token account)
+    /// 1. `[]` Platform config account (PDA, checked for pause state)
+    /// 2. `[writable]` Reward distributor account (PDA)
+    /// 3. `[writable]` Distributor vault account (PDA)
+    /// 4. `[]` Reward mint account (PDA)
+    /// 5. `[writable]` User's associated token account of the reward mint
+    /// 6. `[]` System program
+    /// 7. `[]` Token program
+    /// 8. `[]` Associated token account program
+    ClaimDistribution {
+        /// Index of the user's entry
+        index: u32,
+        /// Amount of the user's entry
+        amount: u64,
+        /// Sibling hashes from the entry's leaf up to the root
+        proof: Vec<[u8; 32]>,
+    },
+
+    /// Move the unclaimed tokens of an expired reward distributor out of its
+    /// vault. Further claims are refused
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer]` Authority account (the distributor's authority)
+    /// 1. `[]` Platform config account (PDA, checked for pause state)
+    /// 2. `[writable]` Reward distributor account (PDA)
+    /// 3. `[writable]` Distributor vault account (PDA)
+    /// 4. `[writable]` Destination token account of the reward mint
+    /// 5. `[]` Token program
+    ClawbackDistribution,
+
+    /// Credit a content's earnings, such as view rewards or campaign payouts
+    /// reported off-chain, as reward credits of its payees, split by their
+    /// royalty basis points (see `RoyaltyDistribution::split`)
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Authority account (an analytics oracle, pays for
+    ///    new rewards accounts)
+    /// 1. `[]` Platform config account (PDA, checked for pause state and oracles)
+    /// 2. `[]` Content account (PDA)
+    /// 3. `[]` System program
+    /// 4+ `[writable]` User rewards account (PDA) of each payee, in the order of
+    ///    `RoyaltyDistribution::payees`
+    AccrueCreatorEarnings {
+        /// Reward credits earned, in reward token base units
+        amount: u64,
+    },
+
+    /// Tip a content in reward tokens, split between its payees by their
+    /// royalty basis points (see `RoyaltyDistribution::split`)
+    /// 
+    /// Accounts expected:
+    /// 0. `[signer]` Tipper account
+    /// 1. `[]` Platform config account (PDA, checked for pause state)
+    /// 2. `[]` Content account (PDA, must be published)
+    /// 3. `[writable]` Tipper's token account of the reward mint
+    /// 4. `[]` Token program
+    /// 5+ `[writable]` Associated token account of the reward mint of each
+    ///    payee, in the order of `RoyaltyDistribution::payees`
+    TipContent {
+        /// Reward tokens tipped, in base units
+        amount: u64,
+    },
+}
+
+/// Replay protection of an analytics attestation. The signed message is the
+/// `AnalyticsAttestation` made of these, the content and the counters
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct AttestationTerms {
+    /// Must be greater than the content's `analytics_nonce`
+    pub nonce: u64,
+    /// Unix timestamp after which the attestation is rejected
+    pub expiry: u64,
+}
+
+/// New counters of one content in a `BatchUpdateAnalytics`
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ContentAnalyticsUpdate {
+    /// Content account the counters are for, which must match the account
+    /// passed for the update
+    pub content: Pubkey,
+    /// Number of views
+    pub views: u64,
+    /// Number of likes
+    pub likes: u64,
+    /// Number of shares
+    pub shares: u64,
+    /// Number of comments
+    pub comments: u64,
+    /// Watch time, completion and replays
+    pub watch: WatchMetrics,
 }
 
 impl BlockTokInstruction {
-    /// Creates an instruction to initialize content
+    /// Bit identifying this instruction in `PlatformConfig::paused_instructions`,
+    /// or `None` for admin instructions, which cannot be paused
+    pub fn pause_flag(&self) -> Option<u64> {
+        match self {
+            BlockTokInstruction::InitializeContent { .. } => Some(pause_flags::INITIALIZE_CONTENT),
+            BlockTokInstruction::MintNFT { .. } => Some(pause_flags::MINT_NFT),
+            BlockTokInstruction::UpdateAnalytics { .. } => Some(pause_flags::UPDATE_ANALYTICS),
+            BlockTokInstruction::SetRoyaltyDistribution { .. } => {
+                Some(pause_flags::SET_ROYALTY_DISTRIBUTION)
+            }
+            BlockTokInstruction::UpdateContentMetadata { .. } => {
+                Some(pause_flags::UPDATE_CONTENT_METADATA)
+            }
+            BlockTokInstruction::CloseContent => Some(pause_flags::CLOSE_CONTENT),
+            BlockTokInstruction::MigrateContent => Some(pause_flags::MIGRATE_CONTENT),
+            BlockTokInstruction::Publish { .. } => Some(pause_flags::PUBLISH_CONTENT),
+            BlockTokInstruction::Unlist => Some(pause_flags::UNLIST_CONTENT),
+            BlockTokInstruction::Archive => Some(pause_flags::ARCHIVE_CONTENT),
+            BlockTokInstruction::IncrementAnalytics { .. } => Some(pause_flags::INCREMENT_ANALYTICS),
+            BlockTokInstruction::InitializeAnalyticsHistory { .. } => {
+                Some(pause_flags::INITIALIZE_ANALYTICS_HISTORY)
+            }
+            BlockTokInstruction::RecordView { .. } => Some(pause_flags::RECORD_VIEW),
+            BlockTokInstruction::CommitEngagementEpoch { .. } => {
+                Some(pause_flags::COMMIT_ENGAGEMENT_EPOCH)
+            }
+            BlockTokInstruction::RecordEpochTotals { .. } => Some(pause_flags::RECORD_EPOCH_TOTALS),
+            // Verification only reads the epoch, so there is nothing to pause
+            BlockTokInstruction::VerifyEngagement { .. } => None,
+            BlockTokInstruction::BatchUpdateAnalytics { .. } => {
+                Some(pause_flags::BATCH_UPDATE_ANALYTICS)
+            }
+            BlockTokInstruction::MintRewards { .. } => Some(pause_flags::MINT_REWARDS),
+            BlockTokInstruction::AccrueRewards { .. } => Some(pause_flags::ACCRUE_REWARDS),
+            BlockTokInstruction::ClaimRewards => Some(pause_flags::CLAIM_REWARDS),
+            BlockTokInstruction::CreateRewardDistributor { .. } => {
+                Some(pause_flags::CREATE_REWARD_DISTRIBUTOR)
+            }
+            BlockTokInstruction::ClaimDistribution { .. } => Some(pause_flags::CLAIM_DISTRIBUTION),
+            BlockTokInstruction::ClawbackDistribution => Some(pause_flags::CLAWBACK_DISTRIBUTION),
+            BlockTokInstruction::AccrueCreatorEarnings { .. } => {
+                Some(pause_flags::ACCRUE_CREATOR_EARNINGS)
+            }
+            BlockTokInstruction::TipContent { .. } => Some(pause_flags::TIP_CONTENT),
+            BlockTokInstruction::InitializeConfig { .. }
+            | BlockTokInstruction::UpdateConfig { .. }
+            | BlockTokInstruction::TransferAdmin { .. }
+            | BlockTokInstruction::AcceptAdmin
+            | BlockTokInstruction::SetPauseState { .. }
+            | BlockTokInstruction::CreateCategory { .. }
+            | BlockTokInstruction::SetCategoryActive { .. }
+            | BlockTokInstruction::SetAnalyticsOracles { .. }
+            | BlockTokInstruction::InitializeRewardMint => None,
+        }
+    }
+
+    /// Creates an instruction to initialize content.
+    ///
+    /// `content_index` must be the creator profile's current `content_count`
+    /// (zero for a creator's first content).
     pub fn initialize_content(
         program_id: &Pubkey,
         creator: &Pubkey,
@@ -94,28 +689,44 @@ impl BlockTokInstruction {
         description: String,
         content_url: String,
         content_type: String,
-        created_at: u64,
+        content_index: u64,
+        tags: Vec<String>,
     ) -> Instruction {
-        // Derive PDA for content account
-        let (content_pubkey, _) = Pubkey::find_program_address(
-            &[b"content", creator.as_ref(), content_id.as_bytes()],
-            program_id,
-        );
+        // Derive PDAs for content, creator profile and content index accounts
+        let (content_pubkey, _) = Content::find_address(program_id, creator, &content_id);
+        let (creator_profile_pubkey, _) = CreatorProfile::find_address(program_id, creator);
+        let (content_index_pubkey, _) = ContentIndex::find_address(program_id, creator, content_index);
+
+        // Build account metas
+        let mut accounts = vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new(content_pubkey, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(creator_profile_pubkey, false),
+            AccountMeta::new(content_index_pubkey, false),
+            AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+        ];
+
+        // Add the legacy content address, if the ID could have had one
+        if let Some((legacy_pubkey, _)) =
+            Content::find_legacy_address(program_id, creator, &content_id)
+        {
+            accounts.push(AccountMeta::new_readonly(legacy_pubkey, false));
+        }
+
+        // Add category accounts for each tag
+        accounts.extend(Self::tag_accounts(program_id, &content_pubkey, &tags));
 
         Instruction {
             program_id: *program_id,
-            accounts: vec![
-                AccountMeta::new_readonly(*creator, true),
-                AccountMeta::new(content_pubkey, false),
-                AccountMeta::new_readonly(system_program::id(), false),
-            ],
+            accounts,
             data: BlockTokInstruction::InitializeContent {
                 content_id,
                 title,
                 description,
                 content_url,
                 content_type,
-                created_at,
+                tags,
             }
             .try_to_vec()
             .unwrap(),
@@ -135,10 +746,7 @@ impl BlockTokInstruction {
         royalty_basis_points: u16,
     ) -> Instruction {
         // Derive PDA for content account
-        let (content_pubkey, _) = Pubkey::find_program_address(
-            &[b"content", creator.as_ref(), content_id.as_bytes()],
-            program_id,
-        );
+        let (content_pubkey, _) = Content::find_address(program_id, creator, content_id);
 
         // Derive PDA for metadata account
         let (metadata_pubkey, _) = Pubkey::find_program_address(
@@ -163,6 +771,7 @@ impl BlockTokInstruction {
                 AccountMeta::new_readonly(spl_token::id(), false),
                 AccountMeta::new_readonly(metaplex_token_metadata::ID, false),
                 AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
             ],
             data: BlockTokInstruction::MintNFT {
                 name,
@@ -175,35 +784,44 @@ impl BlockTokInstruction {
         }
     }
 
-    /// Creates an instruction to update content analytics
+    /// Creates an instruction to update content analytics.
+    ///
+    /// `authority` signs: the creator, or an analytics oracle for any creator's content.
     pub fn update_analytics(
         program_id: &Pubkey,
         authority: &Pubkey,
+        creator: &Pubkey,
         content_id: &String,
         views: u64,
         likes: u64,
         shares: u64,
         comments: u64,
-        updated_at: u64,
+        watch: WatchMetrics,
+        attestation: Option<AttestationTerms>,
     ) -> Instruction {
         // Derive PDA for content account
-        let (content_pubkey, _) = Pubkey::find_program_address(
-            &[b"content", authority.as_ref(), content_id.as_bytes()],
-            program_id,
-        );
+        let (content_pubkey, _) = Content::find_address(program_id, creator, content_id);
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(content_pubkey, false),
+            AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+            AccountMeta::new(AnalyticsHistory::find_address(program_id, &content_pubkey).0, false),
+        ];
+        if attestation.is_some() {
+            accounts.push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+        }
 
         Instruction {
             program_id: *program_id,
-            accounts: vec![
-                AccountMeta::new_readonly(*authority, true),
-                AccountMeta::new(content_pubkey, false),
-            ],
+            accounts,
             data: BlockTokInstruction::UpdateAnalytics {
                 views,
                 likes,
                 shares,
                 comments,
-                updated_at,
+                watch,
+                attestation,
             }
             .try_to_vec()
             .unwrap(),
@@ -219,15 +837,14 @@ impl BlockTokInstruction {
         recipient_accounts: &[Pubkey],
     ) -> Instruction {
         // Derive PDA for content account
-        let (content_pubkey, _) = Pubkey::find_program_address(
-            &[b"content", creator.as_ref(), content_id.as_bytes()],
-            program_id,
-        );
+        let (content_pubkey, _) = Content::find_address(program_id, creator, content_id);
 
         // Build account metas
         let mut accounts = vec![
-            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new(*creator, true),
             AccountMeta::new(content_pubkey, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
         ];
 
         // Add recipient accounts
@@ -245,4 +862,856 @@ impl BlockTokInstruction {
             .unwrap(),
         }
     }
-} 
\ No newline at end of file
+
+    /// Creates an instruction to update content metadata.
+    ///
+    /// `current_tags` must be the content's tags before the update; they are
+    /// only used to work out which category accounts to pass when `tags` is set.
+    pub fn update_content_metadata(
+        program_id: &Pubkey,
+        creator: &Pubkey,
+        content_id: &String,
+        title: Option<String>,
+        description: Option<String>,
+        content_url: Option<String>,
+        content_type: Option<String>,
+        tags: Option<Vec<String>>,
+        current_tags: &[String],
+    ) -> Instruction {
+        // Derive PDA for content account
+        let (content_pubkey, _) = Content::find_address(program_id, creator, content_id);
+
+        // Build account metas
+        let mut accounts = vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new(content_pubkey, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+        ];
+
+        // Add category accounts for each removed tag, then each added tag
+        if let Some(tags) = &tags {
+            let (removed, added) = tag_changes(current_tags, tags);
+            accounts.extend(Self::tag_accounts(program_id, &content_pubkey, removed));
+            accounts.extend(Self::tag_accounts(program_id, &content_pubkey, added));
+        }
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: BlockTokInstruction::UpdateContentMetadata {
+                title,
+                description,
+                content_url,
+                content_type,
+                tags,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to close content
+    pub fn close_content(
+        program_id: &Pubkey,
+        creator: &Pubkey,
+        content_id: &String,
+        nft_mint: Option<&Pubkey>,
+        tags: &[String],
+    ) -> Instruction {
+        // Derive PDA for content account
+        let (content_pubkey, _) = Content::find_address(program_id, creator, content_id);
+
+        // Build account metas
+        let mut accounts = vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new(content_pubkey, false),
+            AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+            AccountMeta::new(AnalyticsHistory::find_address(program_id, &content_pubkey).0, false),
+        ];
+
+        // Add NFT mint account if one was minted
+        if let Some(nft_mint) = nft_mint {
+            accounts.push(AccountMeta::new_readonly(*nft_mint, false));
+        }
+
+        // Add category accounts for each tag
+        accounts.extend(Self::tag_accounts(program_id, &content_pubkey, tags));
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: BlockTokInstruction::CloseContent.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Creates an instruction to migrate content to the current layout version
+    pub fn migrate_content(
+        program_id: &Pubkey,
+        creator: &Pubkey,
+        content_id: &String,
+    ) -> Instruction {
+        // Derive PDA for content account
+        let (content_pubkey, _) = Content::find_address(program_id, creator, content_id);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*creator, true),
+                AccountMeta::new(content_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+            ],
+            data: BlockTokInstruction::MigrateContent.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Creates an instruction to initialize the platform config
+    pub fn initialize_config(
+        program_id: &Pubkey,
+        upgrade_authority: &Pubkey,
+        treasury: Pubkey,
+        platform_fee_bps: u16,
+        features: u64,
+    ) -> Instruction {
+        // Derive PDAs for the config and program data accounts
+        let (config_pubkey, _) = PlatformConfig::find_address(program_id);
+        let (program_data_pubkey, _) =
+            Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*upgrade_authority, true),
+                AccountMeta::new(config_pubkey, false),
+                AccountMeta::new_readonly(program_data_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: BlockTokInstruction::InitializeConfig {
+                treasury,
+                platform_fee_bps,
+                features,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to update the platform config
+    pub fn update_config(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        treasury: Option<Pubkey>,
+        platform_fee_bps: Option<u16>,
+        features: Option<u64>,
+        min_analytics_interval: Option<u64>,
+        engagement_weights: Option<EngagementWeights>,
+        view_reward: Option<u64>,
+        emission_schedule: Option<EmissionSchedule>,
+    ) -> Instruction {
+        // Derive PDA for config account
+        let (config_pubkey, _) = PlatformConfig::find_address(program_id);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*admin, true),
+                AccountMeta::new(config_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: BlockTokInstruction::UpdateConfig {
+                treasury,
+                platform_fee_bps,
+                features,
+                min_analytics_interval,
+                engagement_weights,
+                view_reward,
+                emission_schedule,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to nominate a new platform admin
+    pub fn transfer_admin(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        new_admin: Pubkey,
+    ) -> Instruction {
+        // Derive PDA for config account
+        let (config_pubkey, _) = PlatformConfig::find_address(program_id);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*admin, true),
+                AccountMeta::new(config_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: BlockTokInstruction::TransferAdmin { new_admin }
+                .try_to_vec()
+                .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to accept a pending platform admin nomination
+    pub fn accept_admin(program_id: &Pubkey, pending_admin: &Pubkey) -> Instruction {
+        // Derive PDA for config account
+        let (config_pubkey, _) = PlatformConfig::find_address(program_id);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*pending_admin, true),
+                AccountMeta::new(config_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: BlockTokInstruction::AcceptAdmin.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Creates an instruction to pause or unpause the program
+    pub fn set_pause_state(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        paused: bool,
+        paused_instructions: u64,
+    ) -> Instruction {
+        // Derive PDA for config account
+        let (config_pubkey, _) = PlatformConfig::find_address(program_id);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*admin, true),
+                AccountMeta::new(config_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: BlockTokInstruction::SetPauseState {
+                paused,
+                paused_instructions,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to set the analytics oracles
+    pub fn set_analytics_oracles(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        oracles: Vec<Pubkey>,
+        allow_creator_analytics: bool,
+    ) -> Instruction {
+        // Derive PDA for config account
+        let (config_pubkey, _) = PlatformConfig::find_address(program_id);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*admin, true),
+                AccountMeta::new(config_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: BlockTokInstruction::SetAnalyticsOracles {
+                oracles,
+                allow_creator_analytics,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to create the analytics history of a content
+    pub fn initialize_analytics_history(
+        program_id: &Pubkey,
+        creator: &Pubkey,
+        content_id: &String,
+        granularity: HistoryGranularity,
+    ) -> Instruction {
+        // Derive PDAs for content and analytics history accounts
+        let (content_pubkey, _) = Content::find_address(program_id, creator, content_id);
+        let (history_pubkey, _) = AnalyticsHistory::find_address(program_id, &content_pubkey);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*creator, true),
+                AccountMeta::new_readonly(content_pubkey, false),
+                AccountMeta::new(history_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+            ],
+            data: BlockTokInstruction::InitializeAnalyticsHistory { granularity }
+                .try_to_vec()
+                .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to record a view of content by `viewer`
+    pub fn record_view(
+        program_id: &Pubkey,
+        viewer: &Pubkey,
+        creator: &Pubkey,
+        content_id: &String,
+        watch_progress_bps: u16,
+    ) -> Instruction {
+        // Derive PDAs for content, view receipt and analytics history accounts
+        let (content_pubkey, _) = Content::find_address(program_id, creator, content_id);
+        let (receipt_pubkey, _) = ViewReceipt::find_address(program_id, &content_pubkey, viewer);
+        let (history_pubkey, _) = AnalyticsHistory::find_address(program_id, &content_pubkey);
+        let (rewards_pubkey, _) = UserRewards::find_address(program_id, viewer);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*viewer, true),
+                AccountMeta::new(content_pubkey, false),
+                AccountMeta::new(receipt_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+                AccountMeta::new(history_pubkey, false),
+                AccountMeta::new(rewards_pubkey, false),
+            ],
+            data: BlockTokInstruction::RecordView { watch_progress_bps }
+                .try_to_vec()
+                .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to commit an engagement epoch
+    pub fn commit_engagement_epoch(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        epoch: u64,
+        start_time: u64,
+        end_time: u64,
+        merkle_root: [u8; 32],
+        event_count: u64,
+    ) -> Instruction {
+        let (epoch_pubkey, _) = EngagementEpoch::find_address(program_id, epoch);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(epoch_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+            ],
+            data: BlockTokInstruction::CommitEngagementEpoch {
+                epoch,
+                start_time,
+                end_time,
+                merkle_root,
+                event_count,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to record a content account's totals for an engagement epoch
+    pub fn record_epoch_totals(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        epoch: u64,
+        content: &Pubkey,
+        views: u64,
+        likes: u64,
+        shares: u64,
+        comments: u64,
+    ) -> Instruction {
+        // Derive PDAs for engagement epoch and totals accounts
+        let (epoch_pubkey, _) = EngagementEpoch::find_address(program_id, epoch);
+        let (totals_pubkey, _) = EpochContentTotals::find_address(program_id, &epoch_pubkey, content);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new_readonly(epoch_pubkey, false),
+                AccountMeta::new_readonly(*content, false),
+                AccountMeta::new(totals_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+            ],
+            data: BlockTokInstruction::RecordEpochTotals {
+                views,
+                likes,
+                shares,
+                comments,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to verify an engagement event's inclusion in an epoch
+    pub fn verify_engagement(
+        program_id: &Pubkey,
+        epoch: u64,
+        event: EngagementEvent,
+        proof: Vec<[u8; 32]>,
+    ) -> Instruction {
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![AccountMeta::new_readonly(
+                EngagementEpoch::find_address(program_id, epoch).0,
+                false,
+            )],
+            data: BlockTokInstruction::VerifyEngagement { event, proof }
+                .try_to_vec()
+                .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to update the analytics of many contents at once
+    pub fn batch_update_analytics(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        updates: Vec<ContentAnalyticsUpdate>,
+    ) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+        ];
+        for update in &updates {
+            accounts.push(AccountMeta::new(update.content, false));
+            accounts.push(AccountMeta::new(
+                AnalyticsHistory::find_address(program_id, &update.content).0,
+                false,
+            ));
+        }
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: BlockTokInstruction::BatchUpdateAnalytics { updates }
+                .try_to_vec()
+                .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to create the reward token mint
+    pub fn initialize_reward_mint(program_id: &Pubkey, admin: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*admin, true),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+                AccountMeta::new(find_reward_mint_address(program_id).0, false),
+                AccountMeta::new_readonly(find_reward_mint_authority_address(program_id).0, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+            data: BlockTokInstruction::InitializeRewardMint.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Creates an instruction to mint reward tokens to `recipient_token_account`
+    pub fn mint_rewards(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        recipient_token_account: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(PlatformConfig::find_address(program_id).0, false),
+                AccountMeta::new(find_reward_mint_address(program_id).0, false),
+                AccountMeta::new_readonly(find_reward_mint_authority_address(program_id).0, false),
+                AccountMeta::new(*recipient_token_account, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+            data: BlockTokInstruction::MintRewards { amount }.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Creates an instruction to accrue reward credits to `user`
+    pub fn accrue_rewards(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        user: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+                AccountMeta::new_readonly(*user, false),
+                AccountMeta::new(UserRewards::find_address(program_id, user).0, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: BlockTokInstruction::AccrueRewards { amount }.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Creates an instruction to claim the unclaimed reward credits of `user`
+    pub fn claim_rewards(program_id: &Pubkey, user: &Pubkey) -> Instruction {
+        let (reward_mint_pubkey, _) = find_reward_mint_address(program_id);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*user, true),
+                AccountMeta::new(PlatformConfig::find_address(program_id).0, false),
+                AccountMeta::new(UserRewards::find_address(program_id, user).0, false),
+                AccountMeta::new(reward_mint_pubkey, false),
+                AccountMeta::new_readonly(find_reward_mint_authority_address(program_id).0, false),
+                AccountMeta::new(get_associated_token_address(user, &reward_mint_pubkey), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            ],
+            data: BlockTokInstruction::ClaimRewards.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Creates an instruction to create the reward distributor of `epoch`,
+    /// funded from `source_token_account`
+    pub fn create_reward_distributor(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        source_token_account: &Pubkey,
+        epoch: u64,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_recipients: u32,
+        expires_at: u64,
+    ) -> Instruction {
+        let (distributor_pubkey, _) = RewardDistributor::find_address(program_id, epoch);
+        let (vault_pubkey, _) =
+            RewardDistributor::find_vault_address(program_id, &distributor_pubkey);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+                AccountMeta::new(distributor_pubkey, false),
+                AccountMeta::new(vault_pubkey, false),
+                AccountMeta::new_readonly(find_reward_mint_address(program_id).0, false),
+                AccountMeta::new(*source_token_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+            data: BlockTokInstruction::CreateRewardDistributor {
+                epoch,
+                merkle_root,
+                total_amount,
+                num_recipients,
+                expires_at,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to claim `user`'s entry of the reward
+    /// distributor of `epoch`
+    pub fn claim_distribution(
+        program_id: &Pubkey,
+        user: &Pubkey,
+        epoch: u64,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Instruction {
+        let (distributor_pubkey, _) = RewardDistributor::find_address(program_id, epoch);
+        let (vault_pubkey, _) =
+            RewardDistributor::find_vault_address(program_id, &distributor_pubkey);
+        let (reward_mint_pubkey, _) = find_reward_mint_address(program_id);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*user, true),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+                AccountMeta::new(distributor_pubkey, false),
+                AccountMeta::new(vault_pubkey, false),
+                AccountMeta::new_readonly(reward_mint_pubkey, false),
+                AccountMeta::new(get_associated_token_address(user, &reward_mint_pubkey), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            ],
+            data: BlockTokInstruction::ClaimDistribution {
+                index,
+                amount,
+                proof,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to claw back the unclaimed tokens of the reward
+    /// distributor of `epoch` into `destination_token_account`
+    pub fn clawback_distribution(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        epoch: u64,
+        destination_token_account: &Pubkey,
+    ) -> Instruction {
+        let (distributor_pubkey, _) = RewardDistributor::find_address(program_id, epoch);
+        let (vault_pubkey, _) =
+            RewardDistributor::find_vault_address(program_id, &distributor_pubkey);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+                AccountMeta::new(distributor_pubkey, false),
+                AccountMeta::new(vault_pubkey, false),
+                AccountMeta::new(*destination_token_account, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+            data: BlockTokInstruction::ClawbackDistribution.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Creates an instruction to credit `amount` of earnings of `content` to
+    /// `payees`, as returned by `RoyaltyDistribution::payees` for the content
+    pub fn accrue_creator_earnings(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        content: &Pubkey,
+        payees: &[Pubkey],
+        amount: u64,
+    ) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+            AccountMeta::new_readonly(*content, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ];
+        for payee in payees {
+            accounts.push(AccountMeta::new(UserRewards::find_address(program_id, payee).0, false));
+        }
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: BlockTokInstruction::AccrueCreatorEarnings { amount }.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Creates an instruction to tip `amount` reward tokens from
+    /// `tipper_token_account` to `content`, split between `payees`, as
+    /// returned by `RoyaltyDistribution::payees` for the content
+    pub fn tip_content(
+        program_id: &Pubkey,
+        tipper: &Pubkey,
+        tipper_token_account: &Pubkey,
+        content: &Pubkey,
+        payees: &[Pubkey],
+        amount: u64,
+    ) -> Instruction {
+        let (reward_mint_pubkey, _) = find_reward_mint_address(program_id);
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*tipper, true),
+            AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+            AccountMeta::new_readonly(*content, false),
+            AccountMeta::new(*tipper_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ];
+        for payee in payees {
+            let payee_token_pubkey = get_associated_token_address(payee, &reward_mint_pubkey);
+            accounts.push(AccountMeta::new(payee_token_pubkey, false));
+        }
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: BlockTokInstruction::TipContent { amount }.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Creates an instruction to register a category
+    pub fn create_category(program_id: &Pubkey, admin: &Pubkey, name: String) -> Instruction {
+        // Derive PDAs for config and category accounts
+        let (config_pubkey, _) = PlatformConfig::find_address(program_id);
+        let (category_pubkey, _) = Category::find_address(program_id, &name);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*admin, true),
+                AccountMeta::new_readonly(config_pubkey, false),
+                AccountMeta::new(category_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: BlockTokInstruction::CreateCategory { name }.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Creates an instruction to activate or deactivate a category
+    pub fn set_category_active(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        name: String,
+        active: bool,
+    ) -> Instruction {
+        // Derive PDAs for config and category accounts
+        let (config_pubkey, _) = PlatformConfig::find_address(program_id);
+        let (category_pubkey, _) = Category::find_address(program_id, &name);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*admin, true),
+                AccountMeta::new_readonly(config_pubkey, false),
+                AccountMeta::new(category_pubkey, false),
+            ],
+            data: BlockTokInstruction::SetCategoryActive { name, active }
+                .try_to_vec()
+                .unwrap(),
+        }
+    }
+
+    /// Creates an instruction to publish content
+    pub fn publish(
+        program_id: &Pubkey,
+        creator: &Pubkey,
+        content_id: &String,
+        publish_at: Option<u64>,
+    ) -> Instruction {
+        Self::content_status_instruction(
+            program_id,
+            creator,
+            content_id,
+            BlockTokInstruction::Publish { publish_at },
+        )
+    }
+
+    /// Creates an instruction to unlist content
+    pub fn unlist(program_id: &Pubkey, creator: &Pubkey, content_id: &String) -> Instruction {
+        Self::content_status_instruction(program_id, creator, content_id, BlockTokInstruction::Unlist)
+    }
+
+    /// Creates an instruction to archive content
+    pub fn archive(program_id: &Pubkey, creator: &Pubkey, content_id: &String) -> Instruction {
+        Self::content_status_instruction(program_id, creator, content_id, BlockTokInstruction::Archive)
+    }
+
+    /// Creates an instruction to add deltas to content analytics.
+    ///
+    /// `authority` signs: the creator, or an analytics oracle for any creator's content.
+    pub fn increment_analytics(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        creator: &Pubkey,
+        content_id: &String,
+        views: u64,
+        likes: u64,
+        shares: u64,
+        comments: u64,
+        watch: WatchMetrics,
+    ) -> Instruction {
+        // Derive PDA for content account
+        let (content_pubkey, _) = Content::find_address(program_id, creator, content_id);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(content_pubkey, false),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+                AccountMeta::new(AnalyticsHistory::find_address(program_id, &content_pubkey).0, false),
+            ],
+            data: BlockTokInstruction::IncrementAnalytics {
+                views,
+                likes,
+                shares,
+                comments,
+                watch,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Creates an Ed25519 program instruction verifying an analytics oracle's
+    /// signature over an attestation. It must immediately precede the
+    /// `UpdateAnalytics` instruction relaying the attestation
+    pub fn verify_analytics_attestation(
+        oracle: &Pubkey,
+        signature: &[u8; 64],
+        attestation: &AnalyticsAttestation,
+    ) -> Instruction {
+        let message = attestation.message();
+        
+        // One signature, with the public key, signature and message following
+        // the offsets in this instruction's own data (index `u16::MAX`)
+        let public_key_offset = ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE;
+        let signature_offset = public_key_offset + PUBKEY_BYTES;
+        let message_offset = signature_offset + signature.len();
+        let mut data = vec![1, 0];
+        for offset in [
+            signature_offset,
+            u16::MAX as usize,
+            public_key_offset,
+            u16::MAX as usize,
+            message_offset,
+            message.len(),
+            u16::MAX as usize,
+        ] {
+            data.extend_from_slice(&(offset as u16).to_le_bytes());
+        }
+        data.extend_from_slice(oracle.as_ref());
+        data.extend_from_slice(signature);
+        data.extend_from_slice(&message);
+        
+        Instruction {
+            program_id: ed25519_program::id(),
+            accounts: vec![],
+            data,
+        }
+    }
+
+    /// Instruction changing a content's status, which all take the same accounts
+    fn content_status_instruction(
+        program_id: &Pubkey,
+        creator: &Pubkey,
+        content_id: &String,
+        instruction: BlockTokInstruction,
+    ) -> Instruction {
+        // Derive PDA for content account
+        let (content_pubkey, _) = Content::find_address(program_id, creator, content_id);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*creator, true),
+                AccountMeta::new(content_pubkey, false),
+                AccountMeta::new_readonly(PlatformConfig::find_address(program_id).0, false),
+            ],
+            data: instruction.try_to_vec().unwrap(),
+        }
+    }
+
+    /// Category and category index account metas for each tag on a content
+    fn tag_accounts<'a>(
+        program_id: &Pubkey,
+        content: &Pubkey,
+        tags: impl IntoIterator<Item = &'a String>,
+    ) -> Vec<AccountMeta> {
+        tags.into_iter()
+            .flat_map(|tag| {
+                let (category_pubkey, _) = Category::find_address(program_id, tag);
+                let (category_index_pubkey, _) =
+                    CategoryIndex::find_address(program_id, &category_pubkey, content);
+                [
+                    AccountMeta::new(category_pubkey, false),
+                    AccountMeta::new(category_index_pubkey, false),
+                ]
+            })
+            .collect()
+    }
+}
@@ -13,6 +13,7 @@ use crate::processor::Processor;
 
 pub mod error;
 pub mod instruction;
+pub mod merkle;
 pub mod processor;
 pub mod state;
 
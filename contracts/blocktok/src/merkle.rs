@@ -0,0 +1,34 @@
+//! Merkle proofs over SHA-256, shared by the commitments the program accepts
+//! from off-chain systems.
+//!
+//! Leaves and inner nodes are hashed with distinct prefixes so a leaf can
+//! never be passed off as an inner node, and each pair of children is hashed
+//! in sorted order so a proof is just the list of siblings from the leaf up.
+
+use solana_program::hash::hashv;
+
+/// Prefix of every leaf hash
+const LEAF_PREFIX: &[u8] = &[0];
+
+/// Prefix of every inner node hash
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Hash serialized leaf data into a leaf of the tree
+pub fn hash_leaf(data: &[u8]) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, data]).to_bytes()
+}
+
+/// Hash two children into their parent node
+pub fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if left <= right { (left, right) } else { (right, left) };
+    hashv(&[NODE_PREFIX, first, second]).to_bytes()
+}
+
+/// Check that `leaf` is in the tree with `root`, given its siblings from the
+/// bottom of the tree up
+pub fn verify_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_node(&node, sibling));
+    computed == *root
+}
@@ -1,26 +1,54 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    ed25519_program,
     entrypoint::ProgramResult,
     msg,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
     program_pack::Pack,
-    pubkey::Pubkey,
+    pubkey::{Pubkey, PUBKEY_BYTES},
     rent::Rent,
-    system_instruction,
-    sysvar::Sysvar,
+    system_instruction, system_program,
+    sysvar::{instructions, Sysvar},
 };
 
-use borsh::{BorshDeserialize, BorshSerialize};
-use spl_token::{instruction as token_instruction, state::Mint};
-use spl_associated_token_account::instruction as associated_token_account_instruction;
+use std::str::FromStr;
+use spl_token::{
+    instruction as token_instruction,
+    state::{Account as TokenAccount, Mint},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction as associated_token_account_instruction,
+};
 
 use crate::{
     error::BlockTokError,
-    instruction::BlockTokInstruction,
-    state::{Content, ContentAnalytics, RoyaltyDistribution, RoyaltyRecipient},
+    instruction::{
+        AttestationTerms, BlockTokInstruction, ContentAnalyticsUpdate, ED25519_OFFSETS_SIZE,
+        ED25519_OFFSETS_START,
+    },
+    merkle,
+    state::{
+        content_id_seed, find_reward_mint_address, find_reward_mint_authority_address, tag_changes,
+        AccountState, AnalyticsAttestation, AnalyticsHistory, Category, CategoryIndex, Content,
+        ContentAnalytics, ContentIndex, ContentStatus, CreatorProfile, DistributionEntry,
+        EmissionSchedule, EngagementEpoch, EngagementEvent, EngagementWeights, EpochContentTotals,
+        HistoryGranularity, PlatformConfig, RewardDistributor, RoyaltyDistribution, UserRewards,
+        ViewReceipt, WatchMetrics, ANALYTICS_HISTORY_SEED, CATEGORY_INDEX_SEED, CATEGORY_SEED,
+        CONTENT_INDEX_SEED, CONTENT_SEED, CREATOR_PROFILE_SEED, DISTRIBUTOR_VAULT_SEED,
+        ENGAGEMENT_EPOCH_SEED, EPOCH_TOTALS_SEED, MAX_ANALYTICS_ORACLES,
+        MAX_BATCH_ANALYTICS_UPDATES, MAX_CONTENT_TAGS, MAX_DISTRIBUTOR_RECIPIENTS,
+        MAX_PLATFORM_FEE_BPS, MAX_ROYALTY_RECIPIENTS, MAX_WATCH_PROGRESS_BPS, PLATFORM_CONFIG_SEED,
+        REWARD_DISTRIBUTOR_SEED, REWARD_MINT_AUTHORITY_SEED, REWARD_MINT_SEED,
+        REWARD_TOKEN_DECIMALS, USER_REWARDS_SEED, VIEW_RECEIPT_SEED,
+    },
 };
 
+/// Bincode variant tag of `UpgradeableLoaderState::ProgramData`
+const PROGRAM_DATA_VARIANT: u32 = 3;
+
 /// Program processor
 pub struct Processor;
 
@@ -31,6 +59,11 @@ impl Processor {
         accounts: &[AccountInfo],
         instruction_data: BlockTokInstruction,
     ) -> ProgramResult {
+        // Refuse paused instructions before touching any other account
+        if let Some(pause_flag) = instruction_data.pause_flag() {
+            Self::check_not_paused(program_id, accounts, pause_flag)?;
+        }
+        
         match instruction_data {
             BlockTokInstruction::InitializeContent {
                 content_id,
@@ -38,7 +71,7 @@ impl Processor {
                 description,
                 content_url,
                 content_type,
-                created_at,
+                tags,
             } => Self::process_initialize_content(
                 program_id,
                 accounts,
@@ -47,7 +80,7 @@ impl Processor {
                 description,
                 content_url,
                 content_type,
-                created_at,
+                tags,
             ),
             
             BlockTokInstruction::MintNFT {
@@ -69,7 +102,8 @@ impl Processor {
                 likes,
                 shares,
                 comments,
-                updated_at,
+                watch,
+                attestation,
             } => Self::process_update_analytics(
                 program_id,
                 accounts,
@@ -77,7 +111,8 @@ impl Processor {
                 likes,
                 shares,
                 comments,
-                updated_at,
+                watch,
+                attestation,
             ),
             
             BlockTokInstruction::SetRoyaltyDistribution {
@@ -87,6 +122,224 @@ impl Processor {
                 accounts,
                 royalty_recipients,
             ),
+            
+            BlockTokInstruction::UpdateContentMetadata {
+                title,
+                description,
+                content_url,
+                content_type,
+                tags,
+            } => Self::process_update_content_metadata(
+                program_id,
+                accounts,
+                title,
+                description,
+                content_url,
+                content_type,
+                tags,
+            ),
+            
+            BlockTokInstruction::CloseContent => Self::process_close_content(
+                program_id,
+                accounts,
+            ),
+            
+            BlockTokInstruction::MigrateContent => Self::process_migrate_content(
+                program_id,
+                accounts,
+            ),
+            
+            BlockTokInstruction::InitializeConfig {
+                treasury,
+                platform_fee_bps,
+                features,
+            } => Self::process_initialize_config(
+                program_id,
+                accounts,
+                treasury,
+                platform_fee_bps,
+                features,
+            ),
+            
+            BlockTokInstruction::UpdateConfig {
+                treasury,
+                platform_fee_bps,
+                features,
+                min_analytics_interval,
+                engagement_weights,
+                view_reward,
+                emission_schedule,
+            } => Self::process_update_config(
+                program_id,
+                accounts,
+                treasury,
+                platform_fee_bps,
+                features,
+                min_analytics_interval,
+                engagement_weights,
+                view_reward,
+                emission_schedule,
+            ),
+            
+            BlockTokInstruction::TransferAdmin { new_admin } => Self::process_transfer_admin(
+                program_id,
+                accounts,
+                new_admin,
+            ),
+            
+            BlockTokInstruction::AcceptAdmin => Self::process_accept_admin(
+                program_id,
+                accounts,
+            ),
+            
+            BlockTokInstruction::SetPauseState {
+                paused,
+                paused_instructions,
+            } => Self::process_set_pause_state(
+                program_id,
+                accounts,
+                paused,
+                paused_instructions,
+            ),
+            
+            BlockTokInstruction::CreateCategory { name } => Self::process_create_category(
+                program_id,
+                accounts,
+                name,
+            ),
+            
+            BlockTokInstruction::SetCategoryActive { name, active } => {
+                Self::process_set_category_active(program_id, accounts, name, active)
+            }
+            
+            BlockTokInstruction::Publish { publish_at } => Self::process_publish(
+                program_id,
+                accounts,
+                publish_at,
+            ),
+            
+            BlockTokInstruction::Unlist => Self::process_unlist(
+                program_id,
+                accounts,
+            ),
+            
+            BlockTokInstruction::Archive => Self::process_archive(
+                program_id,
+                accounts,
+            ),
+            
+            BlockTokInstruction::IncrementAnalytics {
+                views,
+                likes,
+                shares,
+                comments,
+                watch,
+            } => Self::process_increment_analytics(
+                program_id,
+                accounts,
+                views,
+                likes,
+                shares,
+                comments,
+                watch,
+            ),
+            
+            BlockTokInstruction::SetAnalyticsOracles {
+                oracles,
+                allow_creator_analytics,
+            } => Self::process_set_analytics_oracles(
+                program_id,
+                accounts,
+                oracles,
+                allow_creator_analytics,
+            ),
+            
+            BlockTokInstruction::InitializeAnalyticsHistory { granularity } => {
+                Self::process_initialize_analytics_history(program_id, accounts, granularity)
+            }
+            
+            BlockTokInstruction::RecordView { watch_progress_bps } => {
+                Self::process_record_view(program_id, accounts, watch_progress_bps)
+            }
+            
+            BlockTokInstruction::CommitEngagementEpoch {
+                epoch,
+                start_time,
+                end_time,
+                merkle_root,
+                event_count,
+            } => Self::process_commit_engagement_epoch(
+                program_id,
+                accounts,
+                epoch,
+                start_time,
+                end_time,
+                merkle_root,
+                event_count,
+            ),
+            
+            BlockTokInstruction::RecordEpochTotals {
+                views,
+                likes,
+                shares,
+                comments,
+            } => Self::process_record_epoch_totals(program_id, accounts, views, likes, shares, comments),
+            
+            BlockTokInstruction::VerifyEngagement { event, proof } => {
+                Self::process_verify_engagement(program_id, accounts, event, proof)
+            }
+            
+            BlockTokInstruction::BatchUpdateAnalytics { updates } => {
+                Self::process_batch_update_analytics(program_id, accounts, updates)
+            }
+            
+            BlockTokInstruction::InitializeRewardMint => {
+                Self::process_initialize_reward_mint(program_id, accounts)
+            }
+            
+            BlockTokInstruction::MintRewards { amount } => {
+                Self::process_mint_rewards(program_id, accounts, amount)
+            }
+            
+            BlockTokInstruction::AccrueRewards { amount } => {
+                Self::process_accrue_rewards(program_id, accounts, amount)
+            }
+            
+            BlockTokInstruction::ClaimRewards => Self::process_claim_rewards(program_id, accounts),
+            
+            BlockTokInstruction::CreateRewardDistributor {
+                epoch,
+                merkle_root,
+                total_amount,
+                num_recipients,
+                expires_at,
+            } => Self::process_create_reward_distributor(
+                program_id,
+                accounts,
+                epoch,
+                merkle_root,
+                total_amount,
+                num_recipients,
+                expires_at,
+            ),
+            
+            BlockTokInstruction::ClaimDistribution {
+                index,
+                amount,
+                proof,
+            } => Self::process_claim_distribution(program_id, accounts, index, amount, proof),
+            
+            BlockTokInstruction::ClawbackDistribution => {
+                Self::process_clawback_distribution(program_id, accounts)
+            }
+            
+            BlockTokInstruction::AccrueCreatorEarnings { amount } => {
+                Self::process_accrue_creator_earnings(program_id, accounts, amount)
+            }
+            
+            BlockTokInstruction::TipContent { amount } => {
+                Self::process_tip_content(program_id, accounts, amount)
+            }
         }
     }
     
@@ -99,7 +352,7 @@ impl Processor {
         description: String,
         content_url: String,
         content_type: String,
-        created_at: u64,
+        tags: Vec<String>,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         
@@ -107,23 +360,43 @@ impl Processor {
         let creator_info = next_account_info(account_info_iter)?;
         let content_account_info = next_account_info(account_info_iter)?;
         let system_program_info = next_account_info(account_info_iter)?;
+        let creator_profile_info = next_account_info(account_info_iter)?;
+        let content_index_info = next_account_info(account_info_iter)?;
+        // Platform config is checked in `Processor::process`
+        let _platform_config_info = next_account_info(account_info_iter)?;
         
         // Check creator is signer
         if !creator_info.is_signer {
             return Err(BlockTokError::Unauthorized.into());
         }
         
+        // Validate tags
+        Self::validate_tags(&tags)?;
+        
         // Derive PDA for content account
-        let (content_pubkey, bump_seed) = Pubkey::find_program_address(
-            &[b"content", creator_info.key.as_ref(), content_id.as_bytes()],
-            program_id,
-        );
+        let (content_pubkey, bump_seed) =
+            Content::find_address(program_id, creator_info.key, &content_id);
+        let content_id_seed = content_id_seed(&content_id);
         
         // Verify content account
         if content_pubkey != *content_account_info.key {
             return Err(ProgramError::InvalidAccountData);
         }
         
+        // Content created under the ID before IDs were hashed lives at the
+        // legacy address, so the ID is already taken if that account exists
+        if let Some((legacy_pubkey, _)) =
+            Content::find_legacy_address(program_id, creator_info.key, &content_id)
+        {
+            let legacy_content_info = next_account_info(account_info_iter)?;
+            if legacy_pubkey != *legacy_content_info.key {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            if legacy_content_info.owner == program_id && !legacy_content_info.data_is_empty() {
+                return Err(BlockTokError::ContentAlreadyExists.into());
+            }
+        }
+        
         // Calculate account size
         let account_size = Content::get_account_size(
             &content_id,
@@ -131,27 +404,18 @@ impl Processor {
             &description,
             &content_url,
             &content_type,
+            0,
+            &tags,
         );
         
         // Create content account
-        let rent = Rent::get()?;
-        let rent_lamports = rent.minimum_balance(account_size);
-        
-        // Create PDA account
-        invoke_signed(
-            &system_instruction::create_account(
-                creator_info.key,
-                &content_pubkey,
-                rent_lamports,
-                account_size as u64,
-                program_id,
-            ),
-            &[
-                creator_info.clone(),
-                content_account_info.clone(),
-                system_program_info.clone(),
-            ],
-            &[&[b"content", creator_info.key.as_ref(), content_id.as_bytes(), &[bump_seed]]],
+        Self::create_pda_account(
+            program_id,
+            creator_info,
+            content_account_info,
+            system_program_info,
+            account_size,
+            &[CONTENT_SEED, creator_info.key.as_ref(), &content_id_seed, &[bump_seed]],
         )?;
         
         // Initialize content data
@@ -162,14 +426,89 @@ impl Processor {
             description,
             content_url,
             content_type,
-            created_at,
+            created_at: Clock::get()?.unix_timestamp as u64,
             nft_mint: None,
             analytics: ContentAnalytics::default(),
             royalty_distribution: RoyaltyDistribution::default(),
+            tags,
+            status: ContentStatus::Draft,
+            publish_at: 0,
+            analytics_nonce: 0,
         };
         
         // Save content data
-        content.serialize(&mut *content_account_info.data.borrow_mut())?;
+        content.pack(&mut content_account_info.data.borrow_mut())?;
+        
+        // Add the content to each tagged category
+        for tag in &content.tags {
+            let category_info = next_account_info(account_info_iter)?;
+            let category_index_info = next_account_info(account_info_iter)?;
+            Self::add_content_tag(
+                program_id,
+                creator_info,
+                system_program_info,
+                &content_pubkey,
+                tag,
+                category_info,
+                category_index_info,
+            )?;
+        }
+        
+        // Create the creator profile on first use, otherwise load it
+        let mut creator_profile = if creator_profile_info.data_is_empty() {
+            let (creator_profile_pubkey, creator_profile_bump) =
+                CreatorProfile::find_address(program_id, creator_info.key);
+            if creator_profile_pubkey != *creator_profile_info.key {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            Self::create_pda_account(
+                program_id,
+                creator_info,
+                creator_profile_info,
+                system_program_info,
+                CreatorProfile::size(),
+                &[CREATOR_PROFILE_SEED, creator_info.key.as_ref(), &[creator_profile_bump]],
+            )?;
+            CreatorProfile {
+                creator: creator_info.key.to_bytes(),
+                content_count: 0,
+            }
+        } else {
+            Self::load_creator_profile(program_id, creator_profile_info, creator_info.key)?
+        };
+        
+        // Point the next catalogue index at the new content
+        let index = creator_profile.content_count;
+        let (content_index_pubkey, content_index_bump) =
+            ContentIndex::find_address(program_id, creator_info.key, index);
+        if content_index_pubkey != *content_index_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Self::create_pda_account(
+            program_id,
+            creator_info,
+            content_index_info,
+            system_program_info,
+            ContentIndex::size(),
+            &[
+                CONTENT_INDEX_SEED,
+                creator_info.key.as_ref(),
+                &index.to_le_bytes(),
+                &[content_index_bump],
+            ],
+        )?;
+        let content_index = ContentIndex {
+            creator: creator_info.key.to_bytes(),
+            index,
+            content: content_pubkey.to_bytes(),
+        };
+        content_index.pack(&mut content_index_info.data.borrow_mut())?;
+        
+        // Advance the creator's content counter
+        creator_profile.content_count = index
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        creator_profile.pack(&mut creator_profile_info.data.borrow_mut())?;
         
         msg!("Content initialized successfully");
         
@@ -205,13 +544,18 @@ impl Processor {
         }
         
         // Load content account data
-        let mut content = Content::try_from_slice(&content_account_info.data.borrow())?;
+        let mut content = Self::load_content(program_id, content_account_info)?;
         
         // Check if creator is the owner
         if content.get_creator() != *creator_info.key {
             return Err(BlockTokError::Unauthorized.into());
         }
         
+        // Only published content can be minted
+        if !content.is_published(Clock::get()?.unix_timestamp as u64) {
+            return Err(BlockTokError::ContentNotPublished.into());
+        }
+        
         // Check if NFT already minted
         if content.nft_mint.is_some() {
             return Err(ProgramError::AccountAlreadyInitialized);
@@ -321,7 +665,7 @@ impl Processor {
         
         // Update content with NFT mint
         content.set_nft_mint(mint_info.key);
-        content.serialize(&mut *content_account_info.data.borrow_mut())?;
+        content.pack(&mut content_account_info.data.borrow_mut())?;
         
         msg!("NFT minted successfully");
         
@@ -336,38 +680,130 @@ impl Processor {
         likes: u64,
         shares: u64,
         comments: u64,
-        updated_at: u64,
+        watch: WatchMetrics,
+        attestation: Option<AttestationTerms>,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         
         // Get accounts
         let authority_info = next_account_info(account_info_iter)?;
         let content_account_info = next_account_info(account_info_iter)?;
+        let platform_config_info = next_account_info(account_info_iter)?;
+        let history_info = next_account_info(account_info_iter)?;
         
-        // Check authority is signer
-        if !authority_info.is_signer {
-            return Err(BlockTokError::Unauthorized.into());
+        // Load content account data
+        let mut content = Self::load_content(program_id, content_account_info)?;
+        let config = Self::load_platform_config_if_initialized(program_id, platform_config_info)?;
+        let now = Clock::get()?.unix_timestamp as u64;
+        
+        match attestation {
+            // Counters signed by an oracle, so the relaying authority needs no role
+            Some(terms) => {
+                let instructions_info = next_account_info(account_info_iter)?;
+                let attestation = AnalyticsAttestation {
+                    content: content_account_info.key.to_bytes(),
+                    views,
+                    likes,
+                    shares,
+                    comments,
+                    watch,
+                    nonce: terms.nonce,
+                    expiry: terms.expiry,
+                };
+                Self::verify_analytics_attestation(instructions_info, config.as_ref(), &attestation)?;
+                
+                if now > attestation.expiry {
+                    return Err(BlockTokError::AttestationExpired.into());
+                }
+                
+                // Each attestation can be applied once, and only after older ones
+                if attestation.nonce <= content.analytics_nonce {
+                    return Err(BlockTokError::AttestationReplayed.into());
+                }
+                content.analytics_nonce = attestation.nonce;
+            }
+            // Check authority is an oracle, or the creator if creator reports are allowed
+            None => Self::check_analytics_authority(authority_info, &content, config.as_ref())?,
         }
         
+        // Only published content collects analytics
+        if !content.is_published(now) {
+            return Err(BlockTokError::ContentNotPublished.into());
+        }
+        Self::check_analytics_interval(&content, config.as_ref(), now)?;
+        
+        // Update analytics, which may only grow
+        let previous = content.analytics.clone();
+        content.analytics.set(views, likes, shares, comments, &watch)?;
+        content.analytics.updated_at = now;
+        Self::refresh_engagement_score(&mut content, config.as_ref(), now);
+        
+        // Save content data and its history
+        content.pack(&mut content_account_info.data.borrow_mut())?;
+        Self::record_analytics_history(
+            program_id,
+            history_info,
+            content_account_info.key,
+            now,
+            &previous,
+            &content.analytics,
+        )?;
+        
+        msg!("Analytics updated successfully");
+        
+        Ok(())
+    }
+    
+    /// Process IncrementAnalytics instruction
+    pub fn process_increment_analytics(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        views: u64,
+        likes: u64,
+        shares: u64,
+        comments: u64,
+        watch: WatchMetrics,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let authority_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        let platform_config_info = next_account_info(account_info_iter)?;
+        let history_info = next_account_info(account_info_iter)?;
+        
         // Load content account data
-        let mut content = Content::try_from_slice(&content_account_info.data.borrow())?;
+        let mut content = Self::load_content(program_id, content_account_info)?;
         
-        // Check if authority is the owner
-        if content.get_creator() != *authority_info.key {
-            return Err(BlockTokError::Unauthorized.into());
+        // Check authority is an oracle, or the creator if creator reports are allowed
+        let config = Self::load_platform_config_if_initialized(program_id, platform_config_info)?;
+        Self::check_analytics_authority(authority_info, &content, config.as_ref())?;
+        
+        // Only published content collects analytics
+        let now = Clock::get()?.unix_timestamp as u64;
+        if !content.is_published(now) {
+            return Err(BlockTokError::ContentNotPublished.into());
         }
+        Self::check_analytics_interval(&content, config.as_ref(), now)?;
         
-        // Update analytics
-        content.analytics.views = views;
-        content.analytics.likes = likes;
-        content.analytics.shares = shares;
-        content.analytics.comments = comments;
-        content.analytics.updated_at = updated_at;
+        // Apply deltas
+        let previous = content.analytics.clone();
+        content.analytics.increment(views, likes, shares, comments, &watch)?;
+        content.analytics.updated_at = now;
+        Self::refresh_engagement_score(&mut content, config.as_ref(), now);
         
-        // Save content data
-        content.serialize(&mut *content_account_info.data.borrow_mut())?;
+        // Save content data and its history
+        content.pack(&mut content_account_info.data.borrow_mut())?;
+        Self::record_analytics_history(
+            program_id,
+            history_info,
+            content_account_info.key,
+            now,
+            &previous,
+            &content.analytics,
+        )?;
         
-        msg!("Analytics updated successfully");
+        msg!("Analytics incremented successfully");
         
         Ok(())
     }
@@ -383,6 +819,10 @@ impl Processor {
         // Get accounts
         let creator_info = next_account_info(account_info_iter)?;
         let content_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        // Platform config is checked in `Processor::process`
+        let _platform_config_info = next_account_info(account_info_iter)?;
+        let recipient_accounts = account_info_iter.as_slice();
         
         // Check creator is signer
         if !creator_info.is_signer {
@@ -390,21 +830,28 @@ impl Processor {
         }
         
         // Load content account data
-        let mut content = Content::try_from_slice(&content_account_info.data.borrow())?;
+        let mut content = Self::load_content(program_id, content_account_info)?;
         
         // Check if creator is the owner
         if content.get_creator() != *creator_info.key {
             return Err(BlockTokError::Unauthorized.into());
         }
         
+        // Check recipient count against the maximum and the accounts passed
+        if royalty_recipients.len() > MAX_ROYALTY_RECIPIENTS {
+            return Err(BlockTokError::TooManyRoyaltyRecipients.into());
+        }
+        if royalty_recipients.len() != recipient_accounts.len() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        
         // Create new royalty distribution
         let mut royalty_distribution = RoyaltyDistribution::default();
         
         // Add all recipients
-        let recipient_accounts = accounts.iter().skip(2);
         for ((pubkey_str, percentage), account_info) in royalty_recipients.iter().zip(recipient_accounts) {
             // Parse pubkey from string
-            let pubkey = match Pubkey::try_from_slice(pubkey_str.as_bytes()) {
+            let pubkey = match Pubkey::from_str(pubkey_str) {
                 Ok(pubkey) => pubkey,
                 Err(_) => return Err(BlockTokError::InvalidMetadata.into()),
             };
@@ -426,11 +873,2397 @@ impl Processor {
         // Update content royalty distribution
         content.royalty_distribution = royalty_distribution;
         
+        // Resize the account to fit the new recipient list
+        Self::resize_account(
+            content_account_info,
+            creator_info,
+            system_program_info,
+            content.account_size(),
+        )?;
+        
         // Save content data
-        content.serialize(&mut *content_account_info.data.borrow_mut())?;
+        content.pack(&mut content_account_info.data.borrow_mut())?;
         
         msg!("Royalty distribution set successfully");
         
         Ok(())
     }
-} 
\ No newline at end of file
+    
+    /// Process UpdateContentMetadata instruction
+    pub fn process_update_content_metadata(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        title: Option<String>,
+        description: Option<String>,
+        content_url: Option<String>,
+        content_type: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let creator_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        // Platform config is checked in `Processor::process`
+        let _platform_config_info = next_account_info(account_info_iter)?;
+        
+        // Check creator is signer
+        if !creator_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Load content account data
+        let mut content = Self::load_content(program_id, content_account_info)?;
+        
+        // Check if creator is the owner
+        if content.get_creator() != *creator_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Apply metadata changes
+        if let Some(title) = title {
+            content.title = title;
+        }
+        if let Some(description) = description {
+            content.description = description;
+        }
+        if let Some(content_url) = content_url {
+            content.content_url = content_url;
+        }
+        if let Some(content_type) = content_type {
+            content.content_type = content_type;
+        }
+        
+        // Move the content between category indexes for any changed tags
+        if let Some(tags) = tags {
+            Self::validate_tags(&tags)?;
+            
+            let (removed, added) = tag_changes(&content.tags, &tags);
+            for tag in removed {
+                let category_info = next_account_info(account_info_iter)?;
+                let category_index_info = next_account_info(account_info_iter)?;
+                Self::remove_content_tag(
+                    program_id,
+                    creator_info,
+                    content_account_info.key,
+                    tag,
+                    category_info,
+                    category_index_info,
+                )?;
+            }
+            for tag in added {
+                let category_info = next_account_info(account_info_iter)?;
+                let category_index_info = next_account_info(account_info_iter)?;
+                Self::add_content_tag(
+                    program_id,
+                    creator_info,
+                    system_program_info,
+                    content_account_info.key,
+                    tag,
+                    category_info,
+                    category_index_info,
+                )?;
+            }
+            
+            content.tags = tags;
+        }
+        
+        // Resize the account to the new metadata size
+        Self::resize_account(
+            content_account_info,
+            creator_info,
+            system_program_info,
+            content.account_size(),
+        )?;
+        
+        // Save content data
+        content.pack(&mut content_account_info.data.borrow_mut())?;
+        
+        msg!("Content metadata updated successfully");
+        
+        Ok(())
+    }
+    
+    /// Process CloseContent instruction
+    pub fn process_close_content(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let creator_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        // Platform config is checked in `Processor::process`
+        let _platform_config_info = next_account_info(account_info_iter)?;
+        let history_info = next_account_info(account_info_iter)?;
+        
+        // Check creator is signer
+        if !creator_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Load content account data
+        let content = Self::load_content(program_id, content_account_info)?;
+        
+        // Check if creator is the owner
+        if content.get_creator() != *creator_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Close the analytics history along with the content
+        if AnalyticsHistory::find_address(program_id, content_account_info.key).0 != *history_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !history_info.data_is_empty() {
+            if history_info.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Self::close_account(history_info, creator_info)?;
+        }
+        
+        // Content with a minted NFT can only be closed once the NFT is burned
+        if let Some(nft_mint) = content.get_nft_mint() {
+            let mint_info = next_account_info(account_info_iter)?;
+            if *mint_info.key != nft_mint {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if *mint_info.owner != spl_token::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let mint = Mint::unpack(&mint_info.data.borrow())?;
+            if mint.supply != 0 {
+                return Err(BlockTokError::NftNotBurned.into());
+            }
+        }
+        
+        // Remove the content from each tagged category
+        for tag in &content.tags {
+            let category_info = next_account_info(account_info_iter)?;
+            let category_index_info = next_account_info(account_info_iter)?;
+            Self::remove_content_tag(
+                program_id,
+                creator_info,
+                content_account_info.key,
+                tag,
+                category_info,
+                category_index_info,
+            )?;
+        }
+        
+        // Zero the account and return its rent to the creator
+        Self::close_account(content_account_info, creator_info)?;
+        
+        msg!("Content closed successfully");
+        
+        Ok(())
+    }
+    
+    /// Process MigrateContent instruction
+    pub fn process_migrate_content(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let creator_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        
+        // Check creator is signer
+        if !creator_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Content account must belong to this program
+        if content_account_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        // Load content account data under whichever layout it was written with
+        let (content, version) = Content::unpack_versioned(&content_account_info.data.borrow())?;
+        if !content.is_valid_address(program_id, content_account_info.key) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        // Check if creator is the owner
+        if content.get_creator() != *creator_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        if version == Content::VERSION {
+            msg!("Content is already at the current version");
+            return Ok(());
+        }
+        
+        // Rewrite the content in place under the current layout
+        Self::resize_account(
+            content_account_info,
+            creator_info,
+            system_program_info,
+            content.account_size(),
+        )?;
+        content.pack(&mut content_account_info.data.borrow_mut())?;
+        
+        msg!("Content migrated from version {} to {}", version, Content::VERSION);
+        
+        Ok(())
+    }
+    
+    /// Process Publish instruction
+    pub fn process_publish(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        publish_at: Option<u64>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let creator_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        
+        // Check creator is signer
+        if !creator_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Load content account data
+        let mut content = Self::load_content(program_id, content_account_info)?;
+        
+        // Check if creator is the owner
+        if content.get_creator() != *creator_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Only content that is not yet live or is unlisted can be published
+        let now = Clock::get()?.unix_timestamp as u64;
+        match content.effective_status(now) {
+            ContentStatus::Draft | ContentStatus::Scheduled | ContentStatus::Unlisted => {}
+            ContentStatus::Published | ContentStatus::Archived => {
+                return Err(BlockTokError::InvalidStatusTransition.into());
+            }
+        }
+        
+        // Publish now, or schedule for later
+        match publish_at {
+            Some(publish_at) if publish_at > now => {
+                content.status = ContentStatus::Scheduled;
+                content.publish_at = publish_at;
+            }
+            _ => {
+                content.status = ContentStatus::Published;
+                content.publish_at = now;
+            }
+        }
+        
+        // Save content data
+        content.pack(&mut content_account_info.data.borrow_mut())?;
+        
+        msg!("Content status set to {:?}", content.status);
+        
+        Ok(())
+    }
+    
+    /// Process Unlist instruction
+    pub fn process_unlist(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let creator_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        
+        // Check creator is signer
+        if !creator_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Load content account data
+        let mut content = Self::load_content(program_id, content_account_info)?;
+        
+        // Check if creator is the owner
+        if content.get_creator() != *creator_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Only published content can be unlisted
+        if !content.is_published(Clock::get()?.unix_timestamp as u64) {
+            return Err(BlockTokError::InvalidStatusTransition.into());
+        }
+        content.status = ContentStatus::Unlisted;
+        
+        // Save content data
+        content.pack(&mut content_account_info.data.borrow_mut())?;
+        
+        msg!("Content unlisted successfully");
+        
+        Ok(())
+    }
+    
+    /// Process Archive instruction
+    pub fn process_archive(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let creator_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        
+        // Check creator is signer
+        if !creator_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Load content account data
+        let mut content = Self::load_content(program_id, content_account_info)?;
+        
+        // Check if creator is the owner
+        if content.get_creator() != *creator_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Archiving is final
+        if content.status == ContentStatus::Archived {
+            return Err(BlockTokError::InvalidStatusTransition.into());
+        }
+        content.status = ContentStatus::Archived;
+        
+        // Save content data
+        content.pack(&mut content_account_info.data.borrow_mut())?;
+        
+        msg!("Content archived successfully");
+        
+        Ok(())
+    }
+    
+    /// Process InitializeConfig instruction
+    pub fn process_initialize_config(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        treasury: Pubkey,
+        platform_fee_bps: u16,
+        features: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let authority_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let program_data_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        
+        // Only the program's upgrade authority may create the config
+        if !authority_info.is_signer
+            || Self::get_upgrade_authority(program_id, program_data_info)? != Some(*authority_info.key)
+        {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Validate fee
+        if platform_fee_bps > MAX_PLATFORM_FEE_BPS {
+            return Err(BlockTokError::InvalidPlatformFee.into());
+        }
+        
+        // Verify config account
+        let (config_pubkey, config_bump) = PlatformConfig::find_address(program_id);
+        if config_pubkey != *config_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        // Create config account
+        Self::create_pda_account(
+            program_id,
+            authority_info,
+            config_info,
+            system_program_info,
+            PlatformConfig::get_account_size(0),
+            &[PLATFORM_CONFIG_SEED, &[config_bump]],
+        )?;
+        
+        // Save config data
+        let config = PlatformConfig {
+            admin: authority_info.key.to_bytes(),
+            pending_admin: None,
+            treasury: treasury.to_bytes(),
+            platform_fee_bps,
+            features,
+            paused: false,
+            paused_instructions: 0,
+            analytics_oracles: Vec::new(),
+            allow_creator_analytics: true,
+            min_analytics_interval: 0,
+            engagement_weights: EngagementWeights::default(),
+            view_reward: 0,
+            emission_schedule: EmissionSchedule::default(),
+            emitted_total: 0,
+            emission_epoch: 0,
+            emitted_in_epoch: 0,
+        };
+        config.pack(&mut config_info.data.borrow_mut())?;
+        
+        msg!("Platform config initialized successfully");
+        
+        Ok(())
+    }
+    
+    /// Process UpdateConfig instruction
+    pub fn process_update_config(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        treasury: Option<Pubkey>,
+        platform_fee_bps: Option<u16>,
+        features: Option<u64>,
+        min_analytics_interval: Option<u64>,
+        engagement_weights: Option<EngagementWeights>,
+        view_reward: Option<u64>,
+        emission_schedule: Option<EmissionSchedule>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let admin_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        
+        // Load config, which may still be at an older layout version
+        let mut config = Self::load_platform_config_versioned(program_id, config_info)?;
+        
+        // Check admin
+        if !admin_info.is_signer || config.get_admin() != *admin_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Apply changes
+        if let Some(treasury) = treasury {
+            config.treasury = treasury.to_bytes();
+        }
+        if let Some(platform_fee_bps) = platform_fee_bps {
+            if platform_fee_bps > MAX_PLATFORM_FEE_BPS {
+                return Err(BlockTokError::InvalidPlatformFee.into());
+            }
+            config.platform_fee_bps = platform_fee_bps;
+        }
+        if let Some(features) = features {
+            config.features = features;
+        }
+        if let Some(min_analytics_interval) = min_analytics_interval {
+            config.min_analytics_interval = min_analytics_interval;
+        }
+        if let Some(engagement_weights) = engagement_weights {
+            config.engagement_weights = engagement_weights;
+        }
+        if let Some(view_reward) = view_reward {
+            config.view_reward = view_reward;
+        }
+        if let Some(emission_schedule) = emission_schedule {
+            config.emission_schedule = emission_schedule;
+        }
+        
+        // Save config data, upgrading it to the current layout
+        Self::resize_account(config_info, admin_info, system_program_info, config.account_size())?;
+        config.pack(&mut config_info.data.borrow_mut())?;
+        
+        msg!("Platform config updated successfully");
+        
+        Ok(())
+    }
+    
+    /// Process TransferAdmin instruction
+    pub fn process_transfer_admin(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_admin: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let admin_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        
+        // Load config, which may still be at an older layout version
+        let mut config = Self::load_platform_config_versioned(program_id, config_info)?;
+        
+        // Check admin
+        if !admin_info.is_signer || config.get_admin() != *admin_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Nominate the new admin, upgrading the config to the current layout
+        config.pending_admin = Some(new_admin.to_bytes());
+        Self::resize_account(config_info, admin_info, system_program_info, config.account_size())?;
+        config.pack(&mut config_info.data.borrow_mut())?;
+        
+        msg!("Platform admin transfer started");
+        
+        Ok(())
+    }
+    
+    /// Process AcceptAdmin instruction
+    pub fn process_accept_admin(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let pending_admin_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        
+        // Load config, which may still be at an older layout version
+        let mut config = Self::load_platform_config_versioned(program_id, config_info)?;
+        
+        // Check the signer is the nominated admin
+        if !pending_admin_info.is_signer
+            || config.get_pending_admin() != Some(*pending_admin_info.key)
+        {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Complete the transfer, upgrading the config to the current layout
+        config.admin = pending_admin_info.key.to_bytes();
+        config.pending_admin = None;
+        Self::resize_account(
+            config_info,
+            pending_admin_info,
+            system_program_info,
+            config.account_size(),
+        )?;
+        config.pack(&mut config_info.data.borrow_mut())?;
+        
+        msg!("Platform admin transfer completed");
+        
+        Ok(())
+    }
+    
+    /// Process SetPauseState instruction
+    pub fn process_set_pause_state(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        paused: bool,
+        paused_instructions: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let admin_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        
+        // Load config, which may still be at an older layout version
+        let mut config = Self::load_platform_config_versioned(program_id, config_info)?;
+        
+        // Check admin
+        if !admin_info.is_signer || config.get_admin() != *admin_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Apply pause state
+        config.paused = paused;
+        config.paused_instructions = paused_instructions;
+        
+        // Save config data, upgrading it to the current layout
+        Self::resize_account(config_info, admin_info, system_program_info, config.account_size())?;
+        config.pack(&mut config_info.data.borrow_mut())?;
+        
+        msg!("Platform pause state set: paused={}, paused_instructions={:#x}", paused, paused_instructions);
+        
+        Ok(())
+    }
+    
+    /// Process CreateCategory instruction
+    pub fn process_create_category(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let admin_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let category_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        
+        // Load config data
+        let config = Self::load_platform_config(program_id, config_info)?;
+        
+        // Check admin
+        if !admin_info.is_signer || config.get_admin() != *admin_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Validate name
+        if !Category::is_valid_name(&name) {
+            return Err(BlockTokError::InvalidCategoryName.into());
+        }
+        
+        // Verify category account
+        let (category_pubkey, category_bump) = Category::find_address(program_id, &name);
+        if category_pubkey != *category_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        // Create category account
+        Self::create_pda_account(
+            program_id,
+            admin_info,
+            category_info,
+            system_program_info,
+            Category::get_account_size(&name),
+            &[CATEGORY_SEED, name.as_bytes(), &[category_bump]],
+        )?;
+        
+        // Save category data
+        let category = Category {
+            name,
+            active: true,
+            content_count: 0,
+        };
+        category.pack(&mut category_info.data.borrow_mut())?;
+        
+        msg!("Category created successfully");
+        
+        Ok(())
+    }
+    
+    /// Process SetCategoryActive instruction
+    pub fn process_set_category_active(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+        active: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let admin_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let category_info = next_account_info(account_info_iter)?;
+        
+        // Load config data
+        let config = Self::load_platform_config(program_id, config_info)?;
+        
+        // Check admin
+        if !admin_info.is_signer || config.get_admin() != *admin_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Update category
+        if !Category::is_valid_name(&name) {
+            return Err(BlockTokError::InvalidCategoryName.into());
+        }
+        let mut category = Self::load_category(program_id, category_info, &name)?;
+        category.active = active;
+        category.pack(&mut category_info.data.borrow_mut())?;
+        
+        msg!("Category {} set active={}", name, active);
+        
+        Ok(())
+    }
+    
+    /// Process SetAnalyticsOracles instruction
+    pub fn process_set_analytics_oracles(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        oracles: Vec<Pubkey>,
+        allow_creator_analytics: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let admin_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        
+        // Load config, which may still be at an older layout version
+        let mut config = Self::load_platform_config_versioned(program_id, config_info)?;
+        
+        // Check admin
+        if !admin_info.is_signer || config.get_admin() != *admin_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Validate oracles
+        if oracles.len() > MAX_ANALYTICS_ORACLES {
+            return Err(BlockTokError::TooManyAnalyticsOracles.into());
+        }
+        
+        // Apply changes
+        config.analytics_oracles = oracles.iter().map(Pubkey::to_bytes).collect();
+        config.allow_creator_analytics = allow_creator_analytics;
+        
+        // Save config data, resizing it for the new oracle list
+        Self::resize_account(config_info, admin_info, system_program_info, config.account_size())?;
+        config.pack(&mut config_info.data.borrow_mut())?;
+        
+        msg!("Analytics oracles set successfully");
+        
+        Ok(())
+    }
+    
+    /// Process InitializeAnalyticsHistory instruction
+    pub fn process_initialize_analytics_history(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        granularity: HistoryGranularity,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let creator_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        let history_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        // Platform config is checked in `Processor::process`
+        let _platform_config_info = next_account_info(account_info_iter)?;
+        
+        // Check creator is signer
+        if !creator_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Load content account data
+        let content = Self::load_content(program_id, content_account_info)?;
+        
+        // Check if creator is the owner
+        if content.get_creator() != *creator_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Verify history account
+        let (history_pubkey, history_bump) =
+            AnalyticsHistory::find_address(program_id, content_account_info.key);
+        if history_pubkey != *history_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        // Create history account
+        Self::create_pda_account(
+            program_id,
+            creator_info,
+            history_info,
+            system_program_info,
+            AnalyticsHistory::size(),
+            &[ANALYTICS_HISTORY_SEED, content_account_info.key.as_ref(), &[history_bump]],
+        )?;
+        
+        // Save history data
+        let history = AnalyticsHistory::new(content_account_info.key, granularity);
+        history.pack(&mut history_info.data.borrow_mut())?;
+        
+        msg!("Analytics history initialized successfully");
+        
+        Ok(())
+    }
+    
+    /// Process RecordView instruction
+    pub fn process_record_view(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        watch_progress_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let viewer_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        let receipt_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let platform_config_info = next_account_info(account_info_iter)?;
+        let history_info = next_account_info(account_info_iter)?;
+        let rewards_info = next_account_info(account_info_iter)?;
+        
+        // Check viewer is signer
+        if !viewer_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Validate watch progress
+        if watch_progress_bps > MAX_WATCH_PROGRESS_BPS {
+            return Err(BlockTokError::InvalidWatchProgress.into());
+        }
+        
+        // Load content account data
+        let mut content = Self::load_content(program_id, content_account_info)?;
+        let config = Self::load_platform_config_if_initialized(program_id, platform_config_info)?;
+        
+        // Only published content can be viewed
+        let now = Clock::get()?.unix_timestamp as u64;
+        if !content.is_published(now) {
+            return Err(BlockTokError::ContentNotPublished.into());
+        }
+        
+        // Verify receipt account
+        let (receipt_pubkey, receipt_bump) =
+            ViewReceipt::find_address(program_id, content_account_info.key, viewer_info.key);
+        if receipt_pubkey != *receipt_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        if receipt_info.data_is_empty() {
+            // First view: create the receipt and count the view and its completion
+            Self::create_pda_account(
+                program_id,
+                viewer_info,
+                receipt_info,
+                system_program_info,
+                ViewReceipt::size(),
+                &[
+                    VIEW_RECEIPT_SEED,
+                    content_account_info.key.as_ref(),
+                    viewer_info.key.as_ref(),
+                    &[receipt_bump],
+                ],
+            )?;
+            let receipt = ViewReceipt {
+                content: content_account_info.key.to_bytes(),
+                viewer: viewer_info.key.to_bytes(),
+                first_seen_at: now,
+                watch_progress_bps,
+            };
+            receipt.pack(&mut receipt_info.data.borrow_mut())?;
+            
+            // Views from receipts do not count as a reported update, so they
+            // leave `updated_at` and its minimum interval alone
+            let previous = content.analytics.clone();
+            let completion = WatchMetrics {
+                total_completion_bps: watch_progress_bps as u64,
+                ..WatchMetrics::default()
+            };
+            content.analytics.increment(1, 0, 0, 0, &completion)?;
+            Self::refresh_engagement_score(&mut content, config.as_ref(), now);
+            content.pack(&mut content_account_info.data.borrow_mut())?;
+            Self::record_analytics_history(
+                program_id,
+                history_info,
+                content_account_info.key,
+                now,
+                &previous,
+                &content.analytics,
+            )?;
+            
+            // The receipt makes the view verified, so it earns the viewer rewards
+            let view_reward = config.as_ref().map_or(0, |config| config.view_reward);
+            if view_reward > 0 {
+                Self::accrue_user_rewards(
+                    program_id,
+                    viewer_info,
+                    viewer_info.key,
+                    rewards_info,
+                    system_program_info,
+                    view_reward,
+                    now,
+                )?;
+            }
+        } else {
+            // Repeat view: count a replay, and advance the watch progress and
+            // the content's completion with it
+            if receipt_info.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let mut receipt = ViewReceipt::unpack(&receipt_info.data.borrow())?;
+            let replay = WatchMetrics {
+                total_completion_bps: watch_progress_bps.saturating_sub(receipt.watch_progress_bps)
+                    as u64,
+                replays: 1,
+                ..WatchMetrics::default()
+            };
+            content.analytics.increment(0, 0, 0, 0, &replay)?;
+            Self::refresh_engagement_score(&mut content, config.as_ref(), now);
+            content.pack(&mut content_account_info.data.borrow_mut())?;
+            
+            if watch_progress_bps > receipt.watch_progress_bps {
+                receipt.watch_progress_bps = watch_progress_bps;
+                receipt.pack(&mut receipt_info.data.borrow_mut())?;
+            }
+        }
+        
+        msg!("View recorded successfully");
+        
+        Ok(())
+    }
+    
+    /// Process CommitEngagementEpoch instruction
+    pub fn process_commit_engagement_epoch(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        epoch: u64,
+        start_time: u64,
+        end_time: u64,
+        merkle_root: [u8; 32],
+        event_count: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let authority_info = next_account_info(account_info_iter)?;
+        let epoch_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        
+        // Check authority is a signing analytics oracle
+        let config = Self::load_platform_config_if_initialized(program_id, config_info)?;
+        if !authority_info.is_signer
+            || !config.map_or(false, |config| config.is_analytics_oracle(authority_info.key))
+        {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Only windows that are already over can be committed
+        let now = Clock::get()?.unix_timestamp as u64;
+        if start_time >= end_time || end_time > now {
+            return Err(BlockTokError::InvalidEpochWindow.into());
+        }
+        
+        // Verify epoch account
+        let (epoch_pubkey, epoch_bump) = EngagementEpoch::find_address(program_id, epoch);
+        if epoch_pubkey != *epoch_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        // Create epoch account, which fails if the epoch was already committed
+        Self::create_pda_account(
+            program_id,
+            authority_info,
+            epoch_info,
+            system_program_info,
+            EngagementEpoch::size(),
+            &[ENGAGEMENT_EPOCH_SEED, &epoch.to_le_bytes(), &[epoch_bump]],
+        )?;
+        
+        // Save epoch data
+        let engagement_epoch = EngagementEpoch {
+            authority: authority_info.key.to_bytes(),
+            epoch,
+            start_time,
+            end_time,
+            merkle_root,
+            event_count,
+            committed_at: now,
+        };
+        engagement_epoch.pack(&mut epoch_info.data.borrow_mut())?;
+        
+        msg!("Engagement epoch {} committed successfully", epoch);
+        
+        Ok(())
+    }
+    
+    /// Process RecordEpochTotals instruction
+    pub fn process_record_epoch_totals(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        views: u64,
+        likes: u64,
+        shares: u64,
+        comments: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let authority_info = next_account_info(account_info_iter)?;
+        let epoch_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        let totals_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        // Platform config is checked in `Processor::process`
+        let _platform_config_info = next_account_info(account_info_iter)?;
+        
+        // Load epoch account data
+        let engagement_epoch = Self::load_engagement_epoch(program_id, epoch_info)?;
+        
+        // Check authority is the signing oracle that committed the epoch
+        if !authority_info.is_signer || engagement_epoch.get_authority() != *authority_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Check the content exists
+        Self::load_content(program_id, content_account_info)?;
+        
+        // Verify totals account
+        let (totals_pubkey, totals_bump) =
+            EpochContentTotals::find_address(program_id, epoch_info.key, content_account_info.key);
+        if totals_pubkey != *totals_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        // Create totals account, which fails if the totals were already recorded
+        Self::create_pda_account(
+            program_id,
+            authority_info,
+            totals_info,
+            system_program_info,
+            EpochContentTotals::size(),
+            &[
+                EPOCH_TOTALS_SEED,
+                epoch_info.key.as_ref(),
+                content_account_info.key.as_ref(),
+                &[totals_bump],
+            ],
+        )?;
+        
+        // Save totals data
+        let totals = EpochContentTotals {
+            epoch: epoch_info.key.to_bytes(),
+            content: content_account_info.key.to_bytes(),
+            views,
+            likes,
+            shares,
+            comments,
+        };
+        totals.pack(&mut totals_info.data.borrow_mut())?;
+        
+        msg!("Engagement epoch totals recorded successfully");
+        
+        Ok(())
+    }
+    
+    /// Process VerifyEngagement instruction
+    pub fn process_verify_engagement(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        event: EngagementEvent,
+        proof: Vec<[u8; 32]>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let epoch_info = next_account_info(account_info_iter)?;
+        
+        // Load epoch account data
+        let engagement_epoch = Self::load_engagement_epoch(program_id, epoch_info)?;
+        
+        // Check the event is in the epoch's window and tree
+        if !engagement_epoch.contains(event.timestamp) {
+            return Err(BlockTokError::EngagementOutsideEpoch.into());
+        }
+        if !merkle::verify_proof(event.leaf(), &proof, &engagement_epoch.merkle_root) {
+            return Err(BlockTokError::InvalidMerkleProof.into());
+        }
+        
+        msg!("Engagement verified in epoch {}", engagement_epoch.epoch);
+        
+        Ok(())
+    }
+    
+    /// Process BatchUpdateAnalytics instruction
+    pub fn process_batch_update_analytics(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        updates: Vec<ContentAnalyticsUpdate>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let authority_info = next_account_info(account_info_iter)?;
+        let platform_config_info = next_account_info(account_info_iter)?;
+        
+        // Validate batch size
+        if updates.len() > MAX_BATCH_ANALYTICS_UPDATES {
+            return Err(BlockTokError::TooManyBatchUpdates.into());
+        }
+        
+        let config = Self::load_platform_config_if_initialized(program_id, platform_config_info)?;
+        let now = Clock::get()?.unix_timestamp as u64;
+        
+        // Any failing update fails the instruction, so no update is applied alone
+        for update in updates {
+            let content_account_info = next_account_info(account_info_iter)?;
+            let history_info = next_account_info(account_info_iter)?;
+            if *content_account_info.key != update.content {
+                return Err(BlockTokError::BatchAccountMismatch.into());
+            }
+            
+            // Load content account data, checking its owner and address
+            let mut content = Self::load_content(program_id, content_account_info)?;
+            
+            // Check authority is an oracle, or the creator if creator reports are allowed
+            Self::check_analytics_authority(authority_info, &content, config.as_ref())?;
+            
+            // Only published content collects analytics
+            if !content.is_published(now) {
+                return Err(BlockTokError::ContentNotPublished.into());
+            }
+            Self::check_analytics_interval(&content, config.as_ref(), now)?;
+            
+            // Update analytics, which may only grow
+            let previous = content.analytics.clone();
+            content.analytics.set(
+                update.views,
+                update.likes,
+                update.shares,
+                update.comments,
+                &update.watch,
+            )?;
+            content.analytics.updated_at = now;
+            Self::refresh_engagement_score(&mut content, config.as_ref(), now);
+            
+            // Save content data and its history
+            content.pack(&mut content_account_info.data.borrow_mut())?;
+            Self::record_analytics_history(
+                program_id,
+                history_info,
+                content_account_info.key,
+                now,
+                &previous,
+                &content.analytics,
+            )?;
+        }
+        
+        msg!("Analytics batch updated successfully");
+        
+        Ok(())
+    }
+    
+    /// Load an engagement epoch owned by the program
+    fn load_engagement_epoch(
+        program_id: &Pubkey,
+        epoch_info: &AccountInfo,
+    ) -> Result<EngagementEpoch, ProgramError> {
+        if epoch_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        EngagementEpoch::unpack(&epoch_info.data.borrow())
+    }
+    
+    /// Fail with `ProgramPaused` if the platform config pauses the instruction
+    /// identified by `pause_flag`. The config account may appear anywhere in
+    /// `accounts`; before the config is initialized nothing is paused
+    fn check_not_paused(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pause_flag: u64,
+    ) -> ProgramResult {
+        let (config_pubkey, _) = PlatformConfig::find_address(program_id);
+        let config_info = accounts
+            .iter()
+            .find(|account_info| *account_info.key == config_pubkey)
+            .ok_or(BlockTokError::MissingPlatformConfig)?;
+        
+        let config = Self::load_platform_config_if_initialized(program_id, config_info)?;
+        if config.map_or(false, |config| config.is_paused(pause_flag)) {
+            msg!("Instruction is paused");
+            return Err(BlockTokError::ProgramPaused.into());
+        }
+        
+        Ok(())
+    }
+    
+    /// Load the platform config at any supported layout version, or `None` if
+    /// it has not been initialized yet
+    fn load_platform_config_if_initialized(
+        program_id: &Pubkey,
+        config_info: &AccountInfo,
+    ) -> Result<Option<PlatformConfig>, ProgramError> {
+        if PlatformConfig::find_address(program_id).0 != *config_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if config_info.data_is_empty() {
+            return Ok(None);
+        }
+        
+        Self::load_platform_config_versioned(program_id, config_info).map(Some)
+    }
+    
+    /// Check that `authority_info` signed and may report analytics for `content`.
+    /// Before the config is initialized only the creator may
+    fn check_analytics_authority(
+        authority_info: &AccountInfo,
+        content: &Content,
+        config: Option<&PlatformConfig>,
+    ) -> ProgramResult {
+        if !authority_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        let creator = content.get_creator();
+        let allowed = match config {
+            Some(config) => config.can_report_analytics(authority_info.key, &creator),
+            None => *authority_info.key == creator,
+        };
+        if !allowed {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        Ok(())
+    }
+    
+    /// Process InitializeRewardMint instruction
+    pub fn process_initialize_reward_mint(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let admin_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let mint_authority_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        
+        // Check admin
+        let config = Self::load_platform_config_versioned(program_id, config_info)?;
+        if !admin_info.is_signer || config.get_admin() != *admin_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Verify mint and mint authority accounts
+        let (mint_pubkey, mint_bump) = find_reward_mint_address(program_id);
+        if mint_pubkey != *mint_info.key
+            || find_reward_mint_authority_address(program_id).0 != *mint_authority_info.key
+        {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if *token_program_info.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if !mint_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        
+        // Create the mint account, owned by the token program
+        Self::create_pda_account(
+            &spl_token::id(),
+            admin_info,
+            mint_info,
+            system_program_info,
+            Mint::LEN,
+            &[REWARD_MINT_SEED, &[mint_bump]],
+        )?;
+        
+        // Initialize the mint without a freeze authority
+        invoke(
+            &token_instruction::initialize_mint2(
+                &spl_token::id(),
+                mint_info.key,
+                mint_authority_info.key,
+                None,
+                REWARD_TOKEN_DECIMALS,
+            )?,
+            &[mint_info.clone(), token_program_info.clone()],
+        )?;
+        
+        msg!("Reward mint initialized successfully");
+        
+        Ok(())
+    }
+    
+    /// Process MintRewards instruction
+    pub fn process_mint_rewards(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let authority_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let mint_authority_info = next_account_info(account_info_iter)?;
+        let recipient_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        
+        // Check authority is an analytics oracle; creators and viewers never mint
+        let mut config = Self::load_platform_config_versioned(program_id, config_info)?;
+        if !authority_info.is_signer || !config.is_analytics_oracle(authority_info.key) {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Validate amount
+        if amount == 0 {
+            return Err(BlockTokError::InvalidRewardAmount.into());
+        }
+        
+        Self::mint_reward_tokens(
+            program_id,
+            config_info,
+            &mut config,
+            mint_info,
+            mint_authority_info,
+            recipient_info,
+            token_program_info,
+            amount,
+        )?;
+        
+        msg!("Rewards minted successfully");
+        
+        Ok(())
+    }
+    
+    /// Process AccrueRewards instruction
+    pub fn process_accrue_rewards(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let authority_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let user_info = next_account_info(account_info_iter)?;
+        let rewards_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        
+        // Check authority is an analytics oracle
+        let config = Self::load_platform_config_versioned(program_id, config_info)?;
+        if !authority_info.is_signer || !config.is_analytics_oracle(authority_info.key) {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Validate amount
+        if amount == 0 {
+            return Err(BlockTokError::InvalidRewardAmount.into());
+        }
+        
+        let now = Clock::get()?.unix_timestamp as u64;
+        Self::accrue_user_rewards(
+            program_id,
+            authority_info,
+            user_info.key,
+            rewards_info,
+            system_program_info,
+            amount,
+            now,
+        )?;
+        
+        msg!("Rewards accrued successfully");
+        
+        Ok(())
+    }
+    
+    /// Process ClaimRewards instruction
+    pub fn process_claim_rewards(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let user_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let rewards_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let mint_authority_info = next_account_info(account_info_iter)?;
+        let token_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let associated_token_program_info = next_account_info(account_info_iter)?;
+        
+        // Check user is signer
+        if !user_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Load the user's rewards
+        if UserRewards::find_address(program_id, user_info.key).0 != *rewards_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if rewards_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut rewards = UserRewards::unpack(&rewards_info.data.borrow())?;
+        let amount = rewards.claimable();
+        if amount == 0 {
+            return Err(BlockTokError::NoRewardsToClaim.into());
+        }
+        
+        // Rewards only go to the user's own associated token account
+        if get_associated_token_address(user_info.key, mint_info.key) != *token_account_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        invoke(
+            &associated_token_account_instruction::create_associated_token_account_idempotent(
+                user_info.key,
+                user_info.key,
+                mint_info.key,
+                &spl_token::id(),
+            ),
+            &[
+                user_info.clone(),
+                token_account_info.clone(),
+                user_info.clone(),
+                mint_info.clone(),
+                system_program_info.clone(),
+                token_program_info.clone(),
+                associated_token_program_info.clone(),
+            ],
+        )?;
+        
+        // Mark the credits claimed before minting them
+        rewards.claimed = rewards.accrued;
+        rewards.pack(&mut rewards_info.data.borrow_mut())?;
+        let mut config = Self::load_platform_config_versioned(program_id, config_info)?;
+        Self::mint_reward_tokens(
+            program_id,
+            config_info,
+            &mut config,
+            mint_info,
+            mint_authority_info,
+            token_account_info,
+            token_program_info,
+            amount,
+        )?;
+        
+        msg!("Rewards claimed successfully");
+        
+        Ok(())
+    }
+    
+    /// Process CreateRewardDistributor instruction
+    pub fn process_create_reward_distributor(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        epoch: u64,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_recipients: u32,
+        expires_at: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let authority_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let distributor_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        
+        // Check authority is an analytics oracle
+        let config = Self::load_platform_config_versioned(program_id, config_info)?;
+        if !authority_info.is_signer || !config.is_analytics_oracle(authority_info.key) {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Validate distributor
+        let now = Clock::get()?.unix_timestamp as u64;
+        if total_amount == 0
+            || num_recipients == 0
+            || num_recipients > MAX_DISTRIBUTOR_RECIPIENTS
+            || expires_at <= now
+        {
+            return Err(BlockTokError::InvalidDistributor.into());
+        }
+        
+        // Verify distributor, vault and mint accounts
+        let (distributor_pubkey, distributor_bump) =
+            RewardDistributor::find_address(program_id, epoch);
+        if distributor_pubkey != *distributor_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let (vault_pubkey, vault_bump) =
+            RewardDistributor::find_vault_address(program_id, &distributor_pubkey);
+        if vault_pubkey != *vault_info.key
+            || find_reward_mint_address(program_id).0 != *mint_info.key
+        {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if *token_program_info.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        // Create distributor account, which fails if the epoch already has one
+        Self::create_pda_account(
+            program_id,
+            authority_info,
+            distributor_info,
+            system_program_info,
+            RewardDistributor::get_account_size(num_recipients),
+            &[REWARD_DISTRIBUTOR_SEED, &epoch.to_le_bytes(), &[distributor_bump]],
+        )?;
+        
+        // Create the vault, a token account owned by the distributor
+        Self::create_pda_account(
+            &spl_token::id(),
+            authority_info,
+            vault_info,
+            system_program_info,
+            TokenAccount::LEN,
+            &[DISTRIBUTOR_VAULT_SEED, distributor_pubkey.as_ref(), &[vault_bump]],
+        )?;
+        invoke(
+            &token_instruction::initialize_account3(
+                &spl_token::id(),
+                vault_info.key,
+                mint_info.key,
+                distributor_info.key,
+            )?,
+            &[vault_info.clone(), mint_info.clone(), token_program_info.clone()],
+        )?;
+        
+        // Fund the vault from the authority's tokens
+        invoke(
+            &token_instruction::transfer(
+                &spl_token::id(),
+                source_info.key,
+                vault_info.key,
+                authority_info.key,
+                &[],
+                total_amount,
+            )?,
+            &[
+                source_info.clone(),
+                vault_info.clone(),
+                authority_info.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+        
+        // Save distributor data
+        let distributor = RewardDistributor {
+            authority: authority_info.key.to_bytes(),
+            epoch,
+            merkle_root,
+            total_amount,
+            claimed_amount: 0,
+            num_recipients,
+            expires_at,
+            clawed_back: false,
+            claimed_bitmap: vec![0; RewardDistributor::bitmap_len(num_recipients)],
+        };
+        distributor.pack(&mut distributor_info.data.borrow_mut())?;
+        
+        msg!("Reward distributor for epoch {} created successfully", epoch);
+        
+        Ok(())
+    }
+    
+    /// Process ClaimDistribution instruction
+    pub fn process_claim_distribution(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let user_info = next_account_info(account_info_iter)?;
+        // Platform config is checked in `Processor::process`
+        let _platform_config_info = next_account_info(account_info_iter)?;
+        let distributor_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let token_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let associated_token_program_info = next_account_info(account_info_iter)?;
+        
+        // Check user is signer
+        if !user_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Load distributor
+        if distributor_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut distributor = RewardDistributor::unpack(&distributor_info.data.borrow())?;
+        let (distributor_pubkey, distributor_bump) =
+            RewardDistributor::find_address(program_id, distributor.epoch);
+        if distributor_pubkey != *distributor_info.key
+            || RewardDistributor::find_vault_address(program_id, &distributor_pubkey).0
+                != *vault_info.key
+            || find_reward_mint_address(program_id).0 != *mint_info.key
+        {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if *token_program_info.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        // Check the entry is still claimable
+        let now = Clock::get()?.unix_timestamp as u64;
+        if distributor.clawed_back || distributor.is_expired(now) {
+            return Err(BlockTokError::DistributionExpired.into());
+        }
+        if index >= distributor.num_recipients {
+            return Err(BlockTokError::InvalidDistributionEntry.into());
+        }
+        if distributor.is_claimed(index) {
+            return Err(BlockTokError::DistributionAlreadyClaimed.into());
+        }
+        
+        // Check the entry is the user's and in the distributor's tree
+        let entry = DistributionEntry {
+            index,
+            user: user_info.key.to_bytes(),
+            amount,
+        };
+        if !merkle::verify_proof(entry.leaf(), &proof, &distributor.merkle_root) {
+            return Err(BlockTokError::InvalidMerkleProof.into());
+        }
+        
+        // Mark the entry claimed before paying it out
+        distributor.set_claimed(index);
+        distributor.claimed_amount = distributor
+            .claimed_amount
+            .checked_add(amount)
+            .filter(|claimed| *claimed <= distributor.total_amount)
+            .ok_or(BlockTokError::RewardOverflow)?;
+        distributor.pack(&mut distributor_info.data.borrow_mut())?;
+        
+        // Payouts only go to the user's own associated token account
+        if get_associated_token_address(user_info.key, mint_info.key) != *token_account_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        invoke(
+            &associated_token_account_instruction::create_associated_token_account_idempotent(
+                user_info.key,
+                user_info.key,
+                mint_info.key,
+                &spl_token::id(),
+            ),
+            &[
+                user_info.clone(),
+                token_account_info.clone(),
+                user_info.clone(),
+                mint_info.clone(),
+                system_program_info.clone(),
+                token_program_info.clone(),
+                associated_token_program_info.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &token_instruction::transfer(
+                &spl_token::id(),
+                vault_info.key,
+                token_account_info.key,
+                distributor_info.key,
+                &[],
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                token_account_info.clone(),
+                distributor_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[&[REWARD_DISTRIBUTOR_SEED, &distributor.epoch.to_le_bytes(), &[distributor_bump]]],
+        )?;
+        
+        msg!("Distribution entry {} claimed successfully", index);
+        
+        Ok(())
+    }
+    
+    /// Process ClawbackDistribution instruction
+    pub fn process_clawback_distribution(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let authority_info = next_account_info(account_info_iter)?;
+        // Platform config is checked in `Processor::process`
+        let _platform_config_info = next_account_info(account_info_iter)?;
+        let distributor_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        
+        // Load distributor
+        if distributor_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut distributor = RewardDistributor::unpack(&distributor_info.data.borrow())?;
+        let (distributor_pubkey, distributor_bump) =
+            RewardDistributor::find_address(program_id, distributor.epoch);
+        if distributor_pubkey != *distributor_info.key
+            || RewardDistributor::find_vault_address(program_id, &distributor_pubkey).0
+                != *vault_info.key
+        {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if *token_program_info.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        // Check authority is the distributor's
+        if !authority_info.is_signer || distributor.get_authority() != *authority_info.key {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Only expired distributors are clawed back, and only once
+        let now = Clock::get()?.unix_timestamp as u64;
+        if distributor.clawed_back || !distributor.is_expired(now) {
+            return Err(BlockTokError::DistributionNotClawable.into());
+        }
+        distributor.clawed_back = true;
+        distributor.pack(&mut distributor_info.data.borrow_mut())?;
+        
+        // Move whatever is left in the vault
+        let remaining = TokenAccount::unpack(&vault_info.data.borrow())?.amount;
+        invoke_signed(
+            &token_instruction::transfer(
+                &spl_token::id(),
+                vault_info.key,
+                destination_info.key,
+                distributor_info.key,
+                &[],
+                remaining,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                distributor_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[&[REWARD_DISTRIBUTOR_SEED, &distributor.epoch.to_le_bytes(), &[distributor_bump]]],
+        )?;
+        
+        msg!("Reward distributor for epoch {} clawed back successfully", distributor.epoch);
+        
+        Ok(())
+    }
+    
+    /// Process AccrueCreatorEarnings instruction
+    pub fn process_accrue_creator_earnings(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let authority_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let payee_infos = account_info_iter.as_slice();
+        
+        // Check authority is an analytics oracle
+        let config = Self::load_platform_config_versioned(program_id, config_info)?;
+        if !authority_info.is_signer || !config.is_analytics_oracle(authority_info.key) {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        // Validate amount
+        if amount == 0 {
+            return Err(BlockTokError::InvalidRewardAmount.into());
+        }
+        
+        // Split the earnings between the content's payees
+        let content = Self::load_content(program_id, content_account_info)?;
+        let payees = content.royalty_distribution.payees(&content.get_creator());
+        if payees.len() != payee_infos.len() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let shares = content.royalty_distribution.split(amount)?;
+        
+        // Credit each share; the rewards account address is checked on accrual
+        let now = Clock::get()?.unix_timestamp as u64;
+        for ((payee, share), rewards_info) in payees.iter().zip(shares).zip(payee_infos) {
+            if share == 0 {
+                continue;
+            }
+            Self::accrue_user_rewards(
+                program_id,
+                authority_info,
+                payee,
+                rewards_info,
+                system_program_info,
+                share,
+                now,
+            )?;
+        }
+        
+        msg!("Creator earnings accrued successfully");
+        
+        Ok(())
+    }
+    
+    /// Process TipContent instruction
+    pub fn process_tip_content(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        
+        // Get accounts
+        let tipper_info = next_account_info(account_info_iter)?;
+        // Platform config is checked in `Processor::process`
+        let _platform_config_info = next_account_info(account_info_iter)?;
+        let content_account_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let payee_infos = account_info_iter.as_slice();
+        
+        // Check tipper is signer
+        if !tipper_info.is_signer {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        if *token_program_info.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        // Validate amount
+        if amount == 0 {
+            return Err(BlockTokError::InvalidRewardAmount.into());
+        }
+        
+        // Only published content can be tipped
+        let content = Self::load_content(program_id, content_account_info)?;
+        let now = Clock::get()?.unix_timestamp as u64;
+        if !content.is_published(now) {
+            return Err(BlockTokError::ContentNotPublished.into());
+        }
+        
+        // Split the tip between the content's payees
+        let payees = content.royalty_distribution.payees(&content.get_creator());
+        if payees.len() != payee_infos.len() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let shares = content.royalty_distribution.split(amount)?;
+        
+        // Pay each share to the payee's own associated token account
+        let (reward_mint_pubkey, _) = find_reward_mint_address(program_id);
+        for ((payee, share), payee_info) in payees.iter().zip(shares).zip(payee_infos) {
+            if get_associated_token_address(payee, &reward_mint_pubkey) != *payee_info.key {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            if share == 0 {
+                continue;
+            }
+            invoke(
+                &token_instruction::transfer(
+                    &spl_token::id(),
+                    source_info.key,
+                    payee_info.key,
+                    tipper_info.key,
+                    &[],
+                    share,
+                )?,
+                &[
+                    source_info.clone(),
+                    payee_info.clone(),
+                    tipper_info.clone(),
+                    token_program_info.clone(),
+                ],
+            )?;
+        }
+        
+        msg!("Content tipped successfully");
+        
+        Ok(())
+    }
+    
+    /// Accrue `amount` reward credits to `user`, creating their rewards
+    /// account at the expense of `payer_info` on the first accrual
+    fn accrue_user_rewards<'a>(
+        program_id: &Pubkey,
+        payer_info: &AccountInfo<'a>,
+        user: &Pubkey,
+        rewards_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        amount: u64,
+        now: u64,
+    ) -> ProgramResult {
+        let (rewards_pubkey, rewards_bump) = UserRewards::find_address(program_id, user);
+        if rewards_pubkey != *rewards_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        let mut rewards = if rewards_info.data_is_empty() {
+            Self::create_pda_account(
+                program_id,
+                payer_info,
+                rewards_info,
+                system_program_info,
+                UserRewards::size(),
+                &[USER_REWARDS_SEED, user.as_ref(), &[rewards_bump]],
+            )?;
+            UserRewards {
+                user: user.to_bytes(),
+                accrued: 0,
+                claimed: 0,
+                last_accrued_at: 0,
+            }
+        } else {
+            if rewards_info.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            UserRewards::unpack(&rewards_info.data.borrow())?
+        };
+        
+        rewards.accrue(amount, now)?;
+        rewards.pack(&mut rewards_info.data.borrow_mut())
+    }
+    
+    /// Mint `amount` reward tokens to `recipient_info`, signing as the reward
+    /// mint authority, and count them against the emission schedule of
+    /// `config`. Every reward-minting path goes through here
+    fn mint_reward_tokens<'a>(
+        program_id: &Pubkey,
+        config_info: &AccountInfo<'a>,
+        config: &mut PlatformConfig,
+        mint_info: &AccountInfo<'a>,
+        mint_authority_info: &AccountInfo<'a>,
+        recipient_info: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let (mint_authority_pubkey, mint_authority_bump) =
+            find_reward_mint_authority_address(program_id);
+        if find_reward_mint_address(program_id).0 != *mint_info.key
+            || mint_authority_pubkey != *mint_authority_info.key
+        {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if *token_program_info.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        // Stay within the emission schedule
+        let now = Clock::get()?.unix_timestamp as u64;
+        config.record_emission(amount, now)?;
+        config.pack(&mut config_info.data.borrow_mut())?;
+        
+        invoke_signed(
+            &token_instruction::mint_to(
+                &spl_token::id(),
+                mint_info.key,
+                recipient_info.key,
+                mint_authority_info.key,
+                &[],
+                amount,
+            )?,
+            &[
+                mint_info.clone(),
+                recipient_info.clone(),
+                mint_authority_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[&[REWARD_MINT_AUTHORITY_SEED, &[mint_authority_bump]]],
+        )
+    }
+    
+    /// Record the growth from the `previous` to the `current` analytics in the
+    /// content's history. Content without a history is left as is
+    fn record_analytics_history(
+        program_id: &Pubkey,
+        history_info: &AccountInfo,
+        content_key: &Pubkey,
+        now: u64,
+        previous: &ContentAnalytics,
+        current: &ContentAnalytics,
+    ) -> ProgramResult {
+        if AnalyticsHistory::find_address(program_id, content_key).0 != *history_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if history_info.data_is_empty() {
+            return Ok(());
+        }
+        if history_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        let mut history = AnalyticsHistory::unpack(&history_info.data.borrow())?;
+        history.record(now, previous, current);
+        history.pack(&mut history_info.data.borrow_mut())
+    }
+    
+    /// Check that the content's analytics were last updated at least the
+    /// config's `min_analytics_interval` before `now`
+    fn check_analytics_interval(
+        content: &Content,
+        config: Option<&PlatformConfig>,
+        now: u64,
+    ) -> ProgramResult {
+        let min_interval = config.map_or(0, |config| config.min_analytics_interval);
+        if now < content.analytics.updated_at.saturating_add(min_interval) {
+            return Err(BlockTokError::AnalyticsUpdateTooFrequent.into());
+        }
+        
+        Ok(())
+    }
+    
+    /// Recompute the content's engagement score with the config's weights,
+    /// which are all zero while the platform config is uninitialized
+    fn refresh_engagement_score(content: &mut Content, config: Option<&PlatformConfig>, now: u64) {
+        let weights = config
+            .map_or_else(EngagementWeights::default, |config| config.engagement_weights);
+        content.analytics.engagement_score =
+            weights.score(&content.analytics, content.created_at, now);
+    }
+    
+    /// Check that the instruction before the current one is an Ed25519 program
+    /// instruction verifying an analytics oracle's signature over `attestation`
+    fn verify_analytics_attestation(
+        instructions_info: &AccountInfo,
+        config: Option<&PlatformConfig>,
+        attestation: &AnalyticsAttestation,
+    ) -> ProgramResult {
+        // The Ed25519 program fails the transaction on a bad signature, so only
+        // who signed and what was signed are left to check
+        let current_index = instructions::load_current_index_checked(instructions_info)?;
+        if current_index == 0 {
+            return Err(BlockTokError::InvalidAttestation.into());
+        }
+        let verify_instruction =
+            instructions::load_instruction_at_checked(current_index as usize - 1, instructions_info)?;
+        if verify_instruction.program_id != ed25519_program::id() {
+            return Err(BlockTokError::InvalidAttestation.into());
+        }
+        
+        let (oracle, message) = Self::parse_ed25519_instruction(&verify_instruction.data)
+            .ok_or(BlockTokError::InvalidAttestation)?;
+        if message != attestation.message().as_slice() {
+            return Err(BlockTokError::InvalidAttestation.into());
+        }
+        
+        if !config.map_or(false, |config| config.is_analytics_oracle(&oracle)) {
+            return Err(BlockTokError::Unauthorized.into());
+        }
+        
+        Ok(())
+    }
+    
+    /// Get the public key and message of an Ed25519 program instruction
+    /// verifying a single signature over data in the instruction itself
+    fn parse_ed25519_instruction(data: &[u8]) -> Option<(Pubkey, &[u8])> {
+        if data.len() < ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE || data[0] != 1 {
+            return None;
+        }
+        let offset = |i: usize| {
+            let start = ED25519_OFFSETS_START + 2 * i;
+            u16::from_le_bytes([data[start], data[start + 1]])
+        };
+        
+        // Signature, public key and message must all come from this instruction
+        if offset(1) != u16::MAX || offset(3) != u16::MAX || offset(6) != u16::MAX {
+            return None;
+        }
+        
+        let public_key_offset = offset(2) as usize;
+        let message_offset = offset(4) as usize;
+        let message_size = offset(5) as usize;
+        let public_key = data.get(public_key_offset..public_key_offset + PUBKEY_BYTES)?;
+        let message = data.get(message_offset..message_offset + message_size)?;
+        Some((Pubkey::try_from(public_key).ok()?, message))
+    }
+    
+    /// Load the platform config, checking that it is owned by this program and
+    /// is the config PDA
+    pub fn load_platform_config(
+        program_id: &Pubkey,
+        config_info: &AccountInfo,
+    ) -> Result<PlatformConfig, ProgramError> {
+        if config_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if PlatformConfig::find_address(program_id).0 != *config_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        PlatformConfig::unpack(&config_info.data.borrow())
+    }
+    
+    /// Load the platform config like `load_platform_config`, but accept any
+    /// supported layout version. For pause checks and for instructions that
+    /// rewrite the config
+    fn load_platform_config_versioned(
+        program_id: &Pubkey,
+        config_info: &AccountInfo,
+    ) -> Result<PlatformConfig, ProgramError> {
+        if config_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if PlatformConfig::find_address(program_id).0 != *config_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        let (config, _) = PlatformConfig::unpack_versioned(&config_info.data.borrow())?;
+        Ok(config)
+    }
+    
+    /// Read this program's upgrade authority from its program data account
+    fn get_upgrade_authority(
+        program_id: &Pubkey,
+        program_data_info: &AccountInfo,
+    ) -> Result<Option<Pubkey>, ProgramError> {
+        let (program_data_pubkey, _) =
+            Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+        if *program_data_info.key != program_data_pubkey
+            || *program_data_info.owner != bpf_loader_upgradeable::id()
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        // Program data starts with a bincode `UpgradeableLoaderState::ProgramData`:
+        // a u32 variant tag, the u64 deploy slot, then the `Option<Pubkey>` authority
+        let data = program_data_info.data.borrow();
+        if data.len() < UpgradeableLoaderState::size_of_programdata_metadata()
+            || data[..4] != PROGRAM_DATA_VARIANT.to_le_bytes()
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        match data[12] {
+            0 => Ok(None),
+            1 => Ok(Some(Pubkey::new_from_array(
+                <[u8; 32]>::try_from(&data[13..45]).map_err(|_| ProgramError::InvalidAccountData)?,
+            ))),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+    
+    /// Load a content account, checking that it is owned by this program and
+    /// lives at its content PDA
+    fn load_content(
+        program_id: &Pubkey,
+        content_account_info: &AccountInfo,
+    ) -> Result<Content, ProgramError> {
+        if content_account_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        let content = Content::unpack(&content_account_info.data.borrow())?;
+        if !content.is_valid_address(program_id, content_account_info.key) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        Ok(content)
+    }
+    
+    /// Load a creator profile, checking that it is owned by this program and
+    /// is the profile PDA of `creator`
+    fn load_creator_profile(
+        program_id: &Pubkey,
+        creator_profile_info: &AccountInfo,
+        creator: &Pubkey,
+    ) -> Result<CreatorProfile, ProgramError> {
+        if creator_profile_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        let creator_profile = CreatorProfile::unpack(&creator_profile_info.data.borrow())?;
+        if creator_profile.get_creator() != *creator
+            || CreatorProfile::find_address(program_id, creator).0 != *creator_profile_info.key
+        {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        Ok(creator_profile)
+    }
+    
+    /// Load a category account, checking that it is owned by this program and
+    /// is the PDA for `name`
+    fn load_category(
+        program_id: &Pubkey,
+        category_info: &AccountInfo,
+        name: &str,
+    ) -> Result<Category, ProgramError> {
+        if category_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if Category::find_address(program_id, name).0 != *category_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        
+        Category::unpack(&category_info.data.borrow())
+    }
+    
+    /// Check a content's tag list is within `MAX_CONTENT_TAGS`, has no duplicates
+    /// and only names valid categories
+    fn validate_tags(tags: &[String]) -> ProgramResult {
+        if tags.len() > MAX_CONTENT_TAGS {
+            return Err(BlockTokError::TooManyTags.into());
+        }
+        for (i, tag) in tags.iter().enumerate() {
+            // Tags name categories, whose names must fit in a seed
+            if !Category::is_valid_name(tag) {
+                return Err(BlockTokError::InvalidCategoryName.into());
+            }
+            if tags[..i].contains(tag) {
+                return Err(BlockTokError::DuplicateTag.into());
+            }
+        }
+        Ok(())
+    }
+    
+    /// Tag `content` with the active category `tag`, creating its category
+    /// index with rent paid by `payer_info`
+    fn add_content_tag<'a>(
+        program_id: &Pubkey,
+        payer_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        content: &Pubkey,
+        tag: &str,
+        category_info: &AccountInfo<'a>,
+        category_index_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let mut category = Self::load_category(program_id, category_info, tag)?;
+        if !category.active {
+            return Err(BlockTokError::CategoryInactive.into());
+        }
+        
+        // Create the category index
+        let (category_index_pubkey, category_index_bump) =
+            CategoryIndex::find_address(program_id, category_info.key, content);
+        if category_index_pubkey != *category_index_info.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Self::create_pda_account(
+            program_id,
+            payer_info,
+            category_index_info,
+            system_program_info,
+            CategoryIndex::size(),
+            &[
+                CATEGORY_INDEX_SEED,
+                category_info.key.as_ref(),
+                content.as_ref(),
+                &[category_index_bump],
+            ],
+        )?;
+        let category_index = CategoryIndex {
+            category: category_info.key.to_bytes(),
+            content: content.to_bytes(),
+        };
+        category_index.pack(&mut category_index_info.data.borrow_mut())?;
+        
+        // Count the content in the category
+        category.content_count = category
+            .content_count
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        category.pack(&mut category_info.data.borrow_mut())
+    }
+    
+    /// Remove the tag `tag` from `content`, closing its category index and
+    /// returning the rent to `destination_info`
+    fn remove_content_tag(
+        program_id: &Pubkey,
+        destination_info: &AccountInfo,
+        content: &Pubkey,
+        tag: &str,
+        category_info: &AccountInfo,
+        category_index_info: &AccountInfo,
+    ) -> ProgramResult {
+        let mut category = Self::load_category(program_id, category_info, tag)?;
+        
+        // Close the category index
+        if category_index_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if CategoryIndex::find_address(program_id, category_info.key, content).0
+            != *category_index_info.key
+        {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Self::close_account(category_index_info, destination_info)?;
+        
+        // Stop counting the content in the category
+        category.content_count = category.content_count.saturating_sub(1);
+        category.pack(&mut category_info.data.borrow_mut())
+    }
+    
+    /// Create a PDA account of `size` bytes owned by `owner`, with rent paid by
+    /// `payer_info`. PDA addresses are predictable, so anyone may have sent
+    /// lamports to the address first, which makes `create_account` fail; instead
+    /// only the missing rent is transferred and the account is then allocated and
+    /// assigned with the PDA seeds.
+    fn create_pda_account<'a>(
+        owner: &Pubkey,
+        payer_info: &AccountInfo<'a>,
+        account_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        size: usize,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let rent = Rent::get()?;
+        let missing_lamports = rent
+            .minimum_balance(size)
+            .saturating_sub(account_info.lamports());
+        if missing_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(payer_info.key, account_info.key, missing_lamports),
+                &[
+                    payer_info.clone(),
+                    account_info.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        }
+        
+        // Fails if the account is already in use
+        invoke_signed(
+            &system_instruction::allocate(account_info.key, size as u64),
+            &[account_info.clone(), system_program_info.clone()],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(account_info.key, owner),
+            &[account_info.clone(), system_program_info.clone()],
+            &[signer_seeds],
+        )
+    }
+    
+    /// Close a program-owned account, zeroing its data and moving all of its
+    /// lamports to `destination_info`
+    fn close_account(
+        account_info: &AccountInfo,
+        destination_info: &AccountInfo,
+    ) -> ProgramResult {
+        let destination_lamports = destination_info
+            .lamports()
+            .checked_add(account_info.lamports())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        **destination_info.try_borrow_mut_lamports()? = destination_lamports;
+        **account_info.try_borrow_mut_lamports()? = 0;
+        
+        account_info.data.borrow_mut().fill(0);
+        account_info.assign(&system_program::id());
+        account_info.realloc(0, false)
+    }
+    
+    /// Resize a program-owned account, charging the rent difference to `payer_info`
+    /// when it grows and refunding it when it shrinks
+    fn resize_account<'a>(
+        account_info: &AccountInfo<'a>,
+        payer_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        new_size: usize,
+    ) -> ProgramResult {
+        let rent = Rent::get()?;
+        let required_lamports = rent.minimum_balance(new_size);
+        let current_lamports = account_info.lamports();
+        
+        if required_lamports > current_lamports {
+            // Top up rent from the payer
+            invoke(
+                &system_instruction::transfer(
+                    payer_info.key,
+                    account_info.key,
+                    required_lamports - current_lamports,
+                ),
+                &[
+                    payer_info.clone(),
+                    account_info.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        } else if current_lamports > required_lamports {
+            // Refund excess rent to the payer
+            let refund = current_lamports - required_lamports;
+            **account_info.try_borrow_mut_lamports()? -= refund;
+            **payer_info.try_borrow_mut_lamports()? += refund;
+        }
+        
+        account_info.realloc(new_size, true)
+    }
+}
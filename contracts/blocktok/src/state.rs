@@ -1,5 +1,207 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::{Pubkey, PUBKEY_BYTES};
+use solana_program::{
+    entrypoint::ProgramResult,
+    hash::hash,
+    program_error::ProgramError,
+    pubkey::{Pubkey, MAX_SEED_LEN, PUBKEY_BYTES},
+};
+
+use crate::{error::BlockTokError, merkle};
+
+/// Size of the header at the start of every state account: an 8 byte
+/// discriminator followed by a 1 byte layout version
+pub const ACCOUNT_HEADER_SIZE: usize = 8 + 1;
+
+/// A program-owned state account, stored as `discriminator || version || data`
+pub trait AccountState: BorshSerialize + BorshDeserialize {
+    /// Discriminator identifying the account type
+    const DISCRIMINATOR: [u8; 8];
+    
+    /// Current layout version
+    const VERSION: u8;
+    
+    /// Deserialize an account body written under the given layout version
+    fn deserialize_version(version: u8, data: &mut &[u8]) -> Result<Self, ProgramError> {
+        if version != Self::VERSION {
+            return Err(BlockTokError::UnsupportedAccountVersion.into());
+        }
+        Self::deserialize(data).map_err(|_| ProgramError::InvalidAccountData)
+    }
+    
+    /// Deserialize an account written under any supported layout version,
+    /// returning it together with that version
+    fn unpack_versioned(data: &[u8]) -> Result<(Self, u8), ProgramError> {
+        if data.len() < ACCOUNT_HEADER_SIZE || data[..8] != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let version = data[8];
+        let account = Self::deserialize_version(version, &mut &data[ACCOUNT_HEADER_SIZE..])?;
+        Ok((account, version))
+    }
+    
+    /// Deserialize an account that must already be at the current layout version
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let (account, version) = Self::unpack_versioned(data)?;
+        if version != Self::VERSION {
+            return Err(BlockTokError::AccountNeedsMigration.into());
+        }
+        Ok(account)
+    }
+    
+    /// Serialize the account with a current header
+    fn pack(&self, dst: &mut [u8]) -> ProgramResult {
+        if dst.len() < ACCOUNT_HEADER_SIZE {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        dst[..8].copy_from_slice(&Self::DISCRIMINATOR);
+        dst[8] = Self::VERSION;
+        let mut body = &mut dst[ACCOUNT_HEADER_SIZE..];
+        self.serialize(&mut body)
+            .map_err(|_| ProgramError::AccountDataTooSmall)
+    }
+}
+
+/// Maximum number of royalty recipients a content may split royalties between
+pub const MAX_ROYALTY_RECIPIENTS: usize = 16;
+
+/// Seed prefix for content account PDAs
+pub const CONTENT_SEED: &[u8] = b"content";
+
+/// Seed prefix for creator profile PDAs
+pub const CREATOR_PROFILE_SEED: &[u8] = b"creator_profile";
+
+/// Seed prefix for creator content index PDAs
+pub const CONTENT_INDEX_SEED: &[u8] = b"content_index";
+
+/// Seed for the singleton platform config PDA
+pub const PLATFORM_CONFIG_SEED: &[u8] = b"platform_config";
+
+/// Maximum platform fee in basis points (100%)
+pub const MAX_PLATFORM_FEE_BPS: u16 = 10000;
+
+/// Seed prefix for category PDAs
+pub const CATEGORY_SEED: &[u8] = b"category";
+
+/// Seed prefix for category index PDAs
+pub const CATEGORY_INDEX_SEED: &[u8] = b"category_index";
+
+/// Maximum number of category tags on a single content
+pub const MAX_CONTENT_TAGS: usize = 5;
+
+/// Maximum length of a category name, so the name can be used as a seed directly
+pub const MAX_CATEGORY_NAME_LEN: usize = MAX_SEED_LEN;
+
+/// Maximum number of analytics oracles in the platform config
+pub const MAX_ANALYTICS_ORACLES: usize = 8;
+
+/// Maximum number of contents a single `BatchUpdateAnalytics` can update,
+/// keeping the instruction within the transaction size and compute limits
+pub const MAX_BATCH_ANALYTICS_UPDATES: usize = 8;
+
+/// Seed prefix for analytics history PDAs
+pub const ANALYTICS_HISTORY_SEED: &[u8] = b"analytics_history";
+
+/// Seed prefix for view receipt PDAs
+pub const VIEW_RECEIPT_SEED: &[u8] = b"view_receipt";
+
+/// Seed prefix for engagement epoch PDAs
+pub const ENGAGEMENT_EPOCH_SEED: &[u8] = b"engagement_epoch";
+
+/// Seed prefix for per-content engagement epoch totals PDAs
+pub const EPOCH_TOTALS_SEED: &[u8] = b"epoch_totals";
+
+/// Seed for the reward token mint PDA
+pub const REWARD_MINT_SEED: &[u8] = b"reward_mint";
+
+/// Seed for the PDA that is the reward mint's mint authority
+pub const REWARD_MINT_AUTHORITY_SEED: &[u8] = b"reward_mint_authority";
+
+/// Decimals of the reward token
+pub const REWARD_TOKEN_DECIMALS: u8 = 6;
+
+/// Seed prefix for user rewards PDAs
+pub const USER_REWARDS_SEED: &[u8] = b"user_rewards";
+
+/// Seed prefix for reward distributor PDAs
+pub const REWARD_DISTRIBUTOR_SEED: &[u8] = b"reward_distributor";
+
+/// Seed prefix for reward distributor vault PDAs
+pub const DISTRIBUTOR_VAULT_SEED: &[u8] = b"distributor_vault";
+
+/// Maximum number of recipients of a single reward distributor, keeping its
+/// claim bitmap at 8 KiB
+pub const MAX_DISTRIBUTOR_RECIPIENTS: u32 = 65536;
+
+/// Watch progress of a fully watched content, in basis points
+pub const MAX_WATCH_PROGRESS_BPS: u16 = 10000;
+
+/// Number of buckets an analytics history keeps before reusing the oldest:
+/// two days of hourly buckets, or seven weeks of daily ones
+pub const ANALYTICS_HISTORY_BUCKETS: usize = 48;
+
+/// Bits of `PlatformConfig::paused_instructions`, one per pausable instruction.
+///
+/// Admin instructions cannot be paused, so a paused program can always be unpaused.
+pub mod pause_flags {
+    pub const INITIALIZE_CONTENT: u64 = 1 << 0;
+    pub const MINT_NFT: u64 = 1 << 1;
+    pub const UPDATE_ANALYTICS: u64 = 1 << 2;
+    pub const SET_ROYALTY_DISTRIBUTION: u64 = 1 << 3;
+    pub const UPDATE_CONTENT_METADATA: u64 = 1 << 4;
+    pub const CLOSE_CONTENT: u64 = 1 << 5;
+    pub const MIGRATE_CONTENT: u64 = 1 << 6;
+    pub const PUBLISH_CONTENT: u64 = 1 << 7;
+    pub const UNLIST_CONTENT: u64 = 1 << 8;
+    pub const ARCHIVE_CONTENT: u64 = 1 << 9;
+    pub const INCREMENT_ANALYTICS: u64 = 1 << 10;
+    pub const INITIALIZE_ANALYTICS_HISTORY: u64 = 1 << 11;
+    pub const RECORD_VIEW: u64 = 1 << 12;
+    pub const COMMIT_ENGAGEMENT_EPOCH: u64 = 1 << 13;
+    pub const RECORD_EPOCH_TOTALS: u64 = 1 << 14;
+    pub const BATCH_UPDATE_ANALYTICS: u64 = 1 << 15;
+    pub const MINT_REWARDS: u64 = 1 << 16;
+    pub const ACCRUE_REWARDS: u64 = 1 << 17;
+    pub const CLAIM_REWARDS: u64 = 1 << 18;
+    pub const CREATE_REWARD_DISTRIBUTOR: u64 = 1 << 19;
+    pub const CLAIM_DISTRIBUTION: u64 = 1 << 20;
+    pub const CLAWBACK_DISTRIBUTION: u64 = 1 << 21;
+    pub const ACCRUE_CREATOR_EARNINGS: u64 = 1 << 22;
+    pub const TIP_CONTENT: u64 = 1 << 23;
+}
+
+/// Read one Borsh-encoded field, for hand-written versioned deserialization
+fn read_field<T: BorshDeserialize>(data: &mut &[u8]) -> Result<T, ProgramError> {
+    T::deserialize(data).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+/// Hash a content ID into a fixed-size PDA seed.
+///
+/// Solana caps each seed at 32 bytes, so content IDs such as hex digests or
+/// URLs cannot be used as seeds directly.
+pub fn content_id_seed(content_id: &str) -> [u8; 32] {
+    hash(content_id.as_bytes()).to_bytes()
+}
+
+/// Find the address of the reward token mint
+pub fn find_reward_mint_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REWARD_MINT_SEED], program_id)
+}
+
+/// Find the address of the reward mint's mint authority. Only the program can
+/// sign for it, so reward tokens are only ever minted by program instructions
+pub fn find_reward_mint_authority_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REWARD_MINT_AUTHORITY_SEED], program_id)
+}
+
+/// Split a change of content tags into the tags removed from `current` and
+/// the tags added by `new`, each in their original order.
+///
+/// Instructions that change tags expect category accounts in this order.
+pub fn tag_changes<'a>(current: &'a [String], new: &'a [String]) -> (Vec<&'a String>, Vec<&'a String>) {
+    let removed = current.iter().filter(|tag| !new.contains(tag)).collect();
+    let added = new.iter().filter(|tag| !current.contains(tag)).collect();
+    (removed, added)
+}
 
 /// Represents a content created in the BlockTok platform
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
@@ -33,6 +235,324 @@ pub struct Content {
     
     /// Royalty distribution
     pub royalty_distribution: RoyaltyDistribution,
+    
+    /// Names of the categories the content is tagged with (since version 2)
+    pub tags: Vec<String>,
+    
+    /// Visibility of the content (since version 3; older content is `Published`)
+    pub status: ContentStatus,
+    
+    /// Unix timestamp a `Scheduled` content becomes published at (since version 3)
+    pub publish_at: u64,
+    
+    /// Nonce of the last analytics attestation applied, to reject replays (since version 4)
+    pub analytics_nonce: u64,
+}
+
+/// Visibility of a content.
+///
+/// Content starts as a `Draft`. `Publish` moves it to `Published`, or to
+/// `Scheduled` until `publish_at` passes; `Unlist` hides published content and
+/// `Archive` retires content of any status for good.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentStatus {
+    /// Only visible to the creator
+    Draft,
+    /// Becomes published once `publish_at` passes
+    Scheduled,
+    /// Publicly listed
+    Published,
+    /// Reachable by link but not listed
+    Unlisted,
+    /// Retired; can no longer be published
+    Archived,
+}
+
+/// Per-creator profile, created with the creator's first content
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CreatorProfile {
+    /// The creator this profile belongs to
+    pub creator: [u8; PUBKEY_BYTES],
+    
+    /// Number of contents the creator has initialized. Only ever increases,
+    /// so indexes `0..content_count` each have a `ContentIndex` account
+    pub content_count: u64,
+}
+
+/// Pointer from a position in a creator's catalogue to a content account.
+///
+/// Pointers are never removed, so a closed content leaves its index pointing
+/// at an empty account.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ContentIndex {
+    /// The creator whose catalogue this index belongs to
+    pub creator: [u8; PUBKEY_BYTES],
+    
+    /// Position of the content in the creator's catalogue
+    pub index: u64,
+    
+    /// The content account at this position
+    pub content: [u8; PUBKEY_BYTES],
+}
+
+/// A category content can be tagged with, created by the platform admin
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Category {
+    /// Category name, also its PDA seed
+    pub name: String,
+    
+    /// Whether content may currently be tagged with this category. Existing
+    /// tags stay in place when a category is deactivated
+    pub active: bool,
+    
+    /// Number of contents currently tagged with this category
+    pub content_count: u64,
+}
+
+/// Marks a content as tagged with a category.
+///
+/// One index exists per (category, content) pair while the tag is set, so a
+/// category's contents can be listed by filtering program accounts on `category`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CategoryIndex {
+    /// The category account
+    pub category: [u8; PUBKEY_BYTES],
+    
+    /// The tagged content account
+    pub content: [u8; PUBKEY_BYTES],
+}
+
+/// Per-content ring buffer of analytics deltas in fixed time buckets.
+///
+/// A bucket's slot is derived from its start time, so each update lands in
+/// the slot for the current bucket, clearing it first if it still holds an
+/// older bucket.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AnalyticsHistory {
+    /// The content account the history belongs to
+    pub content: [u8; PUBKEY_BYTES],
+    
+    /// Length of each bucket
+    pub granularity: HistoryGranularity,
+    
+    /// Always `ANALYTICS_HISTORY_BUCKETS` long
+    pub buckets: Vec<AnalyticsBucket>,
+}
+
+/// Proof that a viewer has viewed a content, created on their first view.
+///
+/// A content's view count grows once per receipt, so views backed by receipts
+/// are unique and can be attributed to the viewer that signed for them.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ViewReceipt {
+    /// The viewed content account
+    pub content: [u8; PUBKEY_BYTES],
+    
+    /// The viewer
+    pub viewer: [u8; PUBKEY_BYTES],
+    
+    /// Unix timestamp of the viewer's first view
+    pub first_seen_at: u64,
+    
+    /// Furthest watch progress reported, in basis points of the content
+    pub watch_progress_bps: u16,
+}
+
+/// Commitment by an analytics oracle to every engagement event in a time
+/// window, as the Merkle root of the events' `EngagementEvent::leaf` hashes.
+/// Events stay off-chain, and any of them can be proven included with
+/// `VerifyEngagement`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct EngagementEpoch {
+    /// The analytics oracle that committed the epoch
+    pub authority: [u8; PUBKEY_BYTES],
+    
+    /// Epoch number, unique across the platform
+    pub epoch: u64,
+    
+    /// Unix timestamp of the start of the window (inclusive)
+    pub start_time: u64,
+    
+    /// Unix timestamp of the end of the window (exclusive)
+    pub end_time: u64,
+    
+    /// Merkle root of the events in the window
+    pub merkle_root: [u8; 32],
+    
+    /// Number of events in the window
+    pub event_count: u64,
+    
+    /// Unix timestamp of the commitment
+    pub committed_at: u64,
+}
+
+/// Engagement totals of a single content over an engagement epoch
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct EpochContentTotals {
+    /// The engagement epoch account
+    pub epoch: [u8; PUBKEY_BYTES],
+    
+    /// The content account
+    pub content: [u8; PUBKEY_BYTES],
+    
+    /// Number of views in the epoch
+    pub views: u64,
+    
+    /// Number of likes in the epoch
+    pub likes: u64,
+    
+    /// Number of shares in the epoch
+    pub shares: u64,
+    
+    /// Number of comments in the epoch
+    pub comments: u64,
+}
+
+/// Reward credits of a user, accrued from verified engagement and paid out
+/// in reward tokens by `ClaimRewards`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct UserRewards {
+    /// The user
+    pub user: [u8; PUBKEY_BYTES],
+    
+    /// Total credits accrued, in reward token base units
+    pub accrued: u64,
+    
+    /// Total credits already claimed
+    pub claimed: u64,
+    
+    /// Unix timestamp of the last accrual
+    pub last_accrued_at: u64,
+}
+
+/// Reward payouts of one epoch, committed as the Merkle root of their
+/// `DistributionEntry::leaf` hashes and backed by a token vault the
+/// distributor owns. Each recipient claims their own entry with a proof
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RewardDistributor {
+    /// The analytics oracle that created the distributor and funded its vault
+    pub authority: [u8; PUBKEY_BYTES],
+    
+    /// Epoch number, unique across the platform
+    pub epoch: u64,
+    
+    /// Merkle root of the entries
+    pub merkle_root: [u8; 32],
+    
+    /// Reward tokens deposited in the vault, in base units
+    pub total_amount: u64,
+    
+    /// Reward tokens claimed so far
+    pub claimed_amount: u64,
+    
+    /// Number of entries, indexed from zero
+    pub num_recipients: u32,
+    
+    /// Unix timestamp from which claims are refused and the rest of the
+    /// vault can be clawed back
+    pub expires_at: u64,
+    
+    /// Whether the unclaimed tokens were clawed back
+    pub clawed_back: bool,
+    
+    /// One bit per entry, set once the entry is claimed
+    pub claimed_bitmap: Vec<u8>,
+}
+
+/// A single payout, one leaf of a reward distributor's Merkle tree
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DistributionEntry {
+    /// Position of the entry in the claim bitmap
+    pub index: u32,
+    
+    /// The recipient
+    pub user: [u8; PUBKEY_BYTES],
+    
+    /// Reward tokens, in base units
+    pub amount: u64,
+}
+
+/// Length of the buckets of an analytics history
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryGranularity {
+    Hourly,
+    Daily,
+}
+
+/// Analytics deltas recorded during one bucket of an analytics history
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnalyticsBucket {
+    /// Unix timestamp the bucket starts at, or zero if the slot was never used
+    pub start: u64,
+    
+    /// Views added during the bucket
+    pub views: u64,
+    
+    /// Likes added during the bucket
+    pub likes: u64,
+    
+    /// Shares added during the bucket
+    pub shares: u64,
+    
+    /// Comments added during the bucket
+    pub comments: u64,
+}
+
+/// Global platform settings, stored in a singleton PDA
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PlatformConfig {
+    /// Authority allowed to change the config
+    pub admin: [u8; PUBKEY_BYTES],
+    
+    /// Admin nominated by `TransferAdmin`, until they accept
+    pub pending_admin: Option<[u8; PUBKEY_BYTES]>,
+    
+    /// Account that receives platform fees
+    pub treasury: [u8; PUBKEY_BYTES],
+    
+    /// Platform fee in basis points (e.g., 250 = 2.5%)
+    pub platform_fee_bps: u16,
+    
+    /// Bitmask of enabled platform features
+    pub features: u64,
+    
+    /// Emergency switch pausing every non-admin instruction (since version 2)
+    pub paused: bool,
+    
+    /// Bitmask of individually paused instructions, see `pause_flags` (since version 2)
+    pub paused_instructions: u64,
+    
+    /// Authorities allowed to report analytics for any content (since version 3)
+    pub analytics_oracles: Vec<[u8; PUBKEY_BYTES]>,
+    
+    /// Whether creators may still report analytics for their own content
+    /// (since version 3; older configs allow it)
+    pub allow_creator_analytics: bool,
+    
+    /// Minimum seconds between analytics updates of a content (since version 4;
+    /// older configs have no minimum)
+    pub min_analytics_interval: u64,
+    
+    /// Weights of the content engagement score (since version 5; older
+    /// configs weigh everything zero)
+    pub engagement_weights: EngagementWeights,
+    
+    /// Reward credits a viewer accrues for each view backed by a new view
+    /// receipt (since version 6; older configs reward nothing)
+    pub view_reward: u64,
+    
+    /// Limits on minting reward tokens (since version 7; older configs mint
+    /// nothing until the admin sets a schedule)
+    pub emission_schedule: EmissionSchedule,
+    
+    /// Reward tokens minted so far, in base units (since version 7)
+    pub emitted_total: u64,
+    
+    /// Emission epoch counted by `emitted_in_epoch` (since version 7)
+    pub emission_epoch: u64,
+    
+    /// Reward tokens minted during `emission_epoch` (since version 7)
+    pub emitted_in_epoch: u64,
 }
 
 /// Analytics data for content
@@ -52,6 +572,132 @@ pub struct ContentAnalytics {
     
     /// Timestamp of the last update
     pub updated_at: u64,
+    
+    /// Watch time, completion and replays
+    pub watch: WatchMetrics,
+    
+    /// Weighted, time-decayed engagement, recomputed from the platform
+    /// config's `engagement_weights` whenever the analytics change
+    pub engagement_score: u64,
+}
+
+/// Weights of the engagement score, set by the platform admin.
+///
+/// The score is the weighted sum of the analytics counters, halved every
+/// `decay_half_life` seconds since the content was created. All weights
+/// default to zero, so scores stay zero until the admin configures them.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngagementWeights {
+    /// Points per view
+    pub views: u64,
+    
+    /// Points per like
+    pub likes: u64,
+    
+    /// Points per share
+    pub shares: u64,
+    
+    /// Points per comment
+    pub comments: u64,
+    
+    /// Points per second watched
+    pub watch_seconds: u64,
+    
+    /// Seconds after which the score halves, or zero for no decay
+    pub decay_half_life: u64,
+}
+
+/// Schedule capping reward token minting, set by the platform admin.
+///
+/// Time is cut into epochs of `epoch_duration` seconds from `start_time`.
+/// Each epoch may mint `tokens_per_epoch`, halved every `halving_interval`
+/// epochs, and all epochs together may mint at most `total_cap`. The default
+/// schedule mints nothing, so rewards stay disabled until the admin sets one.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EmissionSchedule {
+    /// Unix timestamp the first epoch starts at
+    pub start_time: u64,
+    
+    /// Seconds per epoch, or zero to mint nothing
+    pub epoch_duration: u64,
+    
+    /// Tokens the first epochs may mint each, in base units
+    pub tokens_per_epoch: u64,
+    
+    /// Epochs after which the per-epoch allowance halves, or zero to never halve
+    pub halving_interval: u64,
+    
+    /// Tokens that may ever be minted, in base units
+    pub total_cap: u64,
+}
+
+/// Watch metrics of short-form video, kept as totals so they can only grow
+/// like the other counters
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WatchMetrics {
+    /// Total seconds watched across all views
+    pub watch_seconds: u64,
+    
+    /// Sum of each view's completion in basis points, so the average
+    /// completion rate is this over the number of views
+    pub total_completion_bps: u64,
+    
+    /// Number of views by viewers that had already viewed the content
+    pub replays: u64,
+}
+
+/// Analytics counters signed off-chain by an analytics oracle, which anyone
+/// can then relay with `UpdateAnalytics`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AnalyticsAttestation {
+    /// Content account the counters are for
+    pub content: [u8; PUBKEY_BYTES],
+    
+    /// Number of views
+    pub views: u64,
+    
+    /// Number of likes
+    pub likes: u64,
+    
+    /// Number of shares
+    pub shares: u64,
+    
+    /// Number of comments
+    pub comments: u64,
+    
+    /// Watch time, completion and replays
+    pub watch: WatchMetrics,
+    
+    /// Must be greater than the content's `analytics_nonce`
+    pub nonce: u64,
+    
+    /// Unix timestamp after which the attestation is rejected
+    pub expiry: u64,
+}
+
+/// A single engagement event, one leaf of an engagement epoch's Merkle tree
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EngagementEvent {
+    /// The content account engaged with
+    pub content: [u8; PUBKEY_BYTES],
+    
+    /// The engaging user
+    pub user: [u8; PUBKEY_BYTES],
+    
+    /// Kind of engagement
+    pub kind: EngagementKind,
+    
+    /// Unix timestamp of the event
+    pub timestamp: u64,
+}
+
+/// Kind of an engagement event
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngagementKind {
+    View,
+    Like,
+    Share,
+    Comment,
 }
 
 /// Royalty distribution for content
@@ -72,6 +718,9 @@ pub struct RoyaltyRecipient {
 }
 
 impl Content {
+    /// Layout version of content written before account headers existed
+    pub const LEGACY_VERSION: u8 = 0;
+    
     /// Get the size this account will take in storage
     pub fn get_account_size(
         content_id: &str,
@@ -79,9 +728,11 @@ impl Content {
         description: &str,
         content_url: &str,
         content_type: &str,
+        num_royalty_recipients: usize,
+        tags: &[String],
     ) -> usize {
         // Base size + string lengths
-        8 + // Discriminator
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
         PUBKEY_BYTES + // creator
         4 + content_id.len() + // content_id (length prefix + string)
         4 + title.len() + // title (length prefix + string)
@@ -91,7 +742,62 @@ impl Content {
         8 + // created_at
         1 + PUBKEY_BYTES + // nft_mint (1 byte for Option variant + pubkey)
         ContentAnalytics::size() + // analytics
-        RoyaltyDistribution::size() // royalty_distribution
+        RoyaltyDistribution::size(num_royalty_recipients) + // royalty_distribution
+        4 + tags.iter().map(|tag| 4 + tag.len()).sum::<usize>() + // tags (length prefix + strings)
+        1 + // status
+        8 + // publish_at
+        8 // analytics_nonce
+    }
+    
+    /// Get the size needed to store this content as it currently is
+    pub fn account_size(&self) -> usize {
+        Self::get_account_size(
+            &self.content_id,
+            &self.title,
+            &self.description,
+            &self.content_url,
+            &self.content_type,
+            self.royalty_distribution.recipients.len(),
+            &self.tags,
+        )
+    }
+    
+    /// Find the content account address for a creator and content ID
+    pub fn find_address(program_id: &Pubkey, creator: &Pubkey, content_id: &str) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[CONTENT_SEED, creator.as_ref(), &content_id_seed(content_id)],
+            program_id,
+        )
+    }
+    
+    /// Find the address content accounts were created at before content IDs
+    /// were hashed, when the raw ID was used as the seed.
+    ///
+    /// Returns `None` for IDs longer than a seed, which could never have been
+    /// created that way. The program accepts either address for existing
+    /// content, so clients holding legacy accounts can pass this one instead.
+    pub fn find_legacy_address(
+        program_id: &Pubkey,
+        creator: &Pubkey,
+        content_id: &str,
+    ) -> Option<(Pubkey, u8)> {
+        if content_id.len() > MAX_SEED_LEN {
+            return None;
+        }
+        Some(Pubkey::find_program_address(
+            &[CONTENT_SEED, creator.as_ref(), content_id.as_bytes()],
+            program_id,
+        ))
+    }
+    
+    /// Check that `address` is this content's PDA under either derivation
+    pub fn is_valid_address(&self, program_id: &Pubkey, address: &Pubkey) -> bool {
+        let creator = self.get_creator();
+        if Self::find_address(program_id, &creator, &self.content_id).0 == *address {
+            return true;
+        }
+        Self::find_legacy_address(program_id, &creator, &self.content_id)
+            .map_or(false, |(legacy_address, _)| legacy_address == *address)
     }
     
     /// Get the public key of the creator
@@ -108,6 +814,623 @@ impl Content {
     pub fn set_nft_mint(&mut self, mint: &Pubkey) {
         self.nft_mint = Some(mint.to_bytes());
     }
+    
+    /// Get the status as of `now`, treating scheduled content whose
+    /// `publish_at` has passed as published
+    pub fn effective_status(&self, now: u64) -> ContentStatus {
+        match self.status {
+            ContentStatus::Scheduled if self.publish_at <= now => ContentStatus::Published,
+            status => status,
+        }
+    }
+    
+    /// Check whether the content is published as of `now`
+    pub fn is_published(&self, now: u64) -> bool {
+        self.effective_status(now) == ContentStatus::Published
+    }
+}
+
+impl AccountState for Content {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:cont";
+    const VERSION: u8 = 6;
+    
+    fn deserialize_version(version: u8, data: &mut &[u8]) -> Result<Self, ProgramError> {
+        // The legacy layout has the same fields as version 1, only without a header
+        if version > Self::VERSION {
+            return Err(BlockTokError::UnsupportedAccountVersion.into());
+        }
+        
+        // Fields added in later versions keep their defaults when reading older layouts
+        let mut content = Content {
+            creator: read_field(data)?,
+            content_id: read_field(data)?,
+            title: read_field(data)?,
+            description: read_field(data)?,
+            content_url: read_field(data)?,
+            content_type: read_field(data)?,
+            created_at: read_field(data)?,
+            nft_mint: read_field(data)?,
+            analytics: ContentAnalytics::deserialize_version(version, data)?,
+            royalty_distribution: read_field(data)?,
+            tags: Vec::new(),
+            status: ContentStatus::Published,
+            publish_at: 0,
+            analytics_nonce: 0,
+        };
+        if version >= 2 {
+            content.tags = read_field(data)?;
+        }
+        if version >= 3 {
+            content.status = read_field(data)?;
+            content.publish_at = read_field(data)?;
+        }
+        if version >= 4 {
+            content.analytics_nonce = read_field(data)?;
+        }
+        Ok(content)
+    }
+    
+    fn unpack_versioned(data: &[u8]) -> Result<(Self, u8), ProgramError> {
+        // Content written before account headers existed starts directly with the creator
+        if data.len() < ACCOUNT_HEADER_SIZE || data[..8] != Self::DISCRIMINATOR {
+            let content = Self::deserialize_version(Self::LEGACY_VERSION, &mut &data[..])?;
+            return Ok((content, Self::LEGACY_VERSION));
+        }
+        let version = data[8];
+        let content = Self::deserialize_version(version, &mut &data[ACCOUNT_HEADER_SIZE..])?;
+        Ok((content, version))
+    }
+}
+
+impl AccountState for CreatorProfile {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:prof";
+    const VERSION: u8 = 1;
+}
+
+impl CreatorProfile {
+    /// Get the size this account will take in storage
+    pub fn size() -> usize {
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
+        PUBKEY_BYTES + // creator
+        8 // content_count
+    }
+    
+    /// Find the profile address for a creator
+    pub fn find_address(program_id: &Pubkey, creator: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[CREATOR_PROFILE_SEED, creator.as_ref()], program_id)
+    }
+    
+    /// Get the public key of the creator
+    pub fn get_creator(&self) -> Pubkey {
+        Pubkey::new_from_array(self.creator)
+    }
+}
+
+impl AccountState for ContentIndex {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:cidx";
+    const VERSION: u8 = 1;
+}
+
+impl ContentIndex {
+    /// Get the size this account will take in storage
+    pub fn size() -> usize {
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
+        PUBKEY_BYTES + // creator
+        8 + // index
+        PUBKEY_BYTES // content
+    }
+    
+    /// Find the address of the pointer at `index` in a creator's catalogue
+    pub fn find_address(program_id: &Pubkey, creator: &Pubkey, index: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[CONTENT_INDEX_SEED, creator.as_ref(), &index.to_le_bytes()],
+            program_id,
+        )
+    }
+    
+    /// Get the public key of the content this index points at
+    pub fn get_content(&self) -> Pubkey {
+        Pubkey::new_from_array(self.content)
+    }
+}
+
+impl AccountState for Category {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:catg";
+    const VERSION: u8 = 1;
+}
+
+impl Category {
+    /// Get the size this account will take in storage
+    pub fn get_account_size(name: &str) -> usize {
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
+        4 + name.len() + // name (length prefix + string)
+        1 + // active
+        8 // content_count
+    }
+    
+    /// Find the category address for a category name. Names longer than a
+    /// seed have no address, and get the default pubkey instead so that
+    /// instructions naming them can still be built and rejected on-chain
+    pub fn find_address(program_id: &Pubkey, name: &str) -> (Pubkey, u8) {
+        Pubkey::try_find_program_address(&[CATEGORY_SEED, name.as_bytes()], program_id)
+            .unwrap_or_default()
+    }
+    
+    /// Check that a category name is non-empty and short enough to be a seed
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && name.len() <= MAX_CATEGORY_NAME_LEN
+    }
+}
+
+impl AccountState for CategoryIndex {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:catx";
+    const VERSION: u8 = 1;
+}
+
+impl CategoryIndex {
+    /// Get the size this account will take in storage
+    pub fn size() -> usize {
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
+        PUBKEY_BYTES + // category
+        PUBKEY_BYTES // content
+    }
+    
+    /// Find the index address marking `content` as tagged with `category`
+    pub fn find_address(program_id: &Pubkey, category: &Pubkey, content: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[CATEGORY_INDEX_SEED, category.as_ref(), content.as_ref()],
+            program_id,
+        )
+    }
+    
+    /// Get the public key of the tagged content
+    pub fn get_content(&self) -> Pubkey {
+        Pubkey::new_from_array(self.content)
+    }
+}
+
+impl AccountState for AnalyticsHistory {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:hist";
+    const VERSION: u8 = 1;
+}
+
+impl AnalyticsHistory {
+    /// Create an empty history for `content`
+    pub fn new(content: &Pubkey, granularity: HistoryGranularity) -> Self {
+        AnalyticsHistory {
+            content: content.to_bytes(),
+            granularity,
+            buckets: vec![AnalyticsBucket::default(); ANALYTICS_HISTORY_BUCKETS],
+        }
+    }
+    
+    /// Get the size this account will take in storage
+    pub fn size() -> usize {
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
+        PUBKEY_BYTES + // content
+        1 + // granularity
+        4 + ANALYTICS_HISTORY_BUCKETS * AnalyticsBucket::size() // buckets (length prefix + buckets)
+    }
+    
+    /// Find the analytics history address for a content account
+    pub fn find_address(program_id: &Pubkey, content: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ANALYTICS_HISTORY_SEED, content.as_ref()], program_id)
+    }
+    
+    /// Add the growth from the `previous` to the `current` counters to the
+    /// bucket containing `now`
+    pub fn record(&mut self, now: u64, previous: &ContentAnalytics, current: &ContentAnalytics) {
+        let bucket_seconds = self.granularity.seconds();
+        let start = now - now % bucket_seconds;
+        let slot = (now / bucket_seconds) as usize % self.buckets.len();
+        
+        let bucket = &mut self.buckets[slot];
+        if bucket.start != start {
+            *bucket = AnalyticsBucket {
+                start,
+                ..AnalyticsBucket::default()
+            };
+        }
+        bucket.views = bucket.views.saturating_add(current.views.saturating_sub(previous.views));
+        bucket.likes = bucket.likes.saturating_add(current.likes.saturating_sub(previous.likes));
+        bucket.shares = bucket.shares.saturating_add(current.shares.saturating_sub(previous.shares));
+        bucket.comments = bucket
+            .comments
+            .saturating_add(current.comments.saturating_sub(previous.comments));
+    }
+    
+    /// Get the recorded buckets as a time series, oldest first. Buckets without
+    /// any updates are left out
+    pub fn time_series(&self) -> Vec<AnalyticsBucket> {
+        let mut buckets: Vec<AnalyticsBucket> = self
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.start != 0)
+            .copied()
+            .collect();
+        buckets.sort_by_key(|bucket| bucket.start);
+        buckets
+    }
+}
+
+impl AccountState for ViewReceipt {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:view";
+    const VERSION: u8 = 1;
+}
+
+impl ViewReceipt {
+    /// Get the size this account will take in storage
+    pub fn size() -> usize {
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
+        PUBKEY_BYTES + // content
+        PUBKEY_BYTES + // viewer
+        8 + // first_seen_at
+        2 // watch_progress_bps
+    }
+    
+    /// Find the receipt address of `viewer` for a content account
+    pub fn find_address(program_id: &Pubkey, content: &Pubkey, viewer: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[VIEW_RECEIPT_SEED, content.as_ref(), viewer.as_ref()],
+            program_id,
+        )
+    }
+    
+    /// Get the public key of the viewer
+    pub fn get_viewer(&self) -> Pubkey {
+        Pubkey::new_from_array(self.viewer)
+    }
+}
+
+impl AccountState for EngagementEpoch {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:epch";
+    const VERSION: u8 = 1;
+}
+
+impl EngagementEpoch {
+    /// Get the size this account will take in storage
+    pub fn size() -> usize {
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
+        PUBKEY_BYTES + // authority
+        8 + // epoch
+        8 + // start_time
+        8 + // end_time
+        32 + // merkle_root
+        8 + // event_count
+        8 // committed_at
+    }
+    
+    /// Find the address of engagement epoch number `epoch`
+    pub fn find_address(program_id: &Pubkey, epoch: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ENGAGEMENT_EPOCH_SEED, &epoch.to_le_bytes()], program_id)
+    }
+    
+    /// Get the public key of the committing oracle
+    pub fn get_authority(&self) -> Pubkey {
+        Pubkey::new_from_array(self.authority)
+    }
+    
+    /// Check whether `timestamp` falls in the epoch's window
+    pub fn contains(&self, timestamp: u64) -> bool {
+        self.start_time <= timestamp && timestamp < self.end_time
+    }
+}
+
+impl AccountState for EpochContentTotals {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:etot";
+    const VERSION: u8 = 1;
+}
+
+impl EpochContentTotals {
+    /// Get the size this account will take in storage
+    pub fn size() -> usize {
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
+        PUBKEY_BYTES + // epoch
+        PUBKEY_BYTES + // content
+        8 + // views
+        8 + // likes
+        8 + // shares
+        8 // comments
+    }
+    
+    /// Find the address of a content's totals for an engagement epoch account
+    pub fn find_address(program_id: &Pubkey, epoch: &Pubkey, content: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[EPOCH_TOTALS_SEED, epoch.as_ref(), content.as_ref()],
+            program_id,
+        )
+    }
+}
+
+impl AccountState for UserRewards {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:urwd";
+    const VERSION: u8 = 1;
+}
+
+impl UserRewards {
+    /// Get the size this account will take in storage
+    pub fn size() -> usize {
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
+        PUBKEY_BYTES + // user
+        8 + // accrued
+        8 + // claimed
+        8 // last_accrued_at
+    }
+    
+    /// Find the rewards address of a user
+    pub fn find_address(program_id: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[USER_REWARDS_SEED, user.as_ref()], program_id)
+    }
+    
+    /// Get the public key of the user
+    pub fn get_user(&self) -> Pubkey {
+        Pubkey::new_from_array(self.user)
+    }
+    
+    /// Get the credits accrued but not yet claimed
+    pub fn claimable(&self) -> u64 {
+        self.accrued - self.claimed
+    }
+    
+    /// Accrue `amount` credits at `now`
+    pub fn accrue(&mut self, amount: u64, now: u64) -> Result<(), BlockTokError> {
+        self.accrued = self.accrued.checked_add(amount).ok_or(BlockTokError::RewardOverflow)?;
+        self.last_accrued_at = now;
+        Ok(())
+    }
+}
+
+impl AccountState for RewardDistributor {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:dist";
+    const VERSION: u8 = 1;
+}
+
+impl RewardDistributor {
+    /// Get the size this account will take in storage
+    pub fn get_account_size(num_recipients: u32) -> usize {
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
+        PUBKEY_BYTES + // authority
+        8 + // epoch
+        32 + // merkle_root
+        8 + // total_amount
+        8 + // claimed_amount
+        4 + // num_recipients
+        8 + // expires_at
+        1 + // clawed_back
+        4 + Self::bitmap_len(num_recipients) // claimed_bitmap (length prefix + bits)
+    }
+    
+    /// Get the number of bytes of a claim bitmap with a bit per recipient
+    pub fn bitmap_len(num_recipients: u32) -> usize {
+        (num_recipients as usize + 7) / 8
+    }
+    
+    /// Find the address of the distributor of epoch number `epoch`
+    pub fn find_address(program_id: &Pubkey, epoch: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REWARD_DISTRIBUTOR_SEED, &epoch.to_le_bytes()], program_id)
+    }
+    
+    /// Find the address of the token vault of a distributor
+    pub fn find_vault_address(program_id: &Pubkey, distributor: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[DISTRIBUTOR_VAULT_SEED, distributor.as_ref()], program_id)
+    }
+    
+    /// Get the public key of the authority
+    pub fn get_authority(&self) -> Pubkey {
+        Pubkey::new_from_array(self.authority)
+    }
+    
+    /// Check whether entry `index` was claimed
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed_bitmap[index as usize / 8] & (1 << (index % 8)) != 0
+    }
+    
+    /// Mark entry `index` claimed
+    pub fn set_claimed(&mut self, index: u32) {
+        self.claimed_bitmap[index as usize / 8] |= 1 << (index % 8);
+    }
+    
+    /// Check whether claims are refused as of `now`
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+}
+
+impl DistributionEntry {
+    /// Get the Merkle leaf of the entry
+    pub fn leaf(&self) -> [u8; 32] {
+        merkle::hash_leaf(&self.try_to_vec().unwrap())
+    }
+}
+
+impl EngagementEvent {
+    /// Get the Merkle leaf of the event
+    pub fn leaf(&self) -> [u8; 32] {
+        merkle::hash_leaf(&self.try_to_vec().unwrap())
+    }
+}
+
+impl HistoryGranularity {
+    /// Get the length of a bucket in seconds
+    pub fn seconds(self) -> u64 {
+        match self {
+            HistoryGranularity::Hourly => 60 * 60,
+            HistoryGranularity::Daily => 24 * 60 * 60,
+        }
+    }
+}
+
+impl AnalyticsBucket {
+    /// Get the size of an AnalyticsBucket in storage
+    pub fn size() -> usize {
+        8 + // start
+        8 + // views
+        8 + // likes
+        8 + // shares
+        8   // comments
+    }
+}
+
+impl AccountState for PlatformConfig {
+    const DISCRIMINATOR: [u8; 8] = *b"btk:conf";
+    const VERSION: u8 = 7;
+    
+    fn deserialize_version(version: u8, data: &mut &[u8]) -> Result<Self, ProgramError> {
+        if version == 0 || version > Self::VERSION {
+            return Err(BlockTokError::UnsupportedAccountVersion.into());
+        }
+        
+        // Fields added in later versions keep their defaults when reading older layouts
+        let mut config = PlatformConfig {
+            admin: read_field(data)?,
+            pending_admin: read_field(data)?,
+            treasury: read_field(data)?,
+            platform_fee_bps: read_field(data)?,
+            features: read_field(data)?,
+            paused: false,
+            paused_instructions: 0,
+            analytics_oracles: Vec::new(),
+            allow_creator_analytics: true,
+            min_analytics_interval: 0,
+            engagement_weights: EngagementWeights::default(),
+            view_reward: 0,
+            emission_schedule: EmissionSchedule::default(),
+            emitted_total: 0,
+            emission_epoch: 0,
+            emitted_in_epoch: 0,
+        };
+        if version >= 2 {
+            config.paused = read_field(data)?;
+            config.paused_instructions = read_field(data)?;
+        }
+        if version >= 3 {
+            config.analytics_oracles = read_field(data)?;
+            config.allow_creator_analytics = read_field(data)?;
+        }
+        if version >= 4 {
+            config.min_analytics_interval = read_field(data)?;
+        }
+        if version >= 5 {
+            config.engagement_weights = read_field(data)?;
+        }
+        if version >= 6 {
+            config.view_reward = read_field(data)?;
+        }
+        if version >= 7 {
+            config.emission_schedule = read_field(data)?;
+            config.emitted_total = read_field(data)?;
+            config.emission_epoch = read_field(data)?;
+            config.emitted_in_epoch = read_field(data)?;
+        }
+        Ok(config)
+    }
+}
+
+impl PlatformConfig {
+    /// Get the size this account will take in storage
+    pub fn get_account_size(num_analytics_oracles: usize) -> usize {
+        ACCOUNT_HEADER_SIZE + // Discriminator and version
+        PUBKEY_BYTES + // admin
+        1 + PUBKEY_BYTES + // pending_admin (1 byte for Option variant + pubkey)
+        PUBKEY_BYTES + // treasury
+        2 + // platform_fee_bps
+        8 + // features
+        1 + // paused
+        8 + // paused_instructions
+        4 + num_analytics_oracles * PUBKEY_BYTES + // analytics_oracles (length prefix + pubkeys)
+        1 + // allow_creator_analytics
+        8 + // min_analytics_interval
+        EngagementWeights::size() + // engagement_weights
+        8 + // view_reward
+        EmissionSchedule::size() + // emission_schedule
+        8 + // emitted_total
+        8 + // emission_epoch
+        8 // emitted_in_epoch
+    }
+    
+    /// Get the size needed to store this config as it currently is
+    pub fn account_size(&self) -> usize {
+        Self::get_account_size(self.analytics_oracles.len())
+    }
+    
+    /// Find the platform config address
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[PLATFORM_CONFIG_SEED], program_id)
+    }
+    
+    /// Get the public key of the admin
+    pub fn get_admin(&self) -> Pubkey {
+        Pubkey::new_from_array(self.admin)
+    }
+    
+    /// Get the public key of the pending admin, if a transfer is in progress
+    pub fn get_pending_admin(&self) -> Option<Pubkey> {
+        self.pending_admin.map(Pubkey::new_from_array)
+    }
+    
+    /// Get the public key of the treasury
+    pub fn get_treasury(&self) -> Pubkey {
+        Pubkey::new_from_array(self.treasury)
+    }
+    
+    /// Check whether all bits of `feature` are enabled
+    pub fn has_feature(&self, feature: u64) -> bool {
+        self.features & feature == feature
+    }
+    
+    /// Check whether the instruction identified by `pause_flag` is paused
+    pub fn is_paused(&self, pause_flag: u64) -> bool {
+        self.paused || self.paused_instructions & pause_flag != 0
+    }
+    
+    /// Check whether `authority` is a registered analytics oracle
+    pub fn is_analytics_oracle(&self, authority: &Pubkey) -> bool {
+        self.analytics_oracles.contains(&authority.to_bytes())
+    }
+    
+    /// Check whether `authority` may report analytics for content owned by `creator`
+    pub fn can_report_analytics(&self, authority: &Pubkey, creator: &Pubkey) -> bool {
+        self.is_analytics_oracle(authority) || (self.allow_creator_analytics && authority == creator)
+    }
+    
+    /// Count `amount` reward tokens minted at `now` against the emission
+    /// schedule, failing if it exceeds the current epoch's allowance or the
+    /// total cap
+    pub fn record_emission(&mut self, amount: u64, now: u64) -> Result<(), BlockTokError> {
+        let epoch = self
+            .emission_schedule
+            .epoch_at(now)
+            .ok_or(BlockTokError::EmissionLimitExceeded)?;
+        if epoch != self.emission_epoch {
+            self.emission_epoch = epoch;
+            self.emitted_in_epoch = 0;
+        }
+        
+        self.emitted_in_epoch = self
+            .emitted_in_epoch
+            .checked_add(amount)
+            .filter(|emitted| *emitted <= self.emission_schedule.epoch_allowance(epoch))
+            .ok_or(BlockTokError::EmissionLimitExceeded)?;
+        self.emitted_total = self
+            .emitted_total
+            .checked_add(amount)
+            .filter(|emitted| *emitted <= self.emission_schedule.total_cap)
+            .ok_or(BlockTokError::EmissionLimitExceeded)?;
+        Ok(())
+    }
+}
+
+impl AnalyticsAttestation {
+    /// Prefix of every attestation message, so oracle signatures over other
+    /// data can never be mistaken for an attestation
+    pub const MESSAGE_PREFIX: &'static [u8] = b"blocktok:analytics-attestation";
+    
+    /// Get the message the analytics oracle signs
+    pub fn message(&self) -> Vec<u8> {
+        let mut message = Self::MESSAGE_PREFIX.to_vec();
+        message.extend_from_slice(&self.try_to_vec().unwrap());
+        message
+    }
 }
 
 impl ContentAnalytics {
@@ -117,17 +1440,195 @@ impl ContentAnalytics {
         8 + // likes
         8 + // shares
         8 + // comments
-        8   // updated_at
+        8 + // updated_at
+        WatchMetrics::size() + // watch
+        8 // engagement_score
+    }
+    
+    /// Read analytics embedded in content at layout `version`. Watch metrics
+    /// were added in content version 5 and the engagement score in version 6
+    fn deserialize_version(version: u8, data: &mut &[u8]) -> Result<Self, ProgramError> {
+        let mut analytics = ContentAnalytics {
+            views: read_field(data)?,
+            likes: read_field(data)?,
+            shares: read_field(data)?,
+            comments: read_field(data)?,
+            updated_at: read_field(data)?,
+            watch: WatchMetrics::default(),
+            engagement_score: 0,
+        };
+        if version >= 5 {
+            analytics.watch = read_field(data)?;
+        }
+        if version >= 6 {
+            analytics.engagement_score = read_field(data)?;
+        }
+        Ok(analytics)
+    }
+    
+    /// Add deltas to the counters, leaving them unchanged if any would overflow
+    pub fn increment(
+        &mut self,
+        views: u64,
+        likes: u64,
+        shares: u64,
+        comments: u64,
+        watch: &WatchMetrics,
+    ) -> Result<(), BlockTokError> {
+        let views = self.views.checked_add(views).ok_or(BlockTokError::AnalyticsOverflow)?;
+        let likes = self.likes.checked_add(likes).ok_or(BlockTokError::AnalyticsOverflow)?;
+        let shares = self.shares.checked_add(shares).ok_or(BlockTokError::AnalyticsOverflow)?;
+        let comments = self.comments.checked_add(comments).ok_or(BlockTokError::AnalyticsOverflow)?;
+        let watch = self.watch.checked_add(watch).ok_or(BlockTokError::AnalyticsOverflow)?;
+        
+        self.views = views;
+        self.likes = likes;
+        self.shares = shares;
+        self.comments = comments;
+        self.watch = watch;
+        Ok(())
+    }
+    
+    /// Set the counters, leaving them unchanged if any would decrease
+    pub fn set(
+        &mut self,
+        views: u64,
+        likes: u64,
+        shares: u64,
+        comments: u64,
+        watch: &WatchMetrics,
+    ) -> Result<(), BlockTokError> {
+        if views < self.views
+            || likes < self.likes
+            || shares < self.shares
+            || comments < self.comments
+            || !watch.covers(&self.watch)
+        {
+            return Err(BlockTokError::AnalyticsDecreased);
+        }
+        
+        self.views = views;
+        self.likes = likes;
+        self.shares = shares;
+        self.comments = comments;
+        self.watch = *watch;
+        Ok(())
+    }
+    
+    /// Get the average completion rate of a view, in basis points
+    pub fn average_completion_bps(&self) -> u64 {
+        self.watch.total_completion_bps.checked_div(self.views).unwrap_or(0)
     }
 }
 
-impl RoyaltyDistribution {
-    /// Get the size of RoyaltyDistribution in storage
+impl EngagementWeights {
+    /// Get the size of EngagementWeights in storage
+    pub fn size() -> usize {
+        8 + // views
+        8 + // likes
+        8 + // shares
+        8 + // comments
+        8 + // watch_seconds
+        8 // decay_half_life
+    }
+    
+    /// Get the engagement score of `analytics` for content created at
+    /// `created_at`, as of `now`.
+    ///
+    /// The weighted sum saturates at `u64::MAX`. Decay halves the score once
+    /// per whole half-life and interpolates linearly within the current one.
+    pub fn score(&self, analytics: &ContentAnalytics, created_at: u64, now: u64) -> u64 {
+        let weighted = analytics.views as u128 * self.views as u128
+            + analytics.likes as u128 * self.likes as u128
+            + analytics.shares as u128 * self.shares as u128
+            + analytics.comments as u128 * self.comments as u128
+            + analytics.watch.watch_seconds as u128 * self.watch_seconds as u128;
+        let score = weighted.min(u64::MAX as u128);
+        
+        if self.decay_half_life == 0 {
+            return score as u64;
+        }
+        let half_life = self.decay_half_life as u128;
+        let age = now.saturating_sub(created_at) as u128;
+        let halvings = age / half_life;
+        if halvings >= 64 {
+            return 0;
+        }
+        let halved = score >> halvings;
+        (halved - halved * (age % half_life) / (2 * half_life)) as u64
+    }
+}
+
+impl EmissionSchedule {
+    /// Get the size of EmissionSchedule in storage
+    pub fn size() -> usize {
+        8 + // start_time
+        8 + // epoch_duration
+        8 + // tokens_per_epoch
+        8 + // halving_interval
+        8 // total_cap
+    }
+    
+    /// Get the epoch `now` falls in, or `None` before the first epoch or
+    /// when the schedule mints nothing
+    pub fn epoch_at(&self, now: u64) -> Option<u64> {
+        if self.epoch_duration == 0 || now < self.start_time {
+            return None;
+        }
+        Some((now - self.start_time) / self.epoch_duration)
+    }
+    
+    /// Get the tokens epoch `epoch` may mint, after halving
+    pub fn epoch_allowance(&self, epoch: u64) -> u64 {
+        if self.halving_interval == 0 {
+            return self.tokens_per_epoch;
+        }
+        let halvings = epoch / self.halving_interval;
+        if halvings >= 64 {
+            return 0;
+        }
+        self.tokens_per_epoch >> halvings
+    }
+}
+
+impl WatchMetrics {
+    /// Get the size of WatchMetrics in storage
+    pub fn size() -> usize {
+        8 + // watch_seconds
+        8 + // total_completion_bps
+        8 // replays
+    }
+    
+    /// Add `other` to each metric, or `None` if any would overflow
+    pub fn checked_add(&self, other: &WatchMetrics) -> Option<WatchMetrics> {
+        Some(WatchMetrics {
+            watch_seconds: self.watch_seconds.checked_add(other.watch_seconds)?,
+            total_completion_bps: self.total_completion_bps.checked_add(other.total_completion_bps)?,
+            replays: self.replays.checked_add(other.replays)?,
+        })
+    }
+    
+    /// Check whether every metric is at least the one in `other`
+    pub fn covers(&self, other: &WatchMetrics) -> bool {
+        self.watch_seconds >= other.watch_seconds
+            && self.total_completion_bps >= other.total_completion_bps
+            && self.replays >= other.replays
+    }
+}
+
+impl RoyaltyRecipient {
+    /// Get the size of a RoyaltyRecipient in storage
     pub fn size() -> usize {
-        // Base size for vector
+        PUBKEY_BYTES + // pubkey
+        2 // percentage
+    }
+}
+
+impl RoyaltyDistribution {
+    /// Get the size of a RoyaltyDistribution with `num_recipients` recipients in storage
+    pub fn size(num_recipients: usize) -> usize {
         4 + // length prefix
-        // Each recipient takes PUBKEY_BYTES + 2 bytes
-        0 // initialized with no recipients
+        num_recipients * RoyaltyRecipient::size() // recipients
     }
     
     /// Get a recipient's public key
@@ -150,4 +1651,49 @@ impl RoyaltyDistribution {
             percentage,
         });
     }
+    
+    /// Get the accounts earnings of content created by `creator` are paid to:
+    /// the recipients in order, or the creator alone when there are none
+    pub fn payees(&self, creator: &Pubkey) -> Vec<Pubkey> {
+        if self.recipients.is_empty() {
+            return vec![*creator];
+        }
+        self.recipients
+            .iter()
+            .map(|recipient| Pubkey::new_from_array(recipient.pubkey))
+            .collect()
+    }
+    
+    /// Split `amount` between the payees by their basis points, in the order
+    /// of `payees`.
+    ///
+    /// Each share is rounded down, then the units left over go one each to
+    /// the recipients with the largest remainders, earlier recipients first
+    /// on ties, so the shares always add up to `amount`. That only holds
+    /// when the basis points add up to 100%, so any other distribution is
+    /// rejected.
+    pub fn split(&self, amount: u64) -> Result<Vec<u64>, BlockTokError> {
+        if self.recipients.is_empty() {
+            return Ok(vec![amount]);
+        }
+        if !self.validate_percentages() {
+            return Err(BlockTokError::InvalidRoyaltyPercentage);
+        }
+        
+        let mut shares = Vec::with_capacity(self.recipients.len());
+        let mut remainders = Vec::with_capacity(self.recipients.len());
+        for recipient in &self.recipients {
+            let exact = amount as u128 * recipient.percentage as u128;
+            shares.push((exact / 10000) as u64);
+            remainders.push(exact % 10000);
+        }
+        
+        let mut order: Vec<usize> = (0..shares.len()).collect();
+        order.sort_by(|a, b| remainders[*b].cmp(&remainders[*a]).then(a.cmp(b)));
+        let leftover = amount.saturating_sub(shares.iter().sum());
+        for index in order.iter().cycle().take(leftover as usize) {
+            shares[*index] += 1;
+        }
+        Ok(shares)
+    }
 } 
\ No newline at end of file
@@ -1,15 +1,23 @@
-use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::{pubkey::Pubkey, system_program};
+use borsh::BorshSerialize;
+use solana_program::{
+    clock::Clock, instruction::InstructionError, program_option::COption, program_pack::Pack,
+    pubkey::Pubkey, system_program,
+};
 use solana_program_test::*;
 use solana_sdk::{
-    account::Account,
+    account::{Account, AccountSharedData},
     signature::{Keypair, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
+use spl_token::state::Mint;
 
 use blocktok::{
-    instruction::BlockTokInstruction,
-    state::{Content, ContentAnalytics},
+    error::BlockTokError,
+    instruction::{BlockTokInstruction, ContentAnalyticsUpdate},
+    state::{
+        AccountState, AnalyticsHistory, Content, ContentAnalytics, ContentIndex, ContentStatus,
+        CreatorProfile, HistoryGranularity, ViewReceipt, WatchMetrics, MAX_BATCH_ANALYTICS_UPDATES,
+    },
 };
 
 #[tokio::test]
@@ -29,7 +37,6 @@ async fn test_initialize_content() {
     let description = String::from("Test Description");
     let content_url = String::from("https://example.com/test-content");
     let content_type = String::from("video");
-    let created_at = 1634567890;
 
     // Add creator account with initial balance
     program_test.add_account(
@@ -46,14 +53,7 @@ async fn test_initialize_content() {
     let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
     // Create content account address
-    let (content_pubkey, _) = Pubkey::find_program_address(
-        &[
-            b"content",
-            creator.pubkey().as_ref(),
-            content_id.as_bytes(),
-        ],
-        &program_id,
-    );
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
 
     // Create initialize content instruction
     let instruction = BlockTokInstruction::initialize_content(
@@ -64,7 +64,8 @@ async fn test_initialize_content() {
         description.clone(),
         content_url.clone(),
         content_type.clone(),
-        created_at,
+        0,
+        vec![],
     );
 
     // Create and send transaction
@@ -82,19 +83,21 @@ async fn test_initialize_content() {
         .unwrap();
 
     // Deserialize content data
-    let content = Content::try_from_slice(&content_account.data).unwrap();
+    let content = Content::unpack(&content_account.data).unwrap();
+    let clock = banks_client.get_sysvar::<Clock>().await.unwrap();
 
-    // Verify content data
+    // Verify content data, stamped with the cluster time
     assert_eq!(content.content_id, content_id);
     assert_eq!(content.title, title);
     assert_eq!(content.description, description);
     assert_eq!(content.content_url, content_url);
     assert_eq!(content.content_type, content_type);
-    assert_eq!(content.created_at, created_at);
+    assert_eq!(content.created_at, clock.unix_timestamp as u64);
     assert_eq!(content.get_creator(), creator.pubkey());
     assert_eq!(content.nft_mint, None);
     assert_eq!(content.analytics, ContentAnalytics::default());
     assert_eq!(content.royalty_distribution.recipients.len(), 0);
+    assert_eq!(content.status, ContentStatus::Draft);
 }
 
 #[tokio::test]
@@ -112,17 +115,10 @@ async fn test_update_analytics() {
     let content_id = String::from("test-content-id");
     
     // Create content account address
-    let (content_pubkey, _) = Pubkey::find_program_address(
-        &[
-            b"content",
-            creator.pubkey().as_ref(),
-            content_id.as_bytes(),
-        ],
-        &program_id,
-    );
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
 
     // Initialize content (would be done separately in a real test)
-    let mut content = Content {
+    let content = Content {
         creator: creator.pubkey().to_bytes(),
         content_id: content_id.clone(),
         title: String::from("Test Content"),
@@ -133,6 +129,10 @@ async fn test_update_analytics() {
         nft_mint: None,
         analytics: ContentAnalytics::default(),
         royalty_distribution: blocktok::state::RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
     };
 
     // Add creator account with initial balance
@@ -148,7 +148,7 @@ async fn test_update_analytics() {
 
     // Add pre-initialized content account
     let mut content_data = vec![0; 1000]; // Sufficient size for the data
-    content.serialize(&mut content_data.as_mut_slice()).unwrap();
+    content.pack(&mut content_data).unwrap();
     program_test.add_account(
         content_pubkey,
         Account {
@@ -167,18 +167,24 @@ async fn test_update_analytics() {
     let likes = 500;
     let shares = 200;
     let comments = 100;
-    let updated_at = 1634567900;
+    let watch = WatchMetrics {
+        watch_seconds: 12_000,
+        total_completion_bps: 6_500_000,
+        replays: 150,
+    };
 
     // Create update analytics instruction
     let instruction = BlockTokInstruction::update_analytics(
         &program_id,
         &creator.pubkey(),
+        &creator.pubkey(),
         &content_id,
         views,
         likes,
         shares,
         comments,
-        updated_at,
+        watch,
+        None,
     );
 
     // Create and send transaction
@@ -196,12 +202,1227 @@ async fn test_update_analytics() {
         .unwrap();
 
     // Deserialize content data
-    let updated_content = Content::try_from_slice(&content_account.data).unwrap();
+    let updated_content = Content::unpack(&content_account.data).unwrap();
+    let clock = banks_client.get_sysvar::<Clock>().await.unwrap();
 
-    // Verify analytics data was updated
+    // Verify analytics data was updated at the cluster time
     assert_eq!(updated_content.analytics.views, views);
     assert_eq!(updated_content.analytics.likes, likes);
     assert_eq!(updated_content.analytics.shares, shares);
     assert_eq!(updated_content.analytics.comments, comments);
-    assert_eq!(updated_content.analytics.updated_at, updated_at);
-} 
\ No newline at end of file
+    assert_eq!(updated_content.analytics.watch, watch);
+    assert_eq!(updated_content.analytics.average_completion_bps(), 6500);
+    assert_eq!(updated_content.analytics.updated_at, clock.unix_timestamp as u64);
+} 
+#[tokio::test]
+async fn test_update_content_metadata() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test data
+    let creator = Keypair::new();
+    let content_id = String::from("test-content-id");
+
+    // Add creator account with initial balance
+    program_test.add_account(
+        creator.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Create content account address
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+
+    // Initialize content, then replace its title and URL with longer values
+    let new_title = String::from("A Much Longer Title Than Before");
+    let new_content_url = String::from("https://cdn.example.com/videos/test-content.mp4");
+    let instructions = [
+        BlockTokInstruction::initialize_content(
+            &program_id,
+            &creator.pubkey(),
+            content_id.clone(),
+            String::from("Test Content"),
+            String::from("Test Description"),
+            String::from("https://example.com/test-content"),
+            String::from("video"),
+            0,
+            vec![],
+        ),
+        BlockTokInstruction::update_content_metadata(
+            &program_id,
+            &creator.pubkey(),
+            &content_id,
+            Some(new_title.clone()),
+            None,
+            Some(new_content_url.clone()),
+            None,
+            None,
+            &[],
+        ),
+    ];
+
+    // Create and send transaction
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+
+    // Process transaction
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Get updated content account data
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Deserialize content data
+    let updated_content = Content::unpack(&content_account.data).unwrap();
+
+    // Verify only the requested fields changed and the account was resized
+    assert_eq!(updated_content.title, new_title);
+    assert_eq!(updated_content.description, "Test Description");
+    assert_eq!(updated_content.content_url, new_content_url);
+    assert_eq!(updated_content.content_type, "video");
+    assert_eq!(content_account.data.len(), updated_content.account_size());
+}
+
+#[tokio::test]
+async fn test_initialize_content_with_long_id() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // A hex SHA-256 content ID is longer than the 32 byte seed limit
+    let creator = Keypair::new();
+    let content_id =
+        String::from("9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08");
+
+    // Add creator account with initial balance
+    program_test.add_account(
+        creator.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Create initialize content instruction
+    let instruction = BlockTokInstruction::initialize_content(
+        &program_id,
+        &creator.pubkey(),
+        content_id.clone(),
+        String::from("Test Content"),
+        String::from("Test Description"),
+        String::from("https://example.com/test-content"),
+        String::from("video"),
+        0,
+        vec![],
+    );
+
+    // Create and send transaction
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+
+    // Process transaction
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Content lives at the hashed address; the legacy derivation does not exist
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let content = Content::unpack(&content_account.data).unwrap();
+    assert_eq!(content.content_id, content_id);
+    assert!(Content::find_legacy_address(&program_id, &creator.pubkey(), &content_id).is_none());
+}
+
+#[tokio::test]
+async fn test_update_analytics_legacy_address() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test data
+    let creator = Keypair::new();
+    let content_id = String::from("test-content-id");
+
+    // Content created before content IDs were hashed
+    let (legacy_pubkey, _) =
+        Content::find_legacy_address(&program_id, &creator.pubkey(), &content_id).unwrap();
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics::default(),
+        royalty_distribution: blocktok::state::RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add creator account with initial balance
+    program_test.add_account(
+        creator.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add pre-initialized content account at the legacy address
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        legacy_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Point the instruction at the legacy account and its history
+    let mut instruction = BlockTokInstruction::update_analytics(
+        &program_id,
+        &creator.pubkey(),
+        &creator.pubkey(),
+        &content_id,
+        10,
+        5,
+        2,
+        1,
+        WatchMetrics::default(),
+        None,
+    );
+    instruction.accounts[1].pubkey = legacy_pubkey;
+    instruction.accounts[3].pubkey = AnalyticsHistory::find_address(&program_id, &legacy_pubkey).0;
+
+    // Create and send transaction
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+
+    // Process transaction
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify analytics were written to the legacy account
+    let content_account = banks_client
+        .get_account(legacy_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let updated_content = Content::unpack(&content_account.data).unwrap();
+    assert_eq!(updated_content.analytics.views, 10);
+}
+
+#[tokio::test]
+async fn test_initialize_content_legacy_address_taken() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test data
+    let creator = Keypair::new();
+    let content_id = String::from("test-content-id");
+
+    // Content created under the same ID before content IDs were hashed
+    let (legacy_pubkey, _) =
+        Content::find_legacy_address(&program_id, &creator.pubkey(), &content_id).unwrap();
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics::default(),
+        royalty_distribution: blocktok::state::RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add creator account with initial balance
+    program_test.add_account(
+        creator.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add pre-initialized content account at the legacy address
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        legacy_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Creating the content again at the hashed address is rejected
+    let instruction = BlockTokInstruction::initialize_content(
+        &program_id,
+        &creator.pubkey(),
+        content_id.clone(),
+        String::from("Test Content"),
+        String::from("Test Description"),
+        String::from("https://example.com/test-content"),
+        String::from("video"),
+        0,
+        vec![],
+    );
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    let err = banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BlockTokError::ContentAlreadyExists as u32)
+        )
+    );
+
+    // No content was created at the hashed address
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    assert!(banks_client.get_account(content_pubkey).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_migrate_content() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test data
+    let creator = Keypair::new();
+    let content_id = String::from("test-content-id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics::default(),
+        royalty_distribution: blocktok::state::RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add creator account with initial balance
+    program_test.add_account(
+        creator.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add content written without a header, as before discriminators existed.
+    // The legacy layout ends at the royalty distribution, so drop the fields
+    // added since: the empty tag list's length prefix, status, publish_at and
+    // analytics_nonce, then the watch metrics and engagement score ending the
+    // analytics before the empty royalty distribution
+    let mut legacy_data = content.try_to_vec().unwrap();
+    legacy_data.truncate(legacy_data.len() - (4 + 1 + 8 + 8));
+    let analytics_end = legacy_data.len() - 4;
+    legacy_data.drain(analytics_end - (WatchMetrics::size() + 8)..analytics_end);
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: legacy_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Create and send transaction
+    let instruction = BlockTokInstruction::migrate_content(&program_id, &creator.pubkey(), &content_id);
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+
+    // Process transaction
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify the account now carries a current header
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let (migrated_content, version) = Content::unpack_versioned(&content_account.data).unwrap();
+    assert_eq!(version, Content::VERSION);
+    assert_eq!(migrated_content.content_id, content_id);
+    assert_eq!(content_account.data.len(), migrated_content.account_size());
+}
+
+#[tokio::test]
+async fn test_creator_catalogue() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test data
+    let creator = Keypair::new();
+    let content_ids = [String::from("first-content"), String::from("second-content")];
+
+    // Add creator account with initial balance
+    program_test.add_account(
+        creator.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Someone sends lamports to the second content and its catalogue index
+    // ahead of time, which must not block the creator
+    let (second_content_pubkey, _) =
+        Content::find_address(&program_id, &creator.pubkey(), &content_ids[1]);
+    let (second_index_pubkey, _) = ContentIndex::find_address(&program_id, &creator.pubkey(), 1);
+    for pubkey in [second_content_pubkey, second_index_pubkey] {
+        program_test.add_account(
+            pubkey,
+            Account {
+                lamports: 1_000,
+                data: vec![],
+                owner: system_program::id(),
+                ..Account::default()
+            },
+        );
+    }
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Initialize two contents at consecutive catalogue indexes
+    let instructions: Vec<_> = content_ids
+        .iter()
+        .enumerate()
+        .map(|(index, content_id)| {
+            BlockTokInstruction::initialize_content(
+                &program_id,
+                &creator.pubkey(),
+                content_id.clone(),
+                String::from("Test Content"),
+                String::from("Test Description"),
+                String::from("https://example.com/test-content"),
+                String::from("video"),
+                index as u64,
+                vec![],
+            )
+        })
+        .collect();
+
+    // Create and send transaction
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+
+    // Process transaction
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify the profile counted both contents
+    let (creator_profile_pubkey, _) = CreatorProfile::find_address(&program_id, &creator.pubkey());
+    let creator_profile_account = banks_client
+        .get_account(creator_profile_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let creator_profile = CreatorProfile::unpack(&creator_profile_account.data).unwrap();
+    assert_eq!(creator_profile.content_count, 2);
+
+    // Verify each index points at its content
+    for (index, content_id) in content_ids.iter().enumerate() {
+        let (content_index_pubkey, _) =
+            ContentIndex::find_address(&program_id, &creator.pubkey(), index as u64);
+        let content_index_account = banks_client
+            .get_account(content_index_pubkey)
+            .await
+            .unwrap()
+            .unwrap();
+        let content_index = ContentIndex::unpack(&content_index_account.data).unwrap();
+        let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), content_id);
+        assert_eq!(content_index.index, index as u64);
+        assert_eq!(content_index.get_content(), content_pubkey);
+    }
+}
+
+#[tokio::test]
+async fn test_content_status_transitions() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test data
+    let creator = Keypair::new();
+    let content_id = String::from("test-content-id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+
+    // Add creator account with initial balance
+    program_test.add_account(
+        creator.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let update_analytics = |views| {
+        BlockTokInstruction::update_analytics(
+            &program_id,
+            &creator.pubkey(),
+            &creator.pubkey(),
+            &content_id,
+            views,
+            0,
+            0,
+            0,
+            WatchMetrics::default(),
+            None,
+        )
+    };
+
+    // Initialize content as a draft
+    let instruction = BlockTokInstruction::initialize_content(
+        &program_id,
+        &creator.pubkey(),
+        content_id.clone(),
+        String::from("Test Content"),
+        String::from("Test Description"),
+        String::from("https://example.com/test-content"),
+        String::from("video"),
+        0,
+        vec![],
+    );
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Drafts do not collect analytics
+    let mut transaction = Transaction::new_with_payer(&[update_analytics(1)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    // Publish, then record analytics
+    let instructions = [
+        BlockTokInstruction::publish(&program_id, &creator.pubkey(), &content_id, None),
+        update_analytics(2),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Archive; archived content neither collects analytics nor can be republished
+    let instruction = BlockTokInstruction::archive(&program_id, &creator.pubkey(), &content_id);
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let mut transaction = Transaction::new_with_payer(&[update_analytics(3)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    let instruction = BlockTokInstruction::publish(&program_id, &creator.pubkey(), &content_id, None);
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    // Verify content data
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let content = Content::unpack(&content_account.data).unwrap();
+    assert_eq!(content.status, ContentStatus::Archived);
+    assert_eq!(content.analytics.views, 2);
+}
+
+#[tokio::test]
+async fn test_increment_analytics() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test data
+    let creator = Keypair::new();
+    let content_id = String::from("test-content-id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics {
+            views: 1000,
+            likes: u64::MAX - 1,
+            ..ContentAnalytics::default()
+        },
+        royalty_distribution: blocktok::state::RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add creator account with initial balance
+    program_test.add_account(
+        creator.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add pre-initialized content account
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Two reporters each add their own deltas
+    let increment_analytics = |views, likes, shares, comments| {
+        BlockTokInstruction::increment_analytics(
+            &program_id,
+            &creator.pubkey(),
+            &creator.pubkey(),
+            &content_id,
+            views,
+            likes,
+            shares,
+            comments,
+            WatchMetrics::default(),
+        )
+    };
+    let instructions = [increment_analytics(10, 1, 0, 2), increment_analytics(5, 0, 3, 0)];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // A delta that would overflow a counter is rejected
+    let mut transaction =
+        Transaction::new_with_payer(&[increment_analytics(1, 1, 0, 0)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    // Verify the deltas accumulated and the rejected one left no trace
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let updated_content = Content::unpack(&content_account.data).unwrap();
+    assert_eq!(updated_content.analytics.views, 1015);
+    assert_eq!(updated_content.analytics.likes, u64::MAX);
+    assert_eq!(updated_content.analytics.shares, 3);
+    assert_eq!(updated_content.analytics.comments, 2);
+}
+
+#[tokio::test]
+async fn test_batch_update_analytics() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test data: two published contents of the same creator
+    let creator = Keypair::new();
+    let content_ids = [String::from("first-content-id"), String::from("second-content-id")];
+    let mut content_pubkeys = Vec::new();
+    for content_id in &content_ids {
+        let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), content_id);
+        let content = Content {
+            creator: creator.pubkey().to_bytes(),
+            content_id: content_id.clone(),
+            title: String::from("Test Content"),
+            description: String::from("Test Description"),
+            content_url: String::from("https://example.com/test-content"),
+            content_type: String::from("video"),
+            created_at: 1634567890,
+            nft_mint: None,
+            analytics: ContentAnalytics::default(),
+            royalty_distribution: blocktok::state::RoyaltyDistribution::default(),
+            tags: vec![],
+            status: ContentStatus::Published,
+            publish_at: 0,
+            analytics_nonce: 0,
+        };
+
+        // Add pre-initialized content account
+        let mut content_data = vec![0; content.account_size()];
+        content.pack(&mut content_data).unwrap();
+        program_test.add_account(
+            content_pubkey,
+            Account {
+                lamports: 1_000_000,
+                data: content_data,
+                owner: program_id,
+                ..Account::default()
+            },
+        );
+        content_pubkeys.push(content_pubkey);
+    }
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let update = |content: Pubkey, views: u64| ContentAnalyticsUpdate {
+        content,
+        views,
+        likes: views / 10,
+        shares: 0,
+        comments: 0,
+        watch: WatchMetrics::default(),
+    };
+
+    // Updates whose accounts are out of order, or too many updates, are rejected
+    let mut instruction = BlockTokInstruction::batch_update_analytics(
+        &program_id,
+        &creator.pubkey(),
+        vec![update(content_pubkeys[0], 10), update(content_pubkeys[1], 20)],
+    );
+    instruction.accounts.swap(2, 4);
+    let too_many = BlockTokInstruction::batch_update_analytics(
+        &program_id,
+        &creator.pubkey(),
+        vec![update(content_pubkeys[0], 10); MAX_BATCH_ANALYTICS_UPDATES + 1],
+    );
+    for instruction in [instruction, too_many] {
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &creator], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    // Update both contents at once
+    let instruction = BlockTokInstruction::batch_update_analytics(
+        &program_id,
+        &creator.pubkey(),
+        vec![update(content_pubkeys[0], 100), update(content_pubkeys[1], 200)],
+    );
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify each content got its own counters
+    for (content_pubkey, views) in content_pubkeys.iter().zip([100, 200]) {
+        let content_account = banks_client
+            .get_account(*content_pubkey)
+            .await
+            .unwrap()
+            .unwrap();
+        let content = Content::unpack(&content_account.data).unwrap();
+        assert_eq!(content.analytics.views, views);
+        assert_eq!(content.analytics.likes, views / 10);
+    }
+
+    // A failing update leaves the other updates unapplied
+    let instruction = BlockTokInstruction::batch_update_analytics(
+        &program_id,
+        &creator.pubkey(),
+        vec![update(content_pubkeys[0], 300), update(content_pubkeys[1], 150)],
+    );
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    let content_account = banks_client
+        .get_account(content_pubkeys[0])
+        .await
+        .unwrap()
+        .unwrap();
+    let content = Content::unpack(&content_account.data).unwrap();
+    assert_eq!(content.analytics.views, 100);
+}
+
+#[tokio::test]
+async fn test_analytics_history() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test data
+    let creator = Keypair::new();
+    let content_id = String::from("test-content-id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let (history_pubkey, _) = AnalyticsHistory::find_address(&program_id, &content_pubkey);
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics {
+            views: 1000,
+            ..ContentAnalytics::default()
+        },
+        royalty_distribution: blocktok::state::RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add creator account with initial balance
+    program_test.add_account(
+        creator.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add pre-initialized content account
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Create an hourly history, then record an increment and an absolute update
+    let instructions = [
+        BlockTokInstruction::initialize_analytics_history(
+            &program_id,
+            &creator.pubkey(),
+            &content_id,
+            HistoryGranularity::Hourly,
+        ),
+        BlockTokInstruction::increment_analytics(
+            &program_id,
+            &creator.pubkey(),
+            &creator.pubkey(),
+            &content_id,
+            10,
+            1,
+            0,
+            2,
+            WatchMetrics::default(),
+        ),
+        BlockTokInstruction::update_analytics(
+            &program_id,
+            &creator.pubkey(),
+            &creator.pubkey(),
+            &content_id,
+            1100,
+            5,
+            1,
+            2,
+            WatchMetrics::default(),
+            None,
+        ),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify both updates landed in the current hour's bucket
+    let history_account = banks_client
+        .get_account(history_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let history = AnalyticsHistory::unpack(&history_account.data).unwrap();
+    let now = banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64;
+
+    let time_series = history.time_series();
+    assert_eq!(time_series.len(), 1);
+    assert_eq!(time_series[0].start, now - now % 3600);
+    assert_eq!(time_series[0].views, 100);
+    assert_eq!(time_series[0].likes, 5);
+    assert_eq!(time_series[0].shares, 1);
+    assert_eq!(time_series[0].comments, 2);
+
+    // Closing the content closes its history too
+    let instruction = BlockTokInstruction::close_content(&program_id, &creator.pubkey(), &content_id, None, &[]);
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+    assert!(banks_client.get_account(history_pubkey).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_record_view() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test data
+    let creator = Keypair::new();
+    let viewer = Keypair::new();
+    let other_viewer = Keypair::new();
+    let content_id = String::from("test-content-id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let (receipt_pubkey, _) = ViewReceipt::find_address(&program_id, &content_pubkey, &viewer.pubkey());
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics {
+            views: 1000,
+            ..ContentAnalytics::default()
+        },
+        royalty_distribution: blocktok::state::RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add viewer accounts with initial balance
+    for viewer in [&viewer, &other_viewer] {
+        program_test.add_account(
+            viewer.pubkey(),
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![],
+                owner: system_program::id(),
+                ..Account::default()
+            },
+        );
+    }
+
+    // Add pre-initialized content account
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // View twice, the second time further into the content
+    for watch_progress_bps in [2500, 7500] {
+        let instruction = BlockTokInstruction::record_view(
+            &program_id,
+            &viewer.pubkey(),
+            &creator.pubkey(),
+            &content_id,
+            watch_progress_bps,
+        );
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &viewer], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+    }
+
+    // Verify the view counted once, the second as a replay, and the receipt
+    // kept the furthest progress
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let content = Content::unpack(&content_account.data).unwrap();
+    assert_eq!(content.analytics.views, 1001);
+    assert_eq!(content.analytics.watch.total_completion_bps, 7500);
+    assert_eq!(content.analytics.watch.replays, 1);
+    assert_eq!(content.analytics.updated_at, 0);
+
+    let receipt_account = banks_client
+        .get_account(receipt_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let receipt = ViewReceipt::unpack(&receipt_account.data).unwrap();
+    let now = banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64;
+    assert_eq!(receipt.get_viewer(), viewer.pubkey());
+    assert_eq!(receipt.first_seen_at, now);
+    assert_eq!(receipt.watch_progress_bps, 7500);
+
+    // A watch progress past the end is rejected
+    let instruction = BlockTokInstruction::record_view(
+        &program_id,
+        &other_viewer.pubkey(),
+        &creator.pubkey(),
+        &content_id,
+        10001,
+    );
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &other_viewer], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    // Another viewer counts as another view
+    let instruction = BlockTokInstruction::record_view(
+        &program_id,
+        &other_viewer.pubkey(),
+        &creator.pubkey(),
+        &content_id,
+        10000,
+    );
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &other_viewer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let content = Content::unpack(&content_account.data).unwrap();
+    assert_eq!(content.analytics.views, 1002);
+    assert_eq!(content.analytics.watch.total_completion_bps, 17500);
+    assert_eq!(content.analytics.watch.replays, 1);
+}
+
+#[tokio::test]
+async fn test_close_content() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test data
+    let creator = Keypair::new();
+    let nft_mint = Pubkey::new_unique();
+    let content_id = String::from("test-content-id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: Some(nft_mint.to_bytes()),
+        analytics: ContentAnalytics::default(),
+        royalty_distribution: blocktok::state::RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add creator account with initial balance
+    program_test.add_account(
+        creator.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add pre-initialized content account
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Add the content's NFT mint, with its one token still in circulation
+    let nft_mint_account = |supply: u64| {
+        let mut data = vec![0; Mint::LEN];
+        let mint = Mint {
+            mint_authority: COption::Some(creator.pubkey()),
+            supply,
+            decimals: 0,
+            is_initialized: true,
+            freeze_authority: COption::Some(creator.pubkey()),
+        };
+        Mint::pack(mint, &mut data).unwrap();
+        Account {
+            lamports: 1_000_000,
+            data,
+            owner: spl_token::id(),
+            ..Account::default()
+        }
+    };
+    program_test.add_account(nft_mint, nft_mint_account(1));
+
+    // Start program test
+    let mut context = program_test.start_with_context().await;
+
+    // Content whose NFT has not been burned cannot be closed
+    let close_content = || {
+        BlockTokInstruction::close_content(&program_id, &creator.pubkey(), &content_id, Some(&nft_mint), &[])
+    };
+    let mut transaction = Transaction::new_with_payer(&[close_content()], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &creator], context.last_blockhash);
+    let err = context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BlockTokError::NftNotBurned as u32)
+        )
+    );
+
+    // Once the NFT is burned, closing returns the rent to the creator
+    context.set_account(&nft_mint, &AccountSharedData::from(nft_mint_account(0)));
+    let creator_lamports = context
+        .banks_client
+        .get_balance(creator.pubkey())
+        .await
+        .unwrap();
+    let mut transaction = Transaction::new_with_payer(&[close_content()], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &creator], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    assert!(context.banks_client.get_account(content_pubkey).await.unwrap().is_none());
+    let creator_balance = context
+        .banks_client
+        .get_balance(creator.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(creator_balance, creator_lamports + 1_000_000);
+}
@@ -0,0 +1,20 @@
+//! Helpers shared by the integration tests
+
+use solana_program::{bpf_loader_upgradeable, pubkey::Pubkey};
+use solana_sdk::account::Account;
+
+/// Build a program data account naming `authority` as the upgrade authority
+pub fn program_data_account(authority: &Pubkey) -> Account {
+    // bincode `UpgradeableLoaderState::ProgramData { slot: 0, upgrade_authority_address: Some(authority) }`
+    let mut data = vec![3, 0, 0, 0];
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(authority.as_ref());
+
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: bpf_loader_upgradeable::id(),
+        ..Account::default()
+    }
+}
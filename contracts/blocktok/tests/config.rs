@@ -0,0 +1,1009 @@
+mod common;
+
+use solana_program::{
+    bpf_loader_upgradeable, clock::Clock, instruction::InstructionError, pubkey::Pubkey,
+    system_program,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use blocktok::{
+    error::BlockTokError,
+    instruction::{AttestationTerms, BlockTokInstruction},
+    state::{
+        pause_flags, AccountState, AnalyticsAttestation, Category, CategoryIndex, Content,
+        ContentAnalytics, ContentStatus, EngagementWeights, PlatformConfig, RoyaltyDistribution,
+        WatchMetrics,
+    },
+};
+
+use common::program_data_account;
+
+#[tokio::test]
+async fn test_platform_config_admin_flow() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let upgrade_authority = Keypair::new();
+    let new_admin = Keypair::new();
+    let treasury = Pubkey::new_unique();
+    let new_treasury = Pubkey::new_unique();
+
+    // Add upgrade authority account with initial balance
+    program_test.add_account(
+        upgrade_authority.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add program data account naming the upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&upgrade_authority.pubkey()));
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Initialize config, update it, then hand admin over to a new key
+    let instructions = [
+        BlockTokInstruction::initialize_config(
+            &program_id,
+            &upgrade_authority.pubkey(),
+            treasury,
+            250,
+            0,
+        ),
+        BlockTokInstruction::update_config(
+            &program_id,
+            &upgrade_authority.pubkey(),
+            Some(new_treasury),
+            Some(500),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ),
+        BlockTokInstruction::transfer_admin(
+            &program_id,
+            &upgrade_authority.pubkey(),
+            new_admin.pubkey(),
+        ),
+        BlockTokInstruction::accept_admin(&program_id, &new_admin.pubkey()),
+    ];
+
+    // Create and send transaction
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &upgrade_authority, &new_admin], recent_blockhash);
+
+    // Process transaction
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Get config account data
+    let (config_pubkey, _) = PlatformConfig::find_address(&program_id);
+    let config_account = banks_client
+        .get_account(config_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let config = PlatformConfig::unpack(&config_account.data).unwrap();
+
+    // Verify config data
+    assert_eq!(config.get_admin(), new_admin.pubkey());
+    assert_eq!(config.get_pending_admin(), None);
+    assert_eq!(config.get_treasury(), new_treasury);
+    assert_eq!(config.platform_fee_bps, 500);
+}
+
+#[tokio::test]
+async fn test_admin_transfer_upgrades_config() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let new_admin = Keypair::new();
+    let treasury = Pubkey::new_unique();
+
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add a config written at layout version 1: admin, pending admin, treasury,
+    // platform fee and features
+    let mut data = PlatformConfig::DISCRIMINATOR.to_vec();
+    data.push(1);
+    data.extend_from_slice(admin.pubkey().as_ref());
+    data.push(0);
+    data.extend_from_slice(treasury.as_ref());
+    data.extend_from_slice(&250u16.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    let (config_pubkey, _) = PlatformConfig::find_address(&program_id);
+    program_test.add_account(
+        config_pubkey,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Hand admin over without migrating the config first
+    let instructions = [
+        BlockTokInstruction::transfer_admin(&program_id, &admin.pubkey(), new_admin.pubkey()),
+        BlockTokInstruction::accept_admin(&program_id, &new_admin.pubkey()),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin, &new_admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // The config is now at the current version
+    let config_account = banks_client
+        .get_account(config_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let config = PlatformConfig::unpack(&config_account.data).unwrap();
+    assert_eq!(config.get_admin(), new_admin.pubkey());
+    assert_eq!(config.get_pending_admin(), None);
+    assert_eq!(config.get_treasury(), treasury);
+    assert_eq!(config.platform_fee_bps, 250);
+}
+
+#[tokio::test]
+async fn test_initialize_config_requires_upgrade_authority() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let upgrade_authority = Pubkey::new_unique();
+    let impostor = Keypair::new();
+
+    // Add impostor account with initial balance
+    program_test.add_account(
+        impostor.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add program data account naming a different upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&upgrade_authority));
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Create initialize config instruction signed by the impostor
+    let instruction = BlockTokInstruction::initialize_config(
+        &program_id,
+        &impostor.pubkey(),
+        Pubkey::new_unique(),
+        250,
+        0,
+    );
+
+    // Create and send transaction
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &impostor], recent_blockhash);
+
+    // Process transaction
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+}
+
+#[tokio::test]
+async fn test_pause_blocks_instructions() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+
+    // Add admin account with initial balance
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Initialize config with content creation paused
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        BlockTokInstruction::set_pause_state(
+            &program_id,
+            &admin.pubkey(),
+            false,
+            pause_flags::INITIALIZE_CONTENT,
+        ),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Content creation is refused while paused
+    let initialize_content = BlockTokInstruction::initialize_content(
+        &program_id,
+        &admin.pubkey(),
+        "test_content_id".to_string(),
+        "Test Content".to_string(),
+        "This is a test content".to_string(),
+        "https://example.com/test".to_string(),
+        "video".to_string(),
+        0,
+        vec![],
+    );
+    let mut transaction = Transaction::new_with_payer(&[initialize_content.clone()], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    // Unpause and retry
+    let instructions = [
+        BlockTokInstruction::set_pause_state(&program_id, &admin.pubkey(), false, 0),
+        initialize_content,
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify config data
+    let (config_pubkey, _) = PlatformConfig::find_address(&program_id);
+    let config_account = banks_client
+        .get_account(config_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let config = PlatformConfig::unpack(&config_account.data).unwrap();
+    assert!(!config.is_paused(pause_flags::INITIALIZE_CONTENT));
+}
+
+#[tokio::test]
+async fn test_category_tags() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let content_id = String::from("test_content_id");
+    let tech = String::from("Tech");
+    let ai_art = String::from("AI Art");
+
+    // Add admin account with initial balance; the admin also creates the content
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Register two categories, create content tagged "Tech", then retag it "AI Art"
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        BlockTokInstruction::create_category(&program_id, &admin.pubkey(), tech.clone()),
+        BlockTokInstruction::create_category(&program_id, &admin.pubkey(), ai_art.clone()),
+        BlockTokInstruction::initialize_content(
+            &program_id,
+            &admin.pubkey(),
+            content_id.clone(),
+            "Test Content".to_string(),
+            "This is a test content".to_string(),
+            "https://example.com/test".to_string(),
+            "video".to_string(),
+            0,
+            vec![tech.clone()],
+        ),
+        BlockTokInstruction::update_content_metadata(
+            &program_id,
+            &admin.pubkey(),
+            &content_id,
+            None,
+            None,
+            None,
+            None,
+            Some(vec![ai_art.clone()]),
+            &[tech.clone()],
+        ),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify the content moved from "Tech" to "AI Art"
+    let (content_pubkey, _) = Content::find_address(&program_id, &admin.pubkey(), &content_id);
+    let (tech_pubkey, _) = Category::find_address(&program_id, &tech);
+    let (ai_art_pubkey, _) = Category::find_address(&program_id, &ai_art);
+    let tech_account = banks_client.get_account(tech_pubkey).await.unwrap().unwrap();
+    assert_eq!(Category::unpack(&tech_account.data).unwrap().content_count, 0);
+    let ai_art_account = banks_client.get_account(ai_art_pubkey).await.unwrap().unwrap();
+    assert_eq!(Category::unpack(&ai_art_account.data).unwrap().content_count, 1);
+
+    let (tech_index_pubkey, _) = CategoryIndex::find_address(&program_id, &tech_pubkey, &content_pubkey);
+    assert!(banks_client.get_account(tech_index_pubkey).await.unwrap().is_none());
+    let (ai_art_index_pubkey, _) = CategoryIndex::find_address(&program_id, &ai_art_pubkey, &content_pubkey);
+    let ai_art_index_account = banks_client.get_account(ai_art_index_pubkey).await.unwrap().unwrap();
+    assert_eq!(CategoryIndex::unpack(&ai_art_index_account.data).unwrap().get_content(), content_pubkey);
+
+    // Deactivated categories cannot be added, but closing removes existing tags
+    let instructions = [
+        BlockTokInstruction::set_category_active(&program_id, &admin.pubkey(), ai_art.clone(), false),
+        BlockTokInstruction::close_content(&program_id, &admin.pubkey(), &content_id, None, &[ai_art.clone()]),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let ai_art_account = banks_client.get_account(ai_art_pubkey).await.unwrap().unwrap();
+    let ai_art_category = Category::unpack(&ai_art_account.data).unwrap();
+    assert!(!ai_art_category.active);
+    assert_eq!(ai_art_category.content_count, 0);
+    assert!(banks_client.get_account(ai_art_index_pubkey).await.unwrap().is_none());
+
+    // Names longer than a seed are rejected rather than derived
+    let long_name = "x".repeat(33);
+    let instructions = [
+        BlockTokInstruction::initialize_content(
+            &program_id,
+            &admin.pubkey(),
+            String::from("long_tag_content"),
+            "Test Content".to_string(),
+            "This is a test content".to_string(),
+            "https://example.com/test".to_string(),
+            "video".to_string(),
+            1,
+            vec![long_name.clone()],
+        ),
+        BlockTokInstruction::set_category_active(&program_id, &admin.pubkey(), long_name, true),
+    ];
+    for instruction in instructions {
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &admin], recent_blockhash);
+        let err = banks_client.process_transaction(transaction).await.unwrap_err().unwrap();
+        assert_eq!(
+            err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(BlockTokError::InvalidCategoryName as u32)
+            )
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_analytics_oracle() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let oracle = Keypair::new();
+    let creator = Keypair::new();
+    let content_id = String::from("test_content_id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics::default(),
+        royalty_distribution: RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add admin account with initial balance
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Add pre-initialized content account
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Register the oracle and stop accepting creator reports
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        BlockTokInstruction::set_analytics_oracles(&program_id, &admin.pubkey(), vec![oracle.pubkey()], false),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // The oracle may report for the creator's content
+    let instruction = BlockTokInstruction::increment_analytics(
+        &program_id,
+        &oracle.pubkey(),
+        &creator.pubkey(),
+        &content_id,
+        100,
+        10,
+        1,
+        0,
+        WatchMetrics::default(),
+    );
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // The creator no longer may
+    let instruction = BlockTokInstruction::increment_analytics(
+        &program_id,
+        &creator.pubkey(),
+        &creator.pubkey(),
+        &content_id,
+        1_000_000,
+        0,
+        0,
+        0,
+        WatchMetrics::default(),
+    );
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    // Verify only the oracle's report was applied
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let updated_content = Content::unpack(&content_account.data).unwrap();
+    assert_eq!(updated_content.analytics.views, 100);
+}
+
+#[tokio::test]
+async fn test_analytics_attestation() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let oracle = Keypair::new();
+    let creator = Keypair::new();
+    let content_id = String::from("test_content_id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics::default(),
+        royalty_distribution: RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add admin account with initial balance
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Add pre-initialized content account
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Register the oracle as the only analytics authority
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        BlockTokInstruction::set_analytics_oracles(&program_id, &admin.pubkey(), vec![oracle.pubkey()], false),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // The payer relays `views` signed by `signer`, claiming the oracle signed `signed_views`
+    let relay = |signer: &Keypair, signed_views: u64, views: u64, nonce: u64, expiry: u64| {
+        let attestation = AnalyticsAttestation {
+            content: content_pubkey.to_bytes(),
+            views: signed_views,
+            likes: 10,
+            shares: 1,
+            comments: 0,
+            watch: WatchMetrics::default(),
+            nonce,
+            expiry,
+        };
+        let signature = signer.sign_message(&attestation.message());
+        [
+            BlockTokInstruction::verify_analytics_attestation(
+                &signer.pubkey(),
+                signature.as_ref().try_into().unwrap(),
+                &attestation,
+            ),
+            BlockTokInstruction::update_analytics(
+                &program_id,
+                &payer.pubkey(),
+                &creator.pubkey(),
+                &content_id,
+                views,
+                10,
+                1,
+                0,
+                WatchMetrics::default(),
+                Some(AttestationTerms { nonce, expiry }),
+            ),
+        ]
+    };
+
+    // An attestation signed by the oracle is applied
+    let mut transaction = Transaction::new_with_payer(
+        &relay(&oracle, 100, 100, 1, u64::MAX),
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Replayed, not signed by an oracle, expired or tampered attestations are
+    // rejected. A new blockhash keeps the replay from being a duplicate transaction
+    let recent_blockhash = banks_client.get_new_latest_blockhash(&recent_blockhash).await.unwrap();
+    for instructions in [
+        relay(&oracle, 100, 100, 1, u64::MAX),
+        relay(&creator, 1_000, 1_000, 2, u64::MAX),
+        relay(&oracle, 1_000, 1_000, 2, 0),
+        relay(&oracle, 1_000, 1_000_000, 2, u64::MAX),
+    ] {
+        let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    // Verify only the first attestation was applied
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let updated_content = Content::unpack(&content_account.data).unwrap();
+    assert_eq!(updated_content.analytics.views, 100);
+    assert_eq!(updated_content.analytics_nonce, 1);
+}
+
+#[tokio::test]
+async fn test_analytics_update_rules() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let content_id = String::from("test_content_id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics::default(),
+        royalty_distribution: RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add admin account with initial balance
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Add pre-initialized content account
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let update_config = |min_analytics_interval| {
+        BlockTokInstruction::update_config(
+            &program_id,
+            &admin.pubkey(),
+            None,
+            None,
+            None,
+            Some(min_analytics_interval),
+            None,
+            None,
+            None,
+        )
+    };
+    let update_analytics = |views| {
+        BlockTokInstruction::update_analytics(
+            &program_id,
+            &creator.pubkey(),
+            &creator.pubkey(),
+            &content_id,
+            views,
+            0,
+            0,
+            0,
+            WatchMetrics::default(),
+            None,
+        )
+    };
+
+    // Require an hour between analytics updates, then report once
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        update_config(3600),
+        update_analytics(100),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // A second report within the hour is refused
+    let mut transaction = Transaction::new_with_payer(&[update_analytics(200)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    // Without a minimum interval, counters still cannot decrease
+    let mut transaction = Transaction::new_with_payer(&[update_config(0)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let mut transaction = Transaction::new_with_payer(&[update_analytics(50)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    let mut transaction = Transaction::new_with_payer(&[update_analytics(300)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify the config and the last accepted report
+    let config_account = banks_client
+        .get_account(PlatformConfig::find_address(&program_id).0)
+        .await
+        .unwrap()
+        .unwrap();
+    let config = PlatformConfig::unpack(&config_account.data).unwrap();
+    assert_eq!(config.min_analytics_interval, 0);
+
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let updated_content = Content::unpack(&content_account.data).unwrap();
+    assert_eq!(updated_content.analytics.views, 300);
+}
+
+#[tokio::test]
+async fn test_engagement_score() {
+    // Decay halves the score every half-life, interpolating linearly in between
+    let weights = EngagementWeights {
+        views: 1,
+        decay_half_life: 100,
+        ..EngagementWeights::default()
+    };
+    let analytics = ContentAnalytics {
+        views: 1000,
+        ..ContentAnalytics::default()
+    };
+    assert_eq!(weights.score(&analytics, 0, 0), 1000);
+    assert_eq!(weights.score(&analytics, 0, 50), 750);
+    assert_eq!(weights.score(&analytics, 0, 100), 500);
+    assert_eq!(weights.score(&analytics, 0, 150), 375);
+    assert_eq!(weights.score(&analytics, 0, 100 * 64), 0);
+
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let content_id = String::from("test_content_id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics::default(),
+        royalty_distribution: RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add admin account with initial balance
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Add pre-initialized content account
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Weigh engagement without decay, then report analytics
+    let mut weights = EngagementWeights {
+        views: 1,
+        likes: 5,
+        shares: 10,
+        comments: 3,
+        watch_seconds: 1,
+        decay_half_life: 0,
+    };
+    let watch = WatchMetrics {
+        watch_seconds: 600,
+        ..WatchMetrics::default()
+    };
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        BlockTokInstruction::update_config(
+            &program_id,
+            &admin.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            Some(weights),
+            None,
+            None,
+        ),
+        BlockTokInstruction::update_analytics(
+            &program_id,
+            &creator.pubkey(),
+            &creator.pubkey(),
+            &content_id,
+            100,
+            10,
+            2,
+            4,
+            watch,
+            None,
+        ),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify the score is the weighted sum
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let updated_content = Content::unpack(&content_account.data).unwrap();
+    assert_eq!(updated_content.analytics.engagement_score, 100 + 50 + 20 + 12 + 600);
+
+    // Decay scores with a 30 day half-life, recomputed on the next change
+    weights.decay_half_life = 30 * 24 * 60 * 60;
+    let instructions = [
+        BlockTokInstruction::update_config(
+            &program_id,
+            &admin.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            Some(weights),
+            None,
+            None,
+        ),
+        BlockTokInstruction::increment_analytics(
+            &program_id,
+            &creator.pubkey(),
+            &creator.pubkey(),
+            &content_id,
+            1,
+            0,
+            0,
+            0,
+            WatchMetrics::default(),
+        ),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify the score decayed with the content's age
+    let now = banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64;
+    let content_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let updated_content = Content::unpack(&content_account.data).unwrap();
+    let expected = weights.score(&updated_content.analytics, updated_content.created_at, now);
+    assert_eq!(updated_content.analytics.engagement_score, expected);
+    assert!(expected < 100 + 1 + 50 + 20 + 12 + 600);
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use solana_program::{bpf_loader_upgradeable, clock::Clock, pubkey::Pubkey, system_program};
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+use blocktok::{
+    instruction::BlockTokInstruction,
+    merkle,
+    state::{
+        AccountState, Content, ContentAnalytics, ContentStatus, EngagementEpoch, EngagementEvent,
+        EngagementKind, EpochContentTotals, RoyaltyDistribution,
+    },
+};
+
+use common::program_data_account;
+
+#[tokio::test]
+async fn test_engagement_epoch() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let oracle = Keypair::new();
+    let impostor = Keypair::new();
+    let creator = Keypair::new();
+    let content_id = String::from("test_content_id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics::default(),
+        royalty_distribution: RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add admin, oracle and impostor accounts with initial balance
+    for account in [&admin, &oracle, &impostor] {
+        program_test.add_account(
+            account.pubkey(),
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![],
+                owner: system_program::id(),
+                ..Account::default()
+            },
+        );
+    }
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Add pre-initialized content account
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Register the oracle as the only analytics authority
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        BlockTokInstruction::set_analytics_oracles(&program_id, &admin.pubkey(), vec![oracle.pubkey()], false),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Build a tree of four events in the hour before now
+    let now = banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64;
+    let (start_time, end_time) = (now - 3600, now);
+    let kinds = [EngagementKind::View, EngagementKind::Like, EngagementKind::View, EngagementKind::Share];
+    let events: Vec<EngagementEvent> = kinds
+        .iter()
+        .enumerate()
+        .map(|(i, kind)| EngagementEvent {
+            content: content_pubkey.to_bytes(),
+            user: Pubkey::new_unique().to_bytes(),
+            kind: *kind,
+            timestamp: start_time + 60 * i as u64,
+        })
+        .collect();
+    let leaves: Vec<[u8; 32]> = events.iter().map(EngagementEvent::leaf).collect();
+    let left = merkle::hash_node(&leaves[0], &leaves[1]);
+    let right = merkle::hash_node(&leaves[2], &leaves[3]);
+    let merkle_root = merkle::hash_node(&left, &right);
+
+    // Only an oracle can commit, and only a window that is over
+    let rejected = [
+        (
+            &impostor,
+            BlockTokInstruction::commit_engagement_epoch(&program_id, &impostor.pubkey(), 1, start_time, end_time, merkle_root, 4),
+        ),
+        (
+            &oracle,
+            BlockTokInstruction::commit_engagement_epoch(&program_id, &oracle.pubkey(), 1, start_time, now + 3600, merkle_root, 4),
+        ),
+    ];
+    for (signer, instruction) in rejected {
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, signer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    // Commit the epoch and the content's totals
+    let instructions = [
+        BlockTokInstruction::commit_engagement_epoch(&program_id, &oracle.pubkey(), 1, start_time, end_time, merkle_root, 4),
+        BlockTokInstruction::record_epoch_totals(&program_id, &oracle.pubkey(), 1, &content_pubkey, 2, 1, 1, 0),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let (epoch_pubkey, _) = EngagementEpoch::find_address(&program_id, 1);
+    let epoch_account = banks_client.get_account(epoch_pubkey).await.unwrap().unwrap();
+    let engagement_epoch = EngagementEpoch::unpack(&epoch_account.data).unwrap();
+    assert_eq!(engagement_epoch.get_authority(), oracle.pubkey());
+    assert_eq!(engagement_epoch.merkle_root, merkle_root);
+    assert_eq!(engagement_epoch.event_count, 4);
+
+    let (totals_pubkey, _) = EpochContentTotals::find_address(&program_id, &epoch_pubkey, &content_pubkey);
+    let totals_account = banks_client.get_account(totals_pubkey).await.unwrap().unwrap();
+    let totals = EpochContentTotals::unpack(&totals_account.data).unwrap();
+    assert_eq!((totals.views, totals.likes, totals.shares, totals.comments), (2, 1, 1, 0));
+
+    // An epoch cannot be committed twice
+    let instruction = BlockTokInstruction::commit_engagement_epoch(&program_id, &oracle.pubkey(), 1, start_time, end_time, [0; 32], 0);
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    // Anyone can prove the third event was included
+    let instruction = BlockTokInstruction::verify_engagement(&program_id, 1, events[2].clone(), vec![leaves[3], left]);
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Altered events and wrong proofs are rejected
+    let mut altered = events[2].clone();
+    altered.kind = EngagementKind::Comment;
+    let mut outside = events[2].clone();
+    outside.timestamp = end_time;
+    let rejected = [
+        BlockTokInstruction::verify_engagement(&program_id, 1, altered, vec![leaves[3], left]),
+        BlockTokInstruction::verify_engagement(&program_id, 1, events[2].clone(), vec![leaves[3], right]),
+        BlockTokInstruction::verify_engagement(&program_id, 1, outside, vec![leaves[3], left]),
+    ];
+    for instruction in rejected {
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+}
@@ -1,16 +1,16 @@
-use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::{pubkey::Pubkey, system_program};
+use solana_program::{instruction::InstructionError, pubkey::Pubkey, system_program};
 use solana_program_test::*;
 use solana_sdk::{
     account::Account,
     signature::{Keypair, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
 use spl_token::state::Mint;
 
 use blocktok::{
+    error::BlockTokError,
     instruction::BlockTokInstruction,
-    state::{Content, ContentAnalytics},
+    state::{AccountState, Content, ContentAnalytics, ContentStatus, MAX_ROYALTY_RECIPIENTS},
 };
 
 #[tokio::test]
@@ -29,20 +29,13 @@ async fn test_mint_nft() {
     let content_id = String::from("test-content-id");
     
     // Create content account address
-    let (content_pubkey, _) = Pubkey::find_program_address(
-        &[
-            b"content",
-            creator.pubkey().as_ref(),
-            content_id.as_bytes(),
-        ],
-        &program_id,
-    );
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
 
     // Create mint account keypair
     let mint_keypair = Keypair::new();
     
     // Initialize content (would be done separately in a real test)
-    let mut content = Content {
+    let content = Content {
         creator: creator.pubkey().to_bytes(),
         content_id: content_id.clone(),
         title: String::from("Test Content"),
@@ -53,6 +46,10 @@ async fn test_mint_nft() {
         nft_mint: None,
         analytics: ContentAnalytics::default(),
         royalty_distribution: blocktok::state::RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
     };
 
     // Add accounts with initial balances
@@ -78,7 +75,7 @@ async fn test_mint_nft() {
 
     // Add pre-initialized content account
     let mut content_data = vec![0; 1000]; // Sufficient size for the data
-    content.serialize(&mut content_data.as_mut_slice()).unwrap();
+    content.pack(&mut content_data).unwrap();
     program_test.add_account(
         content_pubkey,
         Account {
@@ -125,7 +122,7 @@ async fn test_mint_nft() {
         .unwrap();
 
     // Deserialize content data
-    let updated_content = Content::try_from_slice(&content_account.data).unwrap();
+    let updated_content = Content::unpack(&content_account.data).unwrap();
 
     // Verify NFT mint was set
     assert!(updated_content.nft_mint.is_some());
@@ -159,17 +156,10 @@ async fn test_set_royalty_distribution() {
     let content_id = String::from("test-content-id");
     
     // Create content account address
-    let (content_pubkey, _) = Pubkey::find_program_address(
-        &[
-            b"content",
-            creator.pubkey().as_ref(),
-            content_id.as_bytes(),
-        ],
-        &program_id,
-    );
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
 
     // Initialize content (would be done separately in a real test)
-    let mut content = Content {
+    let content = Content {
         creator: creator.pubkey().to_bytes(),
         content_id: content_id.clone(),
         title: String::from("Test Content"),
@@ -180,6 +170,10 @@ async fn test_set_royalty_distribution() {
         nft_mint: None,
         analytics: ContentAnalytics::default(),
         royalty_distribution: blocktok::state::RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
     };
 
     // Add creator account with initial balance
@@ -194,8 +188,8 @@ async fn test_set_royalty_distribution() {
     );
 
     // Add pre-initialized content account
-    let mut content_data = vec![0; 1000]; // Sufficient size for the data
-    content.serialize(&mut content_data.as_mut_slice()).unwrap();
+    let mut content_data = vec![0; content.account_size()]; // Sized for no recipients
+    content.pack(&mut content_data).unwrap();
     program_test.add_account(
         content_pubkey,
         Account {
@@ -211,8 +205,8 @@ async fn test_set_royalty_distribution() {
     
     // Royalty recipient data
     let recipients = vec![
-        (recipient1.pubkey(), 70), // 70%
-        (recipient2.pubkey(), 30), // 30%
+        (recipient1.pubkey().to_string(), 7000), // 70%
+        (recipient2.pubkey().to_string(), 3000), // 30%
     ];
 
     // Create set royalty distribution instruction
@@ -221,6 +215,7 @@ async fn test_set_royalty_distribution() {
         &creator.pubkey(),
         &content_id,
         recipients.clone(),
+        &[recipient1.pubkey(), recipient2.pubkey()],
     );
 
     // Create and send transaction
@@ -238,12 +233,59 @@ async fn test_set_royalty_distribution() {
         .unwrap();
 
     // Deserialize content data
-    let updated_content = Content::try_from_slice(&content_account.data).unwrap();
+    let updated_content = Content::unpack(&content_account.data).unwrap();
 
     // Verify royalty distribution
     assert_eq!(updated_content.royalty_distribution.recipients.len(), 2);
     assert_eq!(updated_content.royalty_distribution.recipients[0].pubkey, recipient1.pubkey().to_bytes());
-    assert_eq!(updated_content.royalty_distribution.recipients[0].percentage, 70);
+    assert_eq!(updated_content.royalty_distribution.recipients[0].percentage, 7000);
     assert_eq!(updated_content.royalty_distribution.recipients[1].pubkey, recipient2.pubkey().to_bytes());
-    assert_eq!(updated_content.royalty_distribution.recipients[1].percentage, 30);
+    assert_eq!(updated_content.royalty_distribution.recipients[1].percentage, 3000);
+
+    // Verify the account grew to fit both recipients
+    assert_eq!(content_account.data.len(), updated_content.account_size());
+
+    // Shrink back to a single recipient, refunding the spare rent
+    let instruction = BlockTokInstruction::set_royalty_distribution(
+        &program_id,
+        &creator.pubkey(),
+        &content_id,
+        vec![(recipient2.pubkey().to_string(), 10000)],
+        &[recipient2.pubkey()],
+    );
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let shrunk_account = banks_client
+        .get_account(content_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let shrunk_content = Content::unpack(&shrunk_account.data).unwrap();
+    assert_eq!(shrunk_content.royalty_distribution.recipients.len(), 1);
+    assert_eq!(shrunk_content.royalty_distribution.recipients[0].pubkey, recipient2.pubkey().to_bytes());
+    assert_eq!(shrunk_account.data.len(), shrunk_content.account_size());
+    assert!(shrunk_account.data.len() < content_account.data.len());
+    assert!(shrunk_account.lamports < content_account.lamports);
+
+    // More than the maximum is rejected; the count is checked before any
+    // recipient is parsed, which keeps the transaction small
+    let instruction = BlockTokInstruction::set_royalty_distribution(
+        &program_id,
+        &creator.pubkey(),
+        &content_id,
+        vec![(String::new(), 0); MAX_ROYALTY_RECIPIENTS + 1],
+        &[],
+    );
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &creator], recent_blockhash);
+    let err = banks_client.process_transaction(transaction).await.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BlockTokError::TooManyRoyaltyRecipients as u32)
+        )
+    );
 } 
\ No newline at end of file
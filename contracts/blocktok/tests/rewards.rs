@@ -0,0 +1,808 @@
+mod common;
+
+use solana_program::{
+    bpf_loader_upgradeable, clock::Clock, program_pack::Pack, pubkey::Pubkey, system_program,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, AccountState as TokenAccountState, Mint};
+
+use blocktok::{
+    error::BlockTokError,
+    instruction::BlockTokInstruction,
+    merkle,
+    state::{
+        find_reward_mint_address, find_reward_mint_authority_address, AccountState, Content,
+        ContentAnalytics, ContentStatus, DistributionEntry, EmissionSchedule, PlatformConfig,
+        RewardDistributor, RoyaltyDistribution, RoyaltyRecipient, UserRewards,
+        REWARD_TOKEN_DECIMALS,
+    },
+};
+
+use common::program_data_account;
+
+/// Build an empty token account of `mint` owned by `owner`
+fn token_account(mint: &Pubkey, owner: &Pubkey) -> Account {
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        state: TokenAccountState::Initialized,
+        ..TokenAccount::default()
+    }
+    .pack_into_slice(&mut data);
+
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: spl_token::id(),
+        ..Account::default()
+    }
+}
+
+/// Build an emission schedule that no test mints anywhere near
+fn ample_emission_schedule() -> EmissionSchedule {
+    EmissionSchedule {
+        start_time: 0,
+        epoch_duration: 86400,
+        tokens_per_epoch: 1_000_000_000,
+        halving_interval: 0,
+        total_cap: u64::MAX,
+    }
+}
+
+#[tokio::test]
+async fn test_reward_mint() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let oracle = Keypair::new();
+    let viewer = Keypair::new();
+    let (reward_mint_pubkey, _) = find_reward_mint_address(&program_id);
+    let viewer_token_pubkey = Pubkey::new_unique();
+
+    // Add admin, oracle and viewer accounts with initial balance
+    for account in [&admin, &oracle, &viewer] {
+        program_test.add_account(
+            account.pubkey(),
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![],
+                owner: system_program::id(),
+                ..Account::default()
+            },
+        );
+    }
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Add the viewer's reward token account
+    program_test.add_account(
+        viewer_token_pubkey,
+        token_account(&reward_mint_pubkey, &viewer.pubkey()),
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Only the admin can create the reward mint
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+            BlockTokInstruction::initialize_reward_mint(&program_id, &oracle.pubkey()),
+        ],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &admin, &oracle], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        BlockTokInstruction::set_analytics_oracles(&program_id, &admin.pubkey(), vec![oracle.pubkey()], false),
+        BlockTokInstruction::update_config(&program_id, &admin.pubkey(), None, None, None, None, None, None, Some(ample_emission_schedule())),
+        BlockTokInstruction::initialize_reward_mint(&program_id, &admin.pubkey()),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify the mint authority is the program PDA
+    let mint_account = banks_client
+        .get_account(reward_mint_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(mint_account.owner, spl_token::id());
+    let mint = Mint::unpack(&mint_account.data).unwrap();
+    assert_eq!(
+        mint.mint_authority,
+        Some(find_reward_mint_authority_address(&program_id).0).into()
+    );
+    assert_eq!(mint.decimals, REWARD_TOKEN_DECIMALS);
+    assert_eq!(mint.supply, 0);
+
+    // Viewers cannot mint rewards to themselves
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::mint_rewards(&program_id, &viewer.pubkey(), &viewer_token_pubkey, 500)],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &viewer], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    // The oracle can
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::mint_rewards(&program_id, &oracle.pubkey(), &viewer_token_pubkey, 500)],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify the viewer received the rewards
+    let token_account = banks_client
+        .get_account(viewer_token_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(TokenAccount::unpack(&token_account.data).unwrap().amount, 500);
+}
+
+#[tokio::test]
+async fn test_claim_rewards() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let oracle = Keypair::new();
+    let viewer = Keypair::new();
+    let creator = Keypair::new();
+    let content_id = String::from("test_content_id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics::default(),
+        royalty_distribution: RoyaltyDistribution::default(),
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+
+    // Add admin, oracle and viewer accounts with initial balance
+    for account in [&admin, &oracle, &viewer] {
+        program_test.add_account(
+            account.pubkey(),
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![],
+                owner: system_program::id(),
+                ..Account::default()
+            },
+        );
+    }
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Add pre-initialized content account
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Reward each receipt-backed view with 10 credits
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        BlockTokInstruction::set_analytics_oracles(&program_id, &admin.pubkey(), vec![oracle.pubkey()], false),
+        BlockTokInstruction::update_config(&program_id, &admin.pubkey(), None, None, None, None, None, Some(10), Some(ample_emission_schedule())),
+        BlockTokInstruction::initialize_reward_mint(&program_id, &admin.pubkey()),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Only the first view is rewarded, and the oracle adds credits for
+    // engagement reported off-chain
+    let instructions = [
+        BlockTokInstruction::record_view(&program_id, &viewer.pubkey(), &creator.pubkey(), &content_id, 5000),
+        BlockTokInstruction::record_view(&program_id, &viewer.pubkey(), &creator.pubkey(), &content_id, 8000),
+        BlockTokInstruction::accrue_rewards(&program_id, &oracle.pubkey(), &viewer.pubkey(), 90),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &viewer, &oracle], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Viewers cannot accrue credits to themselves
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::accrue_rewards(&program_id, &viewer.pubkey(), &viewer.pubkey(), 1000)],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &viewer], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    // Verify the accrued credits
+    let (rewards_pubkey, _) = UserRewards::find_address(&program_id, &viewer.pubkey());
+    let rewards_account = banks_client
+        .get_account(rewards_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let rewards = UserRewards::unpack(&rewards_account.data).unwrap();
+    assert_eq!(rewards.get_user(), viewer.pubkey());
+    assert_eq!(rewards.accrued, 100);
+    assert_eq!(rewards.claimable(), 100);
+
+    // Claim into the viewer's associated token account
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::claim_rewards(&program_id, &viewer.pubkey())],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &viewer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let token_pubkey =
+        get_associated_token_address(&viewer.pubkey(), &find_reward_mint_address(&program_id).0);
+    let token_account = banks_client
+        .get_account(token_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(TokenAccount::unpack(&token_account.data).unwrap().amount, 100);
+
+    let rewards_account = banks_client
+        .get_account(rewards_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let rewards = UserRewards::unpack(&rewards_account.data).unwrap();
+    assert_eq!(rewards.claimed, 100);
+    assert_eq!(rewards.claimable(), 0);
+
+    // Nothing is left to claim
+    let recent_blockhash = banks_client.get_new_latest_blockhash(&recent_blockhash).await.unwrap();
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::claim_rewards(&program_id, &viewer.pubkey())],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &viewer], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+}
+
+#[tokio::test]
+async fn test_reward_distributor() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let oracle = Keypair::new();
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let (reward_mint_pubkey, _) = find_reward_mint_address(&program_id);
+    let oracle_token_pubkey = Pubkey::new_unique();
+
+    // Add admin, oracle and recipient accounts with initial balance
+    for account in [&admin, &oracle, &alice, &bob] {
+        program_test.add_account(
+            account.pubkey(),
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![],
+                owner: system_program::id(),
+                ..Account::default()
+            },
+        );
+    }
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Add the oracle's reward token account
+    program_test.add_account(
+        oracle_token_pubkey,
+        token_account(&reward_mint_pubkey, &oracle.pubkey()),
+    );
+
+    // Start program test
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.insecure_clone();
+    let recent_blockhash = context.last_blockhash;
+
+    // Give the oracle tokens to fund the distributor with
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        BlockTokInstruction::set_analytics_oracles(&program_id, &admin.pubkey(), vec![oracle.pubkey()], false),
+        BlockTokInstruction::update_config(&program_id, &admin.pubkey(), None, None, None, None, None, None, Some(ample_emission_schedule())),
+        BlockTokInstruction::initialize_reward_mint(&program_id, &admin.pubkey()),
+        BlockTokInstruction::mint_rewards(&program_id, &oracle.pubkey(), &oracle_token_pubkey, 1000),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin, &oracle], recent_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    // Build a tree of the epoch's two payouts
+    let entries = [
+        DistributionEntry { index: 0, user: alice.pubkey().to_bytes(), amount: 300 },
+        DistributionEntry { index: 1, user: bob.pubkey().to_bytes(), amount: 200 },
+    ];
+    let leaves: Vec<[u8; 32]> = entries.iter().map(DistributionEntry::leaf).collect();
+    let merkle_root = merkle::hash_node(&leaves[0], &leaves[1]);
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64;
+    let expires_at = now + 86400;
+
+    // Only an oracle can create a distributor, and only an unexpired one
+    let rejected = [
+        (
+            &alice,
+            BlockTokInstruction::create_reward_distributor(&program_id, &alice.pubkey(), &oracle_token_pubkey, 1, merkle_root, 500, 2, expires_at),
+        ),
+        (
+            &oracle,
+            BlockTokInstruction::create_reward_distributor(&program_id, &oracle.pubkey(), &oracle_token_pubkey, 1, merkle_root, 500, 2, now),
+        ),
+    ];
+    for (signer, instruction) in rejected {
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, signer], recent_blockhash);
+        assert!(context.banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::create_reward_distributor(&program_id, &oracle.pubkey(), &oracle_token_pubkey, 1, merkle_root, 500, 2, expires_at)],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify the vault holds the deposit
+    let (distributor_pubkey, _) = RewardDistributor::find_address(&program_id, 1);
+    let (vault_pubkey, _) = RewardDistributor::find_vault_address(&program_id, &distributor_pubkey);
+    let vault_account = context.banks_client.get_account(vault_pubkey).await.unwrap().unwrap();
+    let vault = TokenAccount::unpack(&vault_account.data).unwrap();
+    assert_eq!(vault.owner, distributor_pubkey);
+    assert_eq!(vault.amount, 500);
+
+    // Alice claims her entry; altered entries and wrong proofs are rejected
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::claim_distribution(&program_id, &alice.pubkey(), 1, 0, 300, vec![leaves[1]])],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &alice], recent_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let rejected = [
+        (&alice, BlockTokInstruction::claim_distribution(&program_id, &alice.pubkey(), 1, 0, 300, vec![leaves[1]])),
+        (&alice, BlockTokInstruction::claim_distribution(&program_id, &alice.pubkey(), 1, 1, 200, vec![leaves[0]])),
+        (&bob, BlockTokInstruction::claim_distribution(&program_id, &bob.pubkey(), 1, 1, 400, vec![leaves[0]])),
+        (&bob, BlockTokInstruction::claim_distribution(&program_id, &bob.pubkey(), 1, 2, 200, vec![leaves[0]])),
+    ];
+    let recent_blockhash = context.banks_client.get_new_latest_blockhash(&recent_blockhash).await.unwrap();
+    for (signer, instruction) in rejected {
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, signer], recent_blockhash);
+        assert!(context.banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    let alice_token_pubkey = get_associated_token_address(&alice.pubkey(), &reward_mint_pubkey);
+    let token_account = context.banks_client.get_account(alice_token_pubkey).await.unwrap().unwrap();
+    assert_eq!(TokenAccount::unpack(&token_account.data).unwrap().amount, 300);
+
+    let distributor_account = context.banks_client.get_account(distributor_pubkey).await.unwrap().unwrap();
+    let distributor = RewardDistributor::unpack(&distributor_account.data).unwrap();
+    assert!(distributor.is_claimed(0));
+    assert!(!distributor.is_claimed(1));
+    assert_eq!(distributor.claimed_amount, 300);
+
+    // Nothing can be clawed back before expiry
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::clawback_distribution(&program_id, &oracle.pubkey(), 1, &oracle_token_pubkey)],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    assert!(context.banks_client.process_transaction(transaction).await.is_err());
+
+    // Once expired, Bob can no longer claim and only the authority claws back
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = expires_at as i64;
+    context.set_sysvar(&clock);
+
+    let recent_blockhash = context.banks_client.get_new_latest_blockhash(&recent_blockhash).await.unwrap();
+    let rejected = [
+        (&bob, BlockTokInstruction::claim_distribution(&program_id, &bob.pubkey(), 1, 1, 200, vec![leaves[0]])),
+        (&alice, BlockTokInstruction::clawback_distribution(&program_id, &alice.pubkey(), 1, &alice_token_pubkey)),
+    ];
+    for (signer, instruction) in rejected {
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, signer], recent_blockhash);
+        assert!(context.banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::clawback_distribution(&program_id, &oracle.pubkey(), 1, &oracle_token_pubkey)],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify Bob's unclaimed tokens went back to the oracle
+    let token_account = context.banks_client.get_account(oracle_token_pubkey).await.unwrap().unwrap();
+    assert_eq!(TokenAccount::unpack(&token_account.data).unwrap().amount, 700);
+
+    let distributor_account = context.banks_client.get_account(distributor_pubkey).await.unwrap().unwrap();
+    let distributor = RewardDistributor::unpack(&distributor_account.data).unwrap();
+    assert!(distributor.clawed_back);
+}
+
+#[tokio::test]
+async fn test_emission_schedule() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let oracle = Keypair::new();
+    let viewer = Keypair::new();
+    let (reward_mint_pubkey, _) = find_reward_mint_address(&program_id);
+    let (config_pubkey, _) = PlatformConfig::find_address(&program_id);
+    let viewer_token_pubkey = Pubkey::new_unique();
+
+    // Add admin, oracle and viewer accounts with initial balance
+    for account in [&admin, &oracle, &viewer] {
+        program_test.add_account(
+            account.pubkey(),
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![],
+                owner: system_program::id(),
+                ..Account::default()
+            },
+        );
+    }
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Add the viewer's reward token account
+    program_test.add_account(
+        viewer_token_pubkey,
+        token_account(&reward_mint_pubkey, &viewer.pubkey()),
+    );
+
+    // Start program test
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.insecure_clone();
+    let recent_blockhash = context.last_blockhash;
+
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        BlockTokInstruction::set_analytics_oracles(&program_id, &admin.pubkey(), vec![oracle.pubkey()], false),
+        BlockTokInstruction::initialize_reward_mint(&program_id, &admin.pubkey()),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    // Nothing can be minted before the admin sets a schedule
+    let mint_rewards = |amount| {
+        BlockTokInstruction::mint_rewards(&program_id, &oracle.pubkey(), &viewer_token_pubkey, amount)
+    };
+    let mut transaction = Transaction::new_with_payer(&[mint_rewards(100)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    assert!(context.banks_client.process_transaction(transaction).await.is_err());
+
+    // Allow 1000 tokens an hour, halving every hour, and 1400 in total
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64;
+    let schedule = EmissionSchedule {
+        start_time: now,
+        epoch_duration: 3600,
+        tokens_per_epoch: 1000,
+        halving_interval: 1,
+        total_cap: 1400,
+    };
+    let instruction = BlockTokInstruction::update_config(&program_id, &admin.pubkey(), None, None, None, None, None, None, Some(schedule));
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin], recent_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    // The first epoch mints up to its allowance
+    let mut transaction = Transaction::new_with_payer(&[mint_rewards(600), mint_rewards(400)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let mut transaction = Transaction::new_with_payer(&[mint_rewards(1)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    assert!(context.banks_client.process_transaction(transaction).await.is_err());
+
+    // The second epoch's halved allowance of 500 is cut short by the total cap
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = (now + 3600) as i64;
+    context.set_sysvar(&clock);
+
+    let recent_blockhash = context.banks_client.get_new_latest_blockhash(&recent_blockhash).await.unwrap();
+    let mut transaction = Transaction::new_with_payer(&[mint_rewards(500)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    assert!(context.banks_client.process_transaction(transaction).await.is_err());
+
+    let mut transaction = Transaction::new_with_payer(&[mint_rewards(400)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    // Claims mint through the same schedule, so credits cannot be claimed either
+    let instructions = [
+        BlockTokInstruction::accrue_rewards(&program_id, &oracle.pubkey(), &viewer.pubkey(), 10),
+        BlockTokInstruction::claim_rewards(&program_id, &viewer.pubkey()),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &oracle, &viewer], recent_blockhash);
+    assert!(context.banks_client.process_transaction(transaction).await.is_err());
+
+    // Verify the emission counters and the minted supply agree
+    let config_account = context.banks_client.get_account(config_pubkey).await.unwrap().unwrap();
+    let config = PlatformConfig::unpack(&config_account.data).unwrap();
+    assert_eq!(config.emission_schedule, schedule);
+    assert_eq!(config.emitted_total, 1400);
+    assert_eq!(config.emission_epoch, 1);
+    assert_eq!(config.emitted_in_epoch, 400);
+
+    let mint_account = context.banks_client.get_account(reward_mint_pubkey).await.unwrap().unwrap();
+    assert_eq!(Mint::unpack(&mint_account.data).unwrap().supply, 1400);
+}
+
+#[tokio::test]
+async fn test_creator_earnings() {
+    // Initialize program test
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "blocktok",
+        program_id,
+        processor!(blocktok::process_instruction),
+    );
+
+    // Setup test accounts
+    let admin = Keypair::new();
+    let oracle = Keypair::new();
+    let tipper = Keypair::new();
+    let creator = Keypair::new();
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    let (reward_mint_pubkey, _) = find_reward_mint_address(&program_id);
+    let tipper_token_pubkey = Pubkey::new_unique();
+    let content_id = String::from("test_content_id");
+    let (content_pubkey, _) = Content::find_address(&program_id, &creator.pubkey(), &content_id);
+    let recipients = [(creator.pubkey(), 5000), (alice, 3333), (bob, 1667)];
+    let content = Content {
+        creator: creator.pubkey().to_bytes(),
+        content_id: content_id.clone(),
+        title: String::from("Test Content"),
+        description: String::from("Test Description"),
+        content_url: String::from("https://example.com/test-content"),
+        content_type: String::from("video"),
+        created_at: 1634567890,
+        nft_mint: None,
+        analytics: ContentAnalytics::default(),
+        royalty_distribution: RoyaltyDistribution {
+            recipients: recipients
+                .iter()
+                .map(|(pubkey, percentage)| RoyaltyRecipient {
+                    pubkey: pubkey.to_bytes(),
+                    percentage: *percentage,
+                })
+                .collect(),
+        },
+        tags: vec![],
+        status: ContentStatus::Published,
+        publish_at: 0,
+        analytics_nonce: 0,
+    };
+    let payees = content.royalty_distribution.payees(&creator.pubkey());
+    assert_eq!(payees, vec![creator.pubkey(), alice, bob]);
+
+    // Shares are rounded down and the dust goes to the largest remainders
+    assert_eq!(content.royalty_distribution.split(100).unwrap(), vec![50, 33, 17]);
+    assert_eq!(content.royalty_distribution.split(10).unwrap(), vec![5, 3, 2]);
+    assert_eq!(content.royalty_distribution.split(2).unwrap(), vec![1, 1, 0]);
+    assert_eq!(RoyaltyDistribution::default().split(7).unwrap(), vec![7]);
+
+    // Distributions that do not add up to 100% cannot be split
+    let partial = RoyaltyDistribution {
+        recipients: vec![RoyaltyRecipient {
+            pubkey: alice.to_bytes(),
+            percentage: 5000,
+        }],
+    };
+    assert!(matches!(partial.split(100), Err(BlockTokError::InvalidRoyaltyPercentage)));
+
+    // Add admin, oracle and tipper accounts with initial balance
+    for account in [&admin, &oracle, &tipper] {
+        program_test.add_account(
+            account.pubkey(),
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![],
+                owner: system_program::id(),
+                ..Account::default()
+            },
+        );
+    }
+
+    // Add program data account naming the admin as upgrade authority
+    let (program_data_pubkey, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    program_test.add_account(program_data_pubkey, program_data_account(&admin.pubkey()));
+
+    // Someone funds alice's rewards address ahead of her first accrual
+    program_test.add_account(
+        UserRewards::find_address(&program_id, &alice).0,
+        Account {
+            lamports: 1_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    // Add pre-initialized content account
+    let mut content_data = vec![0; content.account_size()];
+    content.pack(&mut content_data).unwrap();
+    program_test.add_account(
+        content_pubkey,
+        Account {
+            lamports: 1_000_000,
+            data: content_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Add the tipper's token account and each payee's associated token account
+    program_test.add_account(
+        tipper_token_pubkey,
+        token_account(&reward_mint_pubkey, &tipper.pubkey()),
+    );
+    for payee in &payees {
+        program_test.add_account(
+            get_associated_token_address(payee, &reward_mint_pubkey),
+            token_account(&reward_mint_pubkey, payee),
+        );
+    }
+
+    // Start program test
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Give the tipper tokens to tip with
+    let instructions = [
+        BlockTokInstruction::initialize_config(&program_id, &admin.pubkey(), Pubkey::new_unique(), 250, 0),
+        BlockTokInstruction::set_analytics_oracles(&program_id, &admin.pubkey(), vec![oracle.pubkey()], false),
+        BlockTokInstruction::update_config(&program_id, &admin.pubkey(), None, None, None, None, None, None, Some(ample_emission_schedule())),
+        BlockTokInstruction::initialize_reward_mint(&program_id, &admin.pubkey()),
+        BlockTokInstruction::mint_rewards(&program_id, &oracle.pubkey(), &tipper_token_pubkey, 1000),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &admin, &oracle], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Only an oracle can credit earnings, and only to the payees in order
+    let reordered = [alice, creator.pubkey(), bob];
+    let rejected = [
+        (
+            &tipper,
+            BlockTokInstruction::accrue_creator_earnings(&program_id, &tipper.pubkey(), &content_pubkey, &payees, 100),
+        ),
+        (
+            &oracle,
+            BlockTokInstruction::accrue_creator_earnings(&program_id, &oracle.pubkey(), &content_pubkey, &reordered, 100),
+        ),
+        (
+            &oracle,
+            BlockTokInstruction::accrue_creator_earnings(&program_id, &oracle.pubkey(), &content_pubkey, &payees[..2], 100),
+        ),
+    ];
+    for (signer, instruction) in rejected {
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, signer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::accrue_creator_earnings(&program_id, &oracle.pubkey(), &content_pubkey, &payees, 100)],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &oracle], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify each payee was credited their share
+    for (payee, expected) in payees.iter().zip([50, 33, 17]) {
+        let (rewards_pubkey, _) = UserRewards::find_address(&program_id, payee);
+        let rewards_account = banks_client
+            .get_account(rewards_pubkey)
+            .await
+            .unwrap()
+            .unwrap();
+        let rewards = UserRewards::unpack(&rewards_account.data).unwrap();
+        assert_eq!(rewards.get_user(), *payee);
+        assert_eq!(rewards.accrued, expected);
+    }
+
+    // Tips are split the same way; the payee accounts must match too
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::tip_content(&program_id, &tipper.pubkey(), &tipper_token_pubkey, &content_pubkey, &reordered, 10)],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &tipper], recent_blockhash);
+    assert!(banks_client.process_transaction(transaction).await.is_err());
+
+    let mut transaction = Transaction::new_with_payer(
+        &[BlockTokInstruction::tip_content(&program_id, &tipper.pubkey(), &tipper_token_pubkey, &content_pubkey, &payees, 10)],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &tipper], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    for (payee, expected) in payees.iter().zip([5, 3, 2]) {
+        let token_account = banks_client
+            .get_account(get_associated_token_address(payee, &reward_mint_pubkey))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(TokenAccount::unpack(&token_account.data).unwrap().amount, expected);
+    }
+    let token_account = banks_client
+        .get_account(tipper_token_pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(TokenAccount::unpack(&token_account.data).unwrap().amount, 990);
+}